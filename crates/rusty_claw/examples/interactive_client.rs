@@ -55,7 +55,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(Message::Assistant(msg)) => {
                 // Print assistant's text responses
                 for block in msg.message.content {
-                    if let ContentBlock::Text { text } = block {
+                    if let ContentBlock::Text { text, .. } = block {
                         println!("Claude: {}", text);
                     }
                 }
@@ -85,7 +85,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match result {
             Ok(Message::Assistant(msg)) => {
                 for block in msg.message.content {
-                    if let ContentBlock::Text { text } = block {
+                    if let ContentBlock::Text { text, .. } = block {
                         println!("Claude: {}", text);
                     }
                 }
@@ -117,7 +117,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match result {
             Ok(Message::Assistant(msg)) => {
                 for block in msg.message.content {
-                    if let ContentBlock::Text { text } = block {
+                    if let ContentBlock::Text { text, .. } = block {
                         println!("Claude: {}", text);
                     }
                 }
@@ -48,7 +48,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 // Process each content block in the message
                 for block in msg.message.content {
                     match block {
-                        ContentBlock::Text { text } => {
+                        ContentBlock::Text { text, .. } => {
                             println!("Claude: {}", text);
                         }
                         ContentBlock::ToolUse { id, name, input } => {
@@ -68,6 +68,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         ContentBlock::Thinking { thinking, .. } => {
                             println!("Thinking: {}", thinking);
                         }
+                        other => {
+                            println!("Other block: {:?}", other.block_type());
+                        }
                     }
                 }
                 println!();
@@ -89,10 +92,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // Control messages (internal protocol - not normally seen in query())
             Ok(Message::ControlRequest { .. })
             | Ok(Message::ControlResponse { .. })
+            | Ok(Message::StreamEvent(_))
             | Ok(Message::RateLimitEvent(_))
             | Ok(Message::McpMessage(_)) => {
                 // Internal protocol messages - not normally seen in query()
             }
+            // Message types added by newer CLI releases
+            Ok(Message::Unknown { r#type, .. }) => {
+                println!("Unrecognized message type: {}", r#type);
+            }
             // Handle errors
             Err(e) => {
                 eprintln!("Error: {}", e);
@@ -0,0 +1,921 @@
+//! Typed models for the Claude Code built-in tools
+//!
+//! The CLI reports every tool call as a tool name plus a JSON `input` object:
+//! in [`ContentBlock::ToolUse`](crate::messages::ContentBlock::ToolUse), in
+//! [`HookInput`](crate::hooks::HookInput), and in permission requests.
+//! [`BuiltinToolCall::parse`] turns that pair into a typed input struct for the
+//! built-in tools, and [`BuiltinToolResult::parse`] does the same for the
+//! structured result the CLI attaches to tool results.
+//!
+//! Tools this module does not model (including MCP tools) parse as
+//! [`BuiltinToolCall::Other`] / [`BuiltinToolResult::Other`] with the raw JSON.
+//! Unknown input fields are ignored and unrecognized enum values (a new todo
+//! status, say) are kept in an `Other` variant, so newer CLI versions keep parsing.
+//!
+//! # Example
+//!
+//! ```
+//! use rusty_claw::builtin_tools::BuiltinToolCall;
+//! use serde_json::json;
+//!
+//! let call = BuiltinToolCall::parse("Bash", &json!({"command": "rm -rf /tmp/x"})).unwrap();
+//! if let BuiltinToolCall::Bash(bash) = &call {
+//!     assert!(bash.command.starts_with("rm "));
+//! }
+//!
+//! let call = BuiltinToolCall::parse("Edit", &json!({
+//!     "file_path": "/repo/src/main.rs",
+//!     "old_string": "foo",
+//!     "new_string": "bar"
+//! }))
+//! .unwrap();
+//! assert_eq!(call.file_path(), Some("/repo/src/main.rs"));
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::ClawError;
+
+/// Input of the `Bash` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BashInput {
+    /// Shell command to run
+    pub command: String,
+    /// Timeout in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Short description of what the command does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Run the command in the background
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_in_background: Option<bool>,
+    /// Run the command outside the sandbox
+    #[serde(
+        default,
+        rename = "dangerouslyDisableSandbox",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub dangerously_disable_sandbox: Option<bool>,
+}
+
+/// Input of the `Read` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadInput {
+    /// Absolute path of the file to read
+    pub file_path: String,
+    /// Line number to start reading from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    /// Maximum number of lines to read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+/// Input of the `Write` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WriteInput {
+    /// Absolute path of the file to write
+    pub file_path: String,
+    /// Full file content
+    pub content: String,
+}
+
+/// Input of the `Edit` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditInput {
+    /// Absolute path of the file to edit
+    pub file_path: String,
+    /// Text to replace
+    pub old_string: String,
+    /// Replacement text
+    pub new_string: String,
+    /// Replace every occurrence instead of a unique one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace_all: Option<bool>,
+}
+
+/// One replacement in a [`MultiEditInput`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditOperation {
+    /// Text to replace
+    pub old_string: String,
+    /// Replacement text
+    pub new_string: String,
+    /// Replace every occurrence instead of a unique one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace_all: Option<bool>,
+}
+
+/// Input of the `MultiEdit` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultiEditInput {
+    /// Absolute path of the file to edit
+    pub file_path: String,
+    /// Replacements, applied in order
+    pub edits: Vec<EditOperation>,
+}
+
+/// Input of the `Glob` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlobInput {
+    /// Glob pattern (e.g., `**/*.rs`)
+    pub pattern: String,
+    /// Directory to search in (defaults to the working directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// What the `Grep` tool returns
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrepOutputMode {
+    /// Matching lines
+    Content,
+    /// Paths of matching files
+    FilesWithMatches,
+    /// Match counts per file
+    Count,
+    /// Mode not recognized by this SDK version, with its wire name
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// Input of the `Grep` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrepInput {
+    /// Regular expression to search for
+    pub pattern: String,
+    /// File or directory to search in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Glob filter for file names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glob: Option<String>,
+    /// File type filter (e.g., "rust")
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub file_type: Option<String>,
+    /// Output mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mode: Option<GrepOutputMode>,
+    /// Case-insensitive search (`-i`)
+    #[serde(default, rename = "-i", skip_serializing_if = "Option::is_none")]
+    pub case_insensitive: Option<bool>,
+    /// Show line numbers (`-n`)
+    #[serde(default, rename = "-n", skip_serializing_if = "Option::is_none")]
+    pub line_numbers: Option<bool>,
+    /// Lines of context after each match (`-A`)
+    #[serde(default, rename = "-A", skip_serializing_if = "Option::is_none")]
+    pub after_context: Option<u32>,
+    /// Lines of context before each match (`-B`)
+    #[serde(default, rename = "-B", skip_serializing_if = "Option::is_none")]
+    pub before_context: Option<u32>,
+    /// Lines of context around each match (`-C`)
+    #[serde(default, rename = "-C", skip_serializing_if = "Option::is_none")]
+    pub context: Option<u32>,
+    /// Limit output to the first N entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_limit: Option<u32>,
+    /// Let patterns span lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiline: Option<bool>,
+}
+
+/// Input of the `WebFetch` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebFetchInput {
+    /// URL to fetch
+    pub url: String,
+    /// What to extract from the page
+    pub prompt: String,
+}
+
+/// Input of the `WebSearch` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebSearchInput {
+    /// Search query
+    pub query: String,
+    /// Only include results from these domains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_domains: Option<Vec<String>>,
+    /// Exclude results from these domains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_domains: Option<Vec<String>>,
+}
+
+/// Input of the `Task` tool (subagent launch)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskInput {
+    /// Short description of the task
+    pub description: String,
+    /// Prompt for the subagent
+    pub prompt: String,
+    /// Name of the subagent to run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subagent_type: Option<String>,
+}
+
+/// Status of a [`TodoItem`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoStatus {
+    /// Not started
+    Pending,
+    /// Being worked on
+    InProgress,
+    /// Done
+    Completed,
+    /// Status not recognized by this SDK version, with its wire name
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// One entry of the `TodoWrite` list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TodoItem {
+    /// What needs to be done
+    pub content: String,
+    /// Current status
+    pub status: TodoStatus,
+    /// Present-tense form shown while in progress
+    #[serde(
+        default,
+        rename = "activeForm",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub active_form: Option<String>,
+}
+
+/// Input of the `TodoWrite` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TodoWriteInput {
+    /// The full, updated todo list
+    pub todos: Vec<TodoItem>,
+}
+
+/// Kind of notebook cell
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotebookCellType {
+    /// Code cell
+    Code,
+    /// Markdown cell
+    Markdown,
+    /// Cell type not recognized by this SDK version, with its wire name
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// How `NotebookEdit` changes the notebook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotebookEditMode {
+    /// Replace the cell source
+    Replace,
+    /// Insert a new cell
+    Insert,
+    /// Delete the cell
+    Delete,
+    /// Mode not recognized by this SDK version, with its wire name
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// Input of the `NotebookEdit` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotebookEditInput {
+    /// Absolute path of the notebook
+    pub notebook_path: String,
+    /// New cell source
+    pub new_source: String,
+    /// ID of the cell to edit (or insert after)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell_id: Option<String>,
+    /// Cell type, required for inserts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell_type: Option<NotebookCellType>,
+    /// Edit mode (defaults to replace)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edit_mode: Option<NotebookEditMode>,
+}
+
+/// A built-in tool call with its typed input
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuiltinToolCall {
+    /// `Bash`
+    Bash(BashInput),
+    /// `Read`
+    Read(ReadInput),
+    /// `Write`
+    Write(WriteInput),
+    /// `Edit`
+    Edit(EditInput),
+    /// `MultiEdit`
+    MultiEdit(MultiEditInput),
+    /// `Glob`
+    Glob(GlobInput),
+    /// `Grep`
+    Grep(GrepInput),
+    /// `WebFetch`
+    WebFetch(WebFetchInput),
+    /// `WebSearch`
+    WebSearch(WebSearchInput),
+    /// `Task`
+    Task(TaskInput),
+    /// `TodoWrite`
+    TodoWrite(TodoWriteInput),
+    /// `NotebookEdit`
+    NotebookEdit(NotebookEditInput),
+    /// Any other tool, including MCP tools
+    Other {
+        /// Tool name
+        name: String,
+        /// Raw tool input
+        input: Value,
+    },
+}
+
+impl BuiltinToolCall {
+    /// Parse a tool call from its name and JSON input
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::MessageParse`] when `name` is a built-in tool but
+    /// `input` does not match its schema. Unknown tool names never fail.
+    pub fn parse(name: &str, input: &Value) -> Result<Self, ClawError> {
+        Ok(match name {
+            "Bash" => BuiltinToolCall::Bash(parse_as(name, input)?),
+            "Read" => BuiltinToolCall::Read(parse_as(name, input)?),
+            "Write" => BuiltinToolCall::Write(parse_as(name, input)?),
+            "Edit" => BuiltinToolCall::Edit(parse_as(name, input)?),
+            "MultiEdit" => BuiltinToolCall::MultiEdit(parse_as(name, input)?),
+            "Glob" => BuiltinToolCall::Glob(parse_as(name, input)?),
+            "Grep" => BuiltinToolCall::Grep(parse_as(name, input)?),
+            "WebFetch" => BuiltinToolCall::WebFetch(parse_as(name, input)?),
+            "WebSearch" => BuiltinToolCall::WebSearch(parse_as(name, input)?),
+            "Task" => BuiltinToolCall::Task(parse_as(name, input)?),
+            "TodoWrite" => BuiltinToolCall::TodoWrite(parse_as(name, input)?),
+            "NotebookEdit" => BuiltinToolCall::NotebookEdit(parse_as(name, input)?),
+            _ => BuiltinToolCall::Other {
+                name: name.to_string(),
+                input: input.clone(),
+            },
+        })
+    }
+
+    /// Tool name as the CLI reports it (e.g., `"Bash"`)
+    pub fn name(&self) -> &str {
+        match self {
+            BuiltinToolCall::Bash(_) => "Bash",
+            BuiltinToolCall::Read(_) => "Read",
+            BuiltinToolCall::Write(_) => "Write",
+            BuiltinToolCall::Edit(_) => "Edit",
+            BuiltinToolCall::MultiEdit(_) => "MultiEdit",
+            BuiltinToolCall::Glob(_) => "Glob",
+            BuiltinToolCall::Grep(_) => "Grep",
+            BuiltinToolCall::WebFetch(_) => "WebFetch",
+            BuiltinToolCall::WebSearch(_) => "WebSearch",
+            BuiltinToolCall::Task(_) => "Task",
+            BuiltinToolCall::TodoWrite(_) => "TodoWrite",
+            BuiltinToolCall::NotebookEdit(_) => "NotebookEdit",
+            BuiltinToolCall::Other { name, .. } => name,
+        }
+    }
+
+    /// File the call reads or modifies, for file-based tools
+    pub fn file_path(&self) -> Option<&str> {
+        match self {
+            BuiltinToolCall::Read(input) => Some(&input.file_path),
+            BuiltinToolCall::Write(input) => Some(&input.file_path),
+            BuiltinToolCall::Edit(input) => Some(&input.file_path),
+            BuiltinToolCall::MultiEdit(input) => Some(&input.file_path),
+            BuiltinToolCall::NotebookEdit(input) => Some(&input.notebook_path),
+            _ => None,
+        }
+    }
+
+    /// Whether the call edits a file through a file tool
+    ///
+    /// True for `Write`, `Edit`, `MultiEdit` and `NotebookEdit`. `Bash` and
+    /// MCP tools can modify files as well but are not counted, so do not use
+    /// this alone to decide whether a call is read-only.
+    pub fn is_file_edit(&self) -> bool {
+        matches!(
+            self,
+            BuiltinToolCall::Write(_)
+                | BuiltinToolCall::Edit(_)
+                | BuiltinToolCall::MultiEdit(_)
+                | BuiltinToolCall::NotebookEdit(_)
+        )
+    }
+}
+
+/// Output of the `Bash` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BashOutput {
+    /// Standard output
+    pub stdout: String,
+    /// Standard error
+    pub stderr: String,
+    /// Whether the command was interrupted
+    pub interrupted: bool,
+    /// Whether stdout holds image data
+    pub is_image: bool,
+}
+
+/// File contents in a [`ReadOutput`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReadFile {
+    /// Path of the file
+    pub file_path: String,
+    /// Text that was read
+    pub content: String,
+    /// Number of lines returned
+    pub num_lines: u64,
+    /// First line returned (1-based)
+    pub start_line: u64,
+    /// Total lines in the file
+    pub total_lines: u64,
+}
+
+/// Output of the `Read` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadOutput {
+    /// Kind of content read (e.g., "text", "image", "notebook")
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// File details for text reads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<ReadFile>,
+}
+
+/// Output of the `Write` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WriteOutput {
+    /// "create" for a new file, "update" for an overwrite
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Path of the file
+    pub file_path: String,
+    /// Content written
+    pub content: String,
+}
+
+/// Output of the `Edit` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EditOutput {
+    /// Path of the file
+    pub file_path: String,
+    /// Text that was replaced
+    pub old_string: String,
+    /// Replacement text
+    pub new_string: String,
+    /// Whether every occurrence was replaced
+    pub replace_all: bool,
+    /// Whether the user changed the proposed edit
+    pub user_modified: bool,
+}
+
+/// Output of the `MultiEdit` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MultiEditOutput {
+    /// Path of the file
+    pub file_path: String,
+    /// Replacements that were applied
+    pub edits: Vec<EditOperation>,
+    /// Whether the user changed the proposed edits
+    pub user_modified: bool,
+}
+
+/// Output of the `Glob` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GlobOutput {
+    /// Matching paths
+    pub filenames: Vec<String>,
+    /// Number of matches
+    pub num_files: u64,
+    /// Whether the list was truncated
+    pub truncated: bool,
+    /// Search time in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Output of the `Grep` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GrepOutput {
+    /// Output mode used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<GrepOutputMode>,
+    /// Matching files
+    pub filenames: Vec<String>,
+    /// Number of matching files
+    pub num_files: u64,
+    /// Matching lines, in `content` mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Number of lines returned, in `content` mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_lines: Option<u64>,
+    /// Total matches, in `count` mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_matches: Option<u64>,
+}
+
+/// Output of the `WebFetch` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WebFetchOutput {
+    /// URL that was fetched
+    pub url: String,
+    /// HTTP status code
+    pub code: u16,
+    /// HTTP status text
+    pub code_text: String,
+    /// Answer extracted from the page
+    pub result: String,
+    /// Size of the response in bytes
+    pub bytes: u64,
+    /// Fetch time in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Output of the `WebSearch` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WebSearchOutput {
+    /// Query that was run
+    pub query: String,
+    /// Search results as JSON
+    pub results: Vec<Value>,
+    /// Search time in seconds
+    pub duration_seconds: f64,
+}
+
+/// Output of the `Task` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TaskOutput {
+    /// Final subagent response content blocks
+    pub content: Vec<Value>,
+    /// Subagent run time in milliseconds
+    pub total_duration_ms: u64,
+    /// Tokens used by the subagent
+    pub total_tokens: u64,
+    /// Tool calls made by the subagent
+    pub total_tool_use_count: u64,
+}
+
+/// Output of the `TodoWrite` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TodoWriteOutput {
+    /// Todo list before the call
+    pub old_todos: Vec<TodoItem>,
+    /// Todo list after the call
+    pub new_todos: Vec<TodoItem>,
+}
+
+/// Output of the `NotebookEdit` tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotebookEditOutput {
+    /// New cell source
+    pub new_source: String,
+    /// ID of the edited cell
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell_id: Option<String>,
+    /// Cell type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell_type: Option<NotebookCellType>,
+    /// Edit mode used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit_mode: Option<NotebookEditMode>,
+    /// Error message, if the edit failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Structured result of a built-in tool call
+///
+/// Parsed from the `tool_use_result` of a [`UserMessage`](crate::messages::UserMessage)
+/// or the `tool_output` of a post-tool-use [`HookInput`](crate::hooks::HookInput).
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::builtin_tools::BuiltinToolResult;
+/// use serde_json::json;
+///
+/// let result = BuiltinToolResult::parse(
+///     "Bash",
+///     &json!({"stdout": "ok\n", "stderr": "", "interrupted": false}),
+/// )
+/// .unwrap();
+/// if let BuiltinToolResult::Bash(output) = result {
+///     assert_eq!(output.stdout, "ok\n");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuiltinToolResult {
+    /// `Bash`
+    Bash(BashOutput),
+    /// `Read`
+    Read(ReadOutput),
+    /// `Write`
+    Write(WriteOutput),
+    /// `Edit`
+    Edit(EditOutput),
+    /// `MultiEdit`
+    MultiEdit(MultiEditOutput),
+    /// `Glob`
+    Glob(GlobOutput),
+    /// `Grep`
+    Grep(GrepOutput),
+    /// `WebFetch`
+    WebFetch(WebFetchOutput),
+    /// `WebSearch`
+    WebSearch(WebSearchOutput),
+    /// `Task`
+    Task(TaskOutput),
+    /// `TodoWrite`
+    TodoWrite(TodoWriteOutput),
+    /// `NotebookEdit`
+    NotebookEdit(NotebookEditOutput),
+    /// Any other tool, or a result that is not a JSON object (e.g., an error string)
+    Other {
+        /// Tool name
+        name: String,
+        /// Raw tool output
+        output: Value,
+    },
+}
+
+impl BuiltinToolResult {
+    /// Parse a tool result from the tool name and its JSON output
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::MessageParse`] when `name` is a built-in tool and
+    /// `output` is an object that does not match its result shape.
+    pub fn parse(name: &str, output: &Value) -> Result<Self, ClawError> {
+        // Failed calls report a plain error string instead of the result object
+        if !output.is_object() {
+            return Ok(BuiltinToolResult::Other {
+                name: name.to_string(),
+                output: output.clone(),
+            });
+        }
+        Ok(match name {
+            "Bash" => BuiltinToolResult::Bash(parse_as(name, output)?),
+            "Read" => BuiltinToolResult::Read(parse_as(name, output)?),
+            "Write" => BuiltinToolResult::Write(parse_as(name, output)?),
+            "Edit" => BuiltinToolResult::Edit(parse_as(name, output)?),
+            "MultiEdit" => BuiltinToolResult::MultiEdit(parse_as(name, output)?),
+            "Glob" => BuiltinToolResult::Glob(parse_as(name, output)?),
+            "Grep" => BuiltinToolResult::Grep(parse_as(name, output)?),
+            "WebFetch" => BuiltinToolResult::WebFetch(parse_as(name, output)?),
+            "WebSearch" => BuiltinToolResult::WebSearch(parse_as(name, output)?),
+            "Task" => BuiltinToolResult::Task(parse_as(name, output)?),
+            "TodoWrite" => BuiltinToolResult::TodoWrite(parse_as(name, output)?),
+            "NotebookEdit" => BuiltinToolResult::NotebookEdit(parse_as(name, output)?),
+            _ => BuiltinToolResult::Other {
+                name: name.to_string(),
+                output: output.clone(),
+            },
+        })
+    }
+}
+
+/// Deserialize a tool payload, naming the tool in the error
+fn parse_as<T: DeserializeOwned>(name: &str, value: &Value) -> Result<T, ClawError> {
+    T::deserialize(value).map_err(|e| ClawError::MessageParse {
+        reason: format!("invalid {} tool payload: {}", name, e),
+        raw: value.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_bash() {
+        let call = BuiltinToolCall::parse(
+            "Bash",
+            &json!({
+                "command": "cargo test",
+                "timeout": 60000,
+                "description": "Run tests",
+                "dangerouslyDisableSandbox": true
+            }),
+        )
+        .unwrap();
+        match &call {
+            BuiltinToolCall::Bash(input) => {
+                assert_eq!(input.command, "cargo test");
+                assert_eq!(input.timeout, Some(60000));
+                assert_eq!(input.dangerously_disable_sandbox, Some(true));
+                assert_eq!(input.run_in_background, None);
+            }
+            other => panic!("Expected Bash, got {:?}", other),
+        }
+        assert_eq!(call.name(), "Bash");
+        assert_eq!(call.file_path(), None);
+        assert!(!call.is_file_edit());
+    }
+
+    #[test]
+    fn test_parse_file_tools() {
+        let read =
+            BuiltinToolCall::parse("Read", &json!({"file_path": "/a.rs", "limit": 10})).unwrap();
+        assert!(matches!(&read, BuiltinToolCall::Read(input) if input.limit == Some(10)));
+        assert_eq!(read.file_path(), Some("/a.rs"));
+
+        let multi = BuiltinToolCall::parse(
+            "MultiEdit",
+            &json!({
+                "file_path": "/b.rs",
+                "edits": [
+                    {"old_string": "a", "new_string": "b"},
+                    {"old_string": "c", "new_string": "d", "replace_all": true}
+                ]
+            }),
+        )
+        .unwrap();
+        match &multi {
+            BuiltinToolCall::MultiEdit(input) => {
+                assert_eq!(input.edits.len(), 2);
+                assert_eq!(input.edits[1].replace_all, Some(true));
+            }
+            other => panic!("Expected MultiEdit, got {:?}", other),
+        }
+        assert!(multi.is_file_edit());
+
+        let notebook = BuiltinToolCall::parse(
+            "NotebookEdit",
+            &json!({
+                "notebook_path": "/n.ipynb",
+                "new_source": "print(1)",
+                "cell_type": "code",
+                "edit_mode": "insert"
+            }),
+        )
+        .unwrap();
+        assert_eq!(notebook.file_path(), Some("/n.ipynb"));
+        assert!(matches!(
+            notebook,
+            BuiltinToolCall::NotebookEdit(NotebookEditInput {
+                cell_type: Some(NotebookCellType::Code),
+                edit_mode: Some(NotebookEditMode::Insert),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_grep_flags() {
+        let input = json!({
+            "pattern": "fn main",
+            "type": "rust",
+            "output_mode": "content",
+            "-i": true,
+            "-C": 2
+        });
+        let call = BuiltinToolCall::parse("Grep", &input).unwrap();
+        match &call {
+            BuiltinToolCall::Grep(grep) => {
+                assert_eq!(grep.file_type.as_deref(), Some("rust"));
+                assert_eq!(grep.output_mode, Some(GrepOutputMode::Content));
+                assert_eq!(grep.case_insensitive, Some(true));
+                assert_eq!(grep.context, Some(2));
+                assert_eq!(serde_json::to_value(grep).unwrap(), input);
+            }
+            other => panic!("Expected Grep, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_todo_write_and_task() {
+        let call = BuiltinToolCall::parse(
+            "TodoWrite",
+            &json!({"todos": [
+                {"content": "Write tests", "status": "in_progress", "activeForm": "Writing tests"}
+            ]}),
+        )
+        .unwrap();
+        match call {
+            BuiltinToolCall::TodoWrite(input) => {
+                assert_eq!(input.todos[0].status, TodoStatus::InProgress);
+                assert_eq!(input.todos[0].active_form.as_deref(), Some("Writing tests"));
+            }
+            other => panic!("Expected TodoWrite, got {:?}", other),
+        }
+
+        // Values added by newer CLI versions keep their wire name
+        let input = json!({"todos": [{"content": "Drop it", "status": "cancelled"}]});
+        match BuiltinToolCall::parse("TodoWrite", &input).unwrap() {
+            BuiltinToolCall::TodoWrite(todos) => {
+                assert_eq!(
+                    todos.todos[0].status,
+                    TodoStatus::Other("cancelled".to_string())
+                );
+                assert_eq!(serde_json::to_value(&todos).unwrap(), input);
+            }
+            other => panic!("Expected TodoWrite, got {:?}", other),
+        }
+
+        let call = BuiltinToolCall::parse(
+            "Task",
+            &json!({"description": "Review", "prompt": "Review the diff", "subagent_type": "reviewer"}),
+        )
+        .unwrap();
+        assert!(
+            matches!(call, BuiltinToolCall::Task(task) if task.subagent_type.as_deref() == Some("reviewer"))
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_tool_is_other() {
+        let input = json!({"query": "SELECT 1"});
+        let call = BuiltinToolCall::parse("mcp__db__query", &input).unwrap();
+        assert_eq!(
+            call,
+            BuiltinToolCall::Other {
+                name: "mcp__db__query".to_string(),
+                input,
+            }
+        );
+        assert_eq!(call.name(), "mcp__db__query");
+    }
+
+    #[test]
+    fn test_parse_invalid_builtin_input() {
+        let err = BuiltinToolCall::parse("Bash", &json!({"cmd": "ls"})).unwrap_err();
+        match err {
+            ClawError::MessageParse { reason, raw } => {
+                assert!(reason.contains("Bash"));
+                assert!(raw.contains("cmd"));
+            }
+            other => panic!("Expected MessageParse, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_results() {
+        let result = BuiltinToolResult::parse(
+            "Glob",
+            &json!({"filenames": ["/a.rs", "/b.rs"], "numFiles": 2, "truncated": false, "durationMs": 4}),
+        )
+        .unwrap();
+        assert!(matches!(result, BuiltinToolResult::Glob(out) if out.num_files == 2));
+
+        let result = BuiltinToolResult::parse(
+            "Read",
+            &json!({
+                "type": "text",
+                "file": {"filePath": "/a.rs", "content": "fn main() {}", "numLines": 1, "startLine": 1, "totalLines": 1}
+            }),
+        )
+        .unwrap();
+        match result {
+            BuiltinToolResult::Read(out) => {
+                assert_eq!(out.kind.as_deref(), Some("text"));
+                assert_eq!(out.file.unwrap().content, "fn main() {}");
+            }
+            other => panic!("Expected Read, got {:?}", other),
+        }
+
+        let result = BuiltinToolResult::parse(
+            "TodoWrite",
+            &json!({"oldTodos": [], "newTodos": [{"content": "x", "status": "completed"}]}),
+        )
+        .unwrap();
+        assert!(
+            matches!(result, BuiltinToolResult::TodoWrite(out) if out.new_todos[0].status == TodoStatus::Completed)
+        );
+    }
+
+    #[test]
+    fn test_parse_error_string_result_is_other() {
+        let output = json!("Error: file not found");
+        let result = BuiltinToolResult::parse("Read", &output).unwrap();
+        assert_eq!(
+            result,
+            BuiltinToolResult::Other {
+                name: "Read".to_string(),
+                output,
+            }
+        );
+    }
+}
@@ -8,6 +8,9 @@
 //! - **Session control** - Interrupt execution, change models, modify permission modes
 //! - **Handler registration** - Install callbacks for tool permission checks, hooks, and MCP
 //! - **Full control protocol access** - All control operations supported by the CLI
+//! - **Session-wide subscriptions** - Observe every message of the session via `subscribe()`
+//! - **Session state** - A live snapshot of session ID, model, tools, usage, and cost via `session()`
+//! - **Crash recovery** - Optionally respawn a dead CLI and resume the session (see [`RecoveryPolicy`](crate::options::RecoveryPolicy))
 //!
 //! # Architecture
 //!
@@ -21,6 +24,13 @@
 //! │  • receive_response()        • set_model()              │
 //! │  • close()                   • mcp_status()             │
 //! │  • get_server_info()         • rewind_files()           │
+//! │  • capabilities()            • add_mcp_server()         │
+//! │                              • add_sdk_mcp_server()     │
+//! │                              • remove_mcp_server()      │
+//! │                              • reconnect_mcp_server()   │
+//! │                              • set_mcp_server_enabled() │
+//! │  • subscribe()                                           │
+//! │  • session()                                             │
 //! │                                                          │
 //! │  ┌────────────────────────────────────────────────────┐ │
 //! │  │        ControlProtocol (request/response)         │ │
@@ -57,7 +67,7 @@
 //!         match result {
 //!             Ok(Message::Assistant(msg)) => {
 //!                 for block in msg.message.content {
-//!                     if let ContentBlock::Text { text } = block {
+//!                     if let ContentBlock::Text { text, .. } = block {
 //!                         println!("Claude: {}", text);
 //!                     }
 //!                 }
@@ -74,7 +84,7 @@
 //!         match result {
 //!             Ok(Message::Assistant(msg)) => {
 //!                 for block in msg.message.content {
-//!                     if let ContentBlock::Text { text } = block {
+//!                     if let ContentBlock::Text { text, .. } = block {
 //!                         println!("Claude: {}", text);
 //!                     }
 //!                 }
@@ -115,6 +125,31 @@
 //! }
 //! ```
 //!
+//! # Example: Session-wide Subscription
+//!
+//! ```no_run
+//! use rusty_claw::prelude::*;
+//! use tokio_stream::StreamExt;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut client = ClaudeClient::new(ClaudeAgentOptions::default())?;
+//!
+//!     // Subscribers see every message of the session, across all turns
+//!     let mut events = client.subscribe();
+//!     tokio::spawn(async move {
+//!         while let Some(Ok(msg)) = events.next().await {
+//!             println!("[log] {:?}", msg);
+//!         }
+//!     });
+//!
+//!     client.connect().await?;
+//!     let messages = client.send_message("Hello").await?.receive_response().await?;
+//!     client.close().await?;
+//!     Ok(())
+//! }
+//! ```
+//!
 //! # Example: Control Operations
 //!
 //! ```no_run
@@ -175,21 +210,39 @@
 //! ```
 
 use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::task::{Context, Poll};
 use tokio::sync::Mutex;
-use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{broadcast, watch};
 use tokio_stream::Stream;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 
-use crate::control::ControlProtocol;
 use crate::control::handlers::{CanUseToolHandler, HookHandler, McpMessageHandler};
+use crate::control::messages::{
+    InitializeResponse, McpSetServersResponse, McpStatusResponse, RuntimeMcpServer,
+    SdkMcpServerRef, ServerInfo,
+};
+use crate::control::{CancellationToken, ControlProtocol, DEFAULT_MAX_CONCURRENT_REQUESTS};
 use crate::error::ClawError;
-use crate::messages::Message;
-use crate::options::{ClaudeAgentOptions, PermissionMode};
-use crate::transport::Transport;
+use crate::mcp_server::SdkMcpServerImpl;
+use crate::messages::{
+    McpServerInfo, Message, RateLimitEvent, RateLimitType, ResultMessage, SystemMessage, ToolInfo,
+    UsageInfo, UserContentBlock, UserInput,
+};
+use crate::options::{
+    ChannelConfig, ClaudeAgentOptions, McpServerConfig, OverflowPolicy, PermissionMode,
+    RateLimitPolicy,
+};
+use crate::transport::{
+    MessageReceiver, MessageSender, SubprocessCLITransport, Transport, is_partial_message,
+    message_channel,
+};
 
 /// Shared slot for the current-turn message sender.
 ///
@@ -197,7 +250,286 @@ use crate::transport::Transport;
 /// called, it creates a new `(tx, rx)` pair and stores `tx` here. The router then
 /// forwards all non-control messages to that sender until the next `send_message()` call
 /// installs a new sender.
-type CurrentTurnSender = Arc<Mutex<Option<mpsc::UnboundedSender<Result<Value, ClawError>>>>>;
+type CurrentTurnSender = Arc<Mutex<Option<TurnSender>>>;
+
+/// Shared slot for the session-wide broadcast sender.
+///
+/// Created with the client so `subscribe()` works before `connect()`. The slot is
+/// emptied when the message router finishes or the client is closed, which drops
+/// the sender and ends every `SessionStream`.
+type SessionSender = Arc<std::sync::Mutex<Option<broadcast::Sender<Message>>>>;
+
+/// Shared session snapshot, kept up to date by the message router.
+type SessionStateSlot = Arc<std::sync::Mutex<SessionState>>;
+
+/// MCP servers added during the session, re-sent to the CLI after crash recovery.
+type RuntimeMcpServers = Arc<std::sync::Mutex<RuntimeMcpRegistry>>;
+
+/// Runtime MCP servers keyed by name, with a count of committed updates
+///
+/// The lock is never held across an `mcp_set_servers` request; an update
+/// built from a stale snapshot sees a newer `version` and is redone.
+#[derive(Debug, Default)]
+struct RuntimeMcpRegistry {
+    servers: HashMap<String, RuntimeMcpServer>,
+    version: u64,
+}
+
+/// State shared between the client and its background message router
+#[derive(Clone)]
+struct RouterState {
+    control: Arc<ControlProtocol>,
+    current_turn_tx: CurrentTurnSender,
+    session_tx: SessionSender,
+    session: SessionStateSlot,
+    is_initialized: Arc<AtomicBool>,
+    closed: Arc<AtomicBool>,
+    /// Capacity and overflow policy of per-turn channels
+    channel: ChannelConfig,
+    /// Options used to respawn the CLI after a crash; `None` disables recovery
+    respawn_options: Option<ClaudeAgentOptions>,
+    /// MCP servers to restore on a respawned CLI
+    runtime_mcp_servers: RuntimeMcpServers,
+}
+
+/// Create an open session broadcast slot buffering `capacity` messages per subscriber
+fn new_session_sender(capacity: usize) -> SessionSender {
+    let (tx, _) = broadcast::channel(capacity.max(1));
+    Arc::new(std::sync::Mutex::new(Some(tx)))
+}
+
+/// Broadcast a message to session subscribers, if there are any
+fn broadcast_message(session_tx: &SessionSender, message: Message) {
+    let sender = session_tx.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(tx) = sender {
+        // Send only fails when there are no subscribers
+        let _ = tx.send(message);
+    }
+}
+
+/// Sending half of a turn's [`ResponseStream`]
+#[derive(Clone)]
+struct TurnSender {
+    tx: MessageSender,
+    /// Error that ended the turn while its channel was full
+    end: TurnEnd,
+}
+
+/// Shared slot for the error that ended a turn, yielded by the
+/// [`ResponseStream`] after the messages buffered before it
+type TurnEnd = Arc<std::sync::Mutex<Option<ClawError>>>;
+
+/// Create a bounded per-turn channel sized by `config`
+fn turn_channel(config: &ChannelConfig) -> (TurnSender, ResponseStream) {
+    let (tx, rx) = message_channel(config);
+    let end = TurnEnd::default();
+    let stream = ResponseStream {
+        rx,
+        end: end.clone(),
+        is_complete: false,
+    };
+    (TurnSender { tx, end }, stream)
+}
+
+/// Delivers routed messages to the current turn; owned by the message router
+///
+/// Under [`OverflowPolicy::Block`] a full turn holds the router until the
+/// consumer reads, so the transport channel fills and the stdout reader stops
+/// reading, which applies backpressure to the CLI. While an SDK control
+/// request is waiting for its response (an `interrupt()`, say), the router
+/// reads ahead instead, parking turn messages until the turn has room, so the
+/// response is not stuck behind a stream its caller is not reading.
+struct TurnForwarder {
+    current_turn_tx: CurrentTurnSender,
+    channel: ChannelConfig,
+    /// Messages waiting for room in their (full) turn, in arrival order
+    parked: VecDeque<(TurnSender, Result<Value, ClawError>)>,
+    /// Number of SDK control requests waiting for a response
+    awaiting: watch::Receiver<usize>,
+}
+
+impl TurnForwarder {
+    fn new(state: &RouterState) -> Self {
+        Self {
+            current_turn_tx: state.current_turn_tx.clone(),
+            channel: state.channel,
+            parked: VecDeque::new(),
+            awaiting: state.control.awaiting_responses(),
+        }
+    }
+
+    /// Read the next message from `rx`, delivering parked messages first
+    ///
+    /// Returns `None` once `rx` is closed.
+    async fn next(&mut self, rx: &mut MessageReceiver) -> Option<Result<Value, ClawError>> {
+        loop {
+            let Some((turn, _)) = self.parked.front() else {
+                return rx.recv().await;
+            };
+            let tx = turn.tx.clone();
+            let read_ahead = *self.awaiting.borrow_and_update() > 0;
+            tokio::select! {
+                permit = tx.reserve() => {
+                    let (_, item) = self.parked.pop_front().expect("parked is not empty");
+                    match permit {
+                        Ok(permit) => permit.send(item),
+                        Err(_) => self.discard(&tx).await,
+                    }
+                }
+                msg = rx.recv(), if read_ahead => return msg,
+                _ = self.awaiting.changed() => {}
+            }
+        }
+    }
+
+    /// Deliver a message or error to the current turn, if one is active
+    async fn forward(&mut self, item: Result<Value, ClawError>) {
+        // Lock briefly to read the sender, then release before delivering
+        let Some(turn) = self.current_turn_tx.lock().await.clone() else {
+            return;
+        };
+        // Stay behind the messages already parked for this turn
+        let sent = if self.is_parked(&turn) {
+            Err(TrySendError::Full(item))
+        } else {
+            turn.tx.try_send(item)
+        };
+        match sent {
+            Ok(()) => {}
+            Err(TrySendError::Full(item)) => match self.channel.overflow {
+                OverflowPolicy::DropPartial if matches!(&item, Ok(v) if is_partial_message(v)) => {
+                    tracing::debug!("Turn channel full, dropped partial message");
+                }
+                OverflowPolicy::Block | OverflowPolicy::DropPartial => {
+                    self.parked.push_back((turn, item));
+                }
+                OverflowPolicy::Error => {
+                    // Stop delivering to this turn; later messages are discarded
+                    tracing::warn!(capacity = self.channel.capacity, "Turn channel overflowed");
+                    clear_turn(&self.current_turn_tx, &turn.tx).await;
+                    let capacity = self.channel.capacity;
+                    self.end(turn, ClawError::ChannelOverflow { capacity });
+                }
+            },
+            Err(TrySendError::Closed(_)) => {
+                // Receiver was dropped (caller discarded the stream). Clear the slot so
+                // future sends are no-ops until send_message() installs a new one.
+                self.discard(&turn.tx).await;
+            }
+        }
+    }
+
+    /// End the current turn, if one is active, with `error`
+    async fn end_current(&self, error: ClawError) {
+        let turn = self.current_turn_tx.lock().await.take();
+        if let Some(turn) = turn {
+            self.end(turn, error);
+        }
+    }
+
+    /// Queue `error` as the last item of `turn` without waiting
+    ///
+    /// When the channel is full the error goes to the turn's end slot, which
+    /// the stream reports once it has yielded everything queued before it.
+    fn end(&self, turn: TurnSender, error: ClawError) {
+        let sent = if self.is_parked(&turn) {
+            Err(TrySendError::Full(Err(error)))
+        } else {
+            turn.tx.try_send(Err(error))
+        };
+        if let Err(TrySendError::Full(Err(error))) = sent {
+            turn.end
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get_or_insert(error);
+        }
+    }
+
+    /// Deliver the remaining parked messages once the transport is gone
+    ///
+    /// Waits for each turn's consumer; a dropped stream discards the rest of
+    /// its messages.
+    async fn flush(&mut self) {
+        while let Some((turn, item)) = self.parked.pop_front() {
+            if turn.tx.send(item).await.is_err() {
+                self.parked
+                    .retain(|(parked, _)| !parked.tx.same_channel(&turn.tx));
+            }
+        }
+    }
+
+    fn is_parked(&self, turn: &TurnSender) -> bool {
+        self.parked
+            .iter()
+            .any(|(parked, _)| parked.tx.same_channel(&turn.tx))
+    }
+
+    /// Forget a turn whose stream was dropped
+    async fn discard(&mut self, tx: &MessageSender) {
+        self.parked
+            .retain(|(parked, _)| !parked.tx.same_channel(tx));
+        clear_turn(&self.current_turn_tx, tx).await;
+    }
+}
+
+/// Empty the current-turn slot if it still holds `tx`
+async fn clear_turn(current_turn_tx: &CurrentTurnSender, tx: &MessageSender) {
+    let mut slot = current_turn_tx.lock().await;
+    if slot
+        .as_ref()
+        .is_some_and(|current| current.tx.same_channel(tx))
+    {
+        *slot = None;
+    }
+}
+
+/// Options for respawning a crashed CLI so it resumes `session`
+///
+/// The model and permission mode come from the session state, so changes made
+/// with `set_model()` and `set_permission_mode()` survive the respawn. Returns
+/// `None` if the session ID is unknown.
+fn resume_options(base: &ClaudeAgentOptions, session: &SessionState) -> Option<ClaudeAgentOptions> {
+    let mut options = base.clone();
+    options.resume = Some(session.session_id.clone()?);
+    options.continue_conversation = false;
+    options.fork_session = false;
+    if let Some(model) = &session.model {
+        options.model = Some(model.clone());
+    }
+    if let Some(mode) = &session.permission_mode {
+        options.permission_mode = Some(mode.clone());
+    }
+    Some(options)
+}
+
+/// Build the subprocess transport for an interactive session
+fn subprocess_transport(options: &ClaudeAgentOptions) -> SubprocessCLITransport {
+    // Build CLI args for interactive mode using the shared base arg builder.
+    // to_base_cli_args() produces all flags except "-p <prompt>", which is
+    // not used in interactive mode — prompts arrive via send_message().
+    let mut cli_args = options.to_base_cli_args();
+
+    // Enable control protocol input (interactive mode only — one-shot query
+    // path uses -p instead and never enters this branch).
+    cli_args.push("--input-format".to_string());
+    cli_args.push("stream-json".to_string());
+
+    // Create transport
+    let mut t = SubprocessCLITransport::new(options.cli_path.clone(), cli_args);
+    t.set_channel_config(options.channel);
+
+    // Apply working directory if configured
+    if let Some(cwd) = &options.cwd {
+        t.set_cwd(cwd.clone());
+    }
+
+    // Apply environment variables if configured
+    if !options.env.is_empty() {
+        t.set_env(options.env.clone());
+    }
+
+    t
+}
 
 /// Client for interactive sessions with Claude CLI
 ///
@@ -254,12 +586,10 @@ type CurrentTurnSender = Arc<Mutex<Option<mpsc::UnboundedSender<Result<Value, Cl
 /// # }
 /// ```
 pub struct ClaudeClient {
-    /// Control protocol for request/response handling
+    /// Control protocol for request/response handling. Owns the transport, which
+    /// is replaced in place when crash recovery respawns the CLI.
     control: Option<Arc<ControlProtocol>>,
 
-    /// Transport layer (stored as Option to allow taking ownership in connect)
-    transport: Option<Arc<dyn Transport>>,
-
     /// Session configuration
     options: ClaudeAgentOptions,
 
@@ -267,9 +597,18 @@ pub struct ClaudeClient {
     /// The background router reads from this slot and forwards messages to it.
     current_turn_tx: CurrentTurnSender,
 
+    /// Broadcast sender for session-wide subscribers (see `subscribe()`)
+    session_tx: SessionSender,
+
+    /// Live session snapshot (see `session()`); its session ID is used to resume after a crash
+    session: SessionStateSlot,
+
     /// Session initialization state
     is_initialized: Arc<AtomicBool>,
 
+    /// Set by `close()` so the router and crash recovery stop
+    closed: Arc<AtomicBool>,
+
     /// Pre-injected transport for dependency injection (set via `with_transport()`).
     ///
     /// When `Some`, `connect()` uses this transport instead of spawning a CLI subprocess.
@@ -280,6 +619,12 @@ pub struct ClaudeClient {
     /// Wrapped in Mutex to allow registration via &self (matching the signature of
     /// register_can_use_tool_handler and register_hook).
     pending_mcp_handler: std::sync::Mutex<Option<Arc<dyn McpMessageHandler>>>,
+
+    /// MCP servers added with `add_mcp_server()` / `add_sdk_mcp_server()`
+    runtime_mcp_servers: RuntimeMcpServers,
+
+    /// Background message router of the current connection, stopped by `close()`
+    router: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl ClaudeClient {
@@ -306,12 +651,16 @@ impl ClaudeClient {
     pub fn new(options: ClaudeAgentOptions) -> Result<Self, ClawError> {
         Ok(Self {
             control: None,
-            transport: None,
             pre_transport: None,
+            session: Arc::new(std::sync::Mutex::new(SessionState::new(&options))),
+            session_tx: new_session_sender(options.channel.capacity),
             options,
             current_turn_tx: Arc::new(Mutex::new(None)),
             is_initialized: Arc::new(AtomicBool::new(false)),
+            closed: Arc::new(AtomicBool::new(false)),
             pending_mcp_handler: std::sync::Mutex::new(None),
+            runtime_mcp_servers: Arc::default(),
+            router: None,
         })
     }
 
@@ -347,12 +696,16 @@ impl ClaudeClient {
     ) -> Result<Self, ClawError> {
         Ok(Self {
             control: None,
-            transport: None,
             pre_transport: Some(transport),
+            session: Arc::new(std::sync::Mutex::new(SessionState::new(&options))),
+            session_tx: new_session_sender(options.channel.capacity),
             options,
             current_turn_tx: Arc::new(Mutex::new(None)),
             is_initialized: Arc::new(AtomicBool::new(false)),
+            closed: Arc::new(AtomicBool::new(false)),
             pending_mcp_handler: std::sync::Mutex::new(None),
+            runtime_mcp_servers: Arc::default(),
+            router: None,
         })
     }
 
@@ -376,9 +729,9 @@ impl ClaudeClient {
     /// # }
     /// ```
     pub fn is_connected(&self) -> bool {
-        self.transport
+        self.control
             .as_ref()
-            .map(|t| t.is_ready())
+            .map(|c| c.transport().is_ready())
             .unwrap_or(false)
             && self.is_initialized.load(Ordering::SeqCst)
     }
@@ -413,39 +766,31 @@ impl ClaudeClient {
     /// # }
     /// ```
     pub async fn connect(&mut self) -> Result<(), ClawError> {
-        use crate::transport::SubprocessCLITransport;
+        // Use pre-injected transport if available; otherwise build a SubprocessCLITransport.
+        // Only transports the client spawned itself can be respawned by crash recovery.
+        let (mut transport, respawn_options): (Box<dyn Transport>, _) =
+            if let Some(pre) = self.pre_transport.take() {
+                (pre, None)
+            } else {
+                let respawn = self
+                    .options
+                    .recovery
+                    .is_some()
+                    .then(|| self.options.clone());
+                (Box::new(subprocess_transport(&self.options)), respawn)
+            };
 
-        // Use pre-injected transport if available; otherwise build a SubprocessCLITransport
-        let mut transport: Box<dyn Transport> = if let Some(pre) = self.pre_transport.take() {
-            pre
-        } else {
-            // Build CLI args for interactive mode using the shared base arg builder.
-            // to_base_cli_args() produces all flags except "-p <prompt>", which is
-            // not used in interactive mode — prompts arrive via send_message().
-            let mut cli_args = self.options.to_base_cli_args();
-
-            // Enable control protocol input (interactive mode only — one-shot query
-            // path uses -p instead and never enters this branch).
-            cli_args.push("--input-format".to_string());
-            cli_args.push("stream-json".to_string());
-
-            // Create transport
-            let mut t = SubprocessCLITransport::new(self.options.cli_path.clone(), cli_args);
-
-            // Apply working directory if configured
-            if let Some(cwd) = &self.options.cwd {
-                t.set_cwd(cwd.clone());
-            }
+        transport.connect().await?;
 
-            // Apply environment variables if configured
-            if !self.options.env.is_empty() {
-                t.set_env(self.options.env.clone());
+        // A previous close() stopped recovery and ended the session broadcast
+        self.closed.store(false, Ordering::SeqCst);
+        {
+            let mut session_tx = self.session_tx.lock().unwrap_or_else(|e| e.into_inner());
+            if session_tx.is_none() {
+                let (tx, _) = broadcast::channel(self.options.channel.capacity.max(1));
+                *session_tx = Some(tx);
             }
-
-            Box::new(t) as Box<dyn Transport>
-        };
-
-        transport.connect().await?;
+        }
 
         // Get message receiver before wrapping in Arc
         let message_rx = transport.messages();
@@ -454,14 +799,25 @@ impl ClaudeClient {
         let transport_arc: Arc<dyn Transport> = Arc::from(transport as Box<dyn Transport>);
 
         // Create control protocol
-        let control = Arc::new(ControlProtocol::new(transport_arc.clone()));
+        let control = Arc::new(
+            ControlProtocol::new(transport_arc)
+                .with_timeouts(self.options.control_timeouts.clone())
+                .with_max_concurrent_requests(
+                    self.options
+                        .max_concurrent_control_requests
+                        .unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS),
+                ),
+        );
 
         // Spawn background message routing task BEFORE initialize().
         // This is critical: initialize() sends a control request and waits for
         // a response. The response arrives via the transport's message channel,
         // so we need a reader routing control messages to the ControlProtocol.
         // Without this, initialize() would always timeout.
-        Self::spawn_message_router(message_rx, control.clone(), self.current_turn_tx.clone());
+        self.router = Some(Self::spawn_message_router(
+            message_rx,
+            self.router_state(control.clone(), respawn_options),
+        ));
 
         // Apply pending MCP handler BEFORE initialize.
         // The CLI sends mcp_message requests during init, so the handler
@@ -491,7 +847,6 @@ impl ClaudeClient {
         control.initialize(&self.options).await?;
 
         // Store state
-        self.transport = Some(transport_arc);
         self.control = Some(control);
         self.is_initialized.store(true, Ordering::SeqCst);
 
@@ -501,12 +856,14 @@ impl ClaudeClient {
     /// Close the session gracefully
     ///
     /// This method:
-    /// 1. Ends input to the CLI (signals no more messages)
-    /// 2. Waits for the CLI subprocess to exit
-    /// 3. Cleans up internal state
+    /// 1. Cancels in-flight control request handlers (permission prompts,
+    ///    hooks, MCP calls) and fails pending outgoing requests
+    /// 2. Ends input to the CLI (signals no more messages)
+    /// 3. Waits for the CLI subprocess to exit
+    /// 4. Cleans up internal state
     ///
-    /// After calling `close()`, the client can no longer send messages. A new
-    /// client must be created for a new session.
+    /// After calling `close()`, the client can no longer send messages until
+    /// [`connect()`](Self::connect) is called again.
     ///
     /// # Errors
     ///
@@ -526,17 +883,36 @@ impl ClaudeClient {
     /// # }
     /// ```
     pub async fn close(&mut self) -> Result<(), ClawError> {
-        if let Some(transport) = &self.transport {
+        // Mark the session closed first so the router does not treat the
+        // CLI exit as a crash and try to recover
+        self.closed.store(true, Ordering::SeqCst);
+        self.is_initialized.store(false, Ordering::SeqCst);
+
+        if let Some(control) = &self.control {
+            // Nobody is left to receive handler answers
+            control.abandon_all().await;
+
             // Graceful shutdown: close stdin, wait, then signal if needed
-            transport.close().await?;
+            control.transport().close().await?;
+        }
+
+        // Stop the router (and any recovery in progress) so it cannot touch
+        // the slots of a later connect()
+        if let Some(router) = self.router.take() {
+            router.abort();
+            let _ = router.await;
         }
 
         // Drop the current turn sender to unblock any waiting ResponseStream
         *self.current_turn_tx.lock().await = None;
 
+        // Drop the session sender to end every SessionStream
+        self.session_tx
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+
         // Clear state
-        self.is_initialized.store(false, Ordering::SeqCst);
-        self.transport = None;
         self.control = None;
 
         Ok(())
@@ -601,6 +977,8 @@ impl ClaudeClient {
     /// # Errors
     ///
     /// - `ClawError::Connection` - Not connected (call `connect()` first)
+    /// - `ClawError::RateLimited` - A rate limit is in effect and the
+    ///   [`RateLimitPolicy`] does not wait for it
     /// - `ClawError::Io` - Failed to write message to CLI
     ///
     /// # Example
@@ -639,6 +1017,189 @@ impl ClaudeClient {
         &self,
         content: impl Into<String>,
     ) -> Result<ResponseStream, ClawError> {
+        self.send_turn(UserInput::text(content)).await
+    }
+
+    /// Send a multimodal message to Claude and get a stream of responses for this turn
+    ///
+    /// Works like [`send_message()`](Self::send_message) but sends a list of
+    /// content blocks, so a single turn can mix text, images (e.g., screenshots),
+    /// and documents (e.g., PDFs).
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content blocks to send to Claude
+    ///
+    /// # Errors
+    ///
+    /// - `ClawError::Connection` - Not connected (call `connect()` first)
+    /// - `ClawError::RateLimited` - A rate limit is in effect and the
+    ///   [`RateLimitPolicy`] does not wait for it
+    /// - `ClawError::Io` - Failed to write message to CLI
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// # let mut client = ClaudeClient::new(options)?;
+    /// # client.connect().await?;
+    /// let stream = client
+    ///     .send_content(vec![
+    ///         UserContentBlock::text("Does this page match the design?"),
+    ///         UserContentBlock::image_file("screenshots/home.png")?,
+    ///     ])
+    ///     .await?;
+    /// let messages = stream.receive_response().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_content(
+        &self,
+        content: Vec<UserContentBlock>,
+    ) -> Result<ResponseStream, ClawError> {
+        self.send_turn(UserInput::blocks(content)).await
+    }
+
+    /// Send a fully specified user message and get a stream of responses for this turn
+    ///
+    /// Use this when the message needs fields that [`send_message()`](Self::send_message)
+    /// and [`send_content()`](Self::send_content) leave unset, such as a
+    /// `parent_tool_use_id` or a `uuid`. An empty `session_id` is filled in with the
+    /// current session ID.
+    ///
+    /// # Errors
+    ///
+    /// - `ClawError::Connection` - Not connected (call `connect()` first)
+    /// - `ClawError::RateLimited` - A rate limit is in effect and the
+    ///   [`RateLimitPolicy`] does not wait for it
+    /// - `ClawError::Io` - Failed to write message to CLI
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// # let mut client = ClaudeClient::new(options)?;
+    /// # client.connect().await?;
+    /// let input = UserInput::text("Continue with the migration").with_uuid("7f3c9a2e-msg");
+    /// let messages = client.send_input(input).await?.receive_response().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_input(&self, input: UserInput) -> Result<ResponseStream, ClawError> {
+        self.send_turn(input).await
+    }
+
+    /// Subscribe to every message of the session
+    ///
+    /// Returns a [`SessionStream`] that yields each non-control `Message` the
+    /// CLI sends for the whole session: system messages, rate limit events,
+    /// and messages arriving between turns or after a `Result`. Any number of
+    /// subscribers can run alongside the per-turn [`ResponseStream`], so
+    /// logging, UI, and persistence can consume the same session independently.
+    ///
+    /// Subscribing before [`connect()`](Self::connect) is supported and sees
+    /// the session from its first message. The stream ends when the client is
+    /// closed or the CLI exits. A subscriber that falls more than
+    /// [`ChannelConfig::capacity`] messages behind receives [`ClawError::SubscriberLagged`] and then
+    /// continues from the oldest buffered message.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # use tokio_stream::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// let mut client = ClaudeClient::new(options)?;
+    /// let mut events = client.subscribe();
+    /// client.connect().await?;
+    ///
+    /// tokio::spawn(async move {
+    ///     while let Some(event) = events.next().await {
+    ///         match event {
+    ///             Ok(Message::RateLimitEvent(event)) => {
+    ///                 eprintln!("rate limit: {:?}", event.rate_limit_info.status)
+    ///             }
+    ///             Ok(msg) => println!("{:?}", msg),
+    ///             Err(e) => eprintln!("subscriber error: {}", e),
+    ///         }
+    ///     }
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe(&self) -> SessionStream {
+        let receiver = match self
+            .session_tx
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            Some(tx) => tx.subscribe(),
+            None => {
+                // Session already ended: hand back a stream that is immediately closed
+                let (_, rx) = broadcast::channel(1);
+                rx
+            }
+        };
+        SessionStream::new(receiver)
+    }
+
+    /// Snapshot of the session as observed so far
+    ///
+    /// The message router updates the state from every message the CLI sends:
+    /// the system init message sets the session ID, model, tools, MCP servers,
+    /// and permission mode, and each `ResultMessage` adds a turn, its usage,
+    /// and its cost. Successful [`set_model()`](Self::set_model) and
+    /// [`set_permission_mode()`](Self::set_permission_mode) calls are recorded
+    /// too. Before the CLI reports anything, the snapshot reflects the options
+    /// the client was created with.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # use tokio_stream::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// let mut client = ClaudeClient::new(options)?;
+    /// client.connect().await?;
+    /// let responses = client.send_message("Hello").await?.receive_response().await?;
+    ///
+    /// let session = client.session();
+    /// println!(
+    ///     "session {:?} on {:?}: {} turns, ${:.4}",
+    ///     session.session_id, session.model, session.turn_count, session.total_cost_usd
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn session(&self) -> SessionState {
+        self.session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Feed a message into the session state as the router would
+    #[cfg(test)]
+    pub(crate) fn record_session(&self, message: &Message) {
+        self.session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(message);
+    }
+
+    /// Install a fresh per-turn channel and write the user message
+    async fn send_turn(&self, input: UserInput) -> Result<ResponseStream, ClawError> {
         // Check if connected
         if self.control.is_none() {
             return Err(ClawError::Connection(
@@ -646,59 +1207,74 @@ impl ClaudeClient {
             ));
         }
 
-        // Create a fresh per-turn channel
-        let (tx, rx) = mpsc::unbounded_channel();
+        self.wait_for_rate_limit().await?;
+
+        // Create a fresh bounded per-turn channel
+        let (turn, stream) = turn_channel(&self.options.channel);
 
         // Install the sender in the routing slot so the background router
         // starts forwarding messages to this turn's receiver
-        *self.current_turn_tx.lock().await = Some(tx);
+        *self.current_turn_tx.lock().await = Some(turn);
 
         // Write the message to the CLI (AFTER installing the sender, so we
         // don't miss any messages that arrive immediately after the write)
-        self.write_message(content.into().as_str()).await?;
+        self.write_message(input).await?;
 
         // Return the stream backed by the per-turn receiver
-        Ok(ResponseStream::new(rx))
+        Ok(stream)
     }
 
-    /// Write a user message to the CLI stdin
-    ///
-    /// This is an internal helper that formats and sends a user message.
-    ///
-    /// # Message Format
+    /// Apply the configured [`RateLimitPolicy`] to the latest rate limit events
     ///
-    /// ```json
-    /// {
-    ///   "type": "user",
-    ///   "session_id": "",
-    ///   "message": {
-    ///     "role": "user",
-    ///     "content": "..."
-    ///   },
-    ///   "parent_tool_use_id": null
-    /// }
-    /// ```
-    async fn write_message(&self, content: &str) -> Result<(), ClawError> {
-        use serde_json::json;
+    /// Sleeps until every blocking limit resets (the latest `retry_after`
+    /// among them), or fails with `ClawError::RateLimited` when the policy
+    /// does not allow the wait.
+    async fn wait_for_rate_limit(&self) -> Result<(), ClawError> {
+        let Some(policy) = self.options.rate_limit_policy else {
+            return Ok(());
+        };
+        let blocking = {
+            let session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+            session
+                .rate_limits
+                .iter()
+                .filter_map(|(limit, event)| Some((*limit, event.rate_limit_info.retry_after()?)))
+                .max_by_key(|(_, retry_after)| *retry_after)
+        };
+        let Some((limit, retry_after)) = blocking else {
+            return Ok(());
+        };
+        let limit = limit.as_str().to_string();
+
+        match policy {
+            RateLimitPolicy::Wait { max_wait } if retry_after <= max_wait => {
+                tracing::info!(limit, ?retry_after, "Rate limited, waiting for reset");
+                tokio::time::sleep(retry_after).await;
+                Ok(())
+            }
+            _ => Err(ClawError::RateLimited { limit, retry_after }),
+        }
+    }
 
+    /// Write a user message to the CLI stdin
+    ///
+    /// This is an internal helper that serializes a [`UserInput`] as one NDJSON
+    /// line. An empty `session_id` is replaced with the current session ID, if known.
+    async fn write_message(&self, mut input: UserInput) -> Result<(), ClawError> {
         let transport = self
-            .transport
+            .control
             .as_ref()
+            .map(|c| c.transport())
             .ok_or_else(|| ClawError::Connection("Transport not available".to_string()))?;
 
-        // Format user message (matches Python SDK format)
-        let message = json!({
-            "type": "user",
-            "session_id": "",
-            "message": {
-                "role": "user",
-                "content": content
-            },
-            "parent_tool_use_id": null
-        });
+        if input.session_id.is_empty()
+            && let Some(session_id) = self.session().session_id
+        {
+            input.session_id = session_id;
+        }
 
         // Serialize to bytes
-        let mut bytes = serde_json::to_vec(&message).map_err(|e| {
+        let mut bytes = serde_json::to_vec(&input).map_err(|e| {
             ClawError::Connection(format!("Failed to serialize user message: {}", e))
         })?;
         bytes.push(b'\n'); // NDJSON requires newline
@@ -709,116 +1285,346 @@ impl ClaudeClient {
         Ok(())
     }
 
+    /// Router state sharing this client's slots and flags
+    fn router_state(
+        &self,
+        control: Arc<ControlProtocol>,
+        respawn_options: Option<ClaudeAgentOptions>,
+    ) -> RouterState {
+        RouterState {
+            control,
+            current_turn_tx: self.current_turn_tx.clone(),
+            session_tx: self.session_tx.clone(),
+            session: self.session.clone(),
+            is_initialized: self.is_initialized.clone(),
+            closed: self.closed.clone(),
+            channel: self.options.channel,
+            respawn_options,
+            runtime_mcp_servers: self.runtime_mcp_servers.clone(),
+        }
+    }
+
     /// Spawn background task that routes messages from the transport channel.
     ///
-    /// Control messages (`control_request`, `control_response`) are dispatched
-    /// to the `ControlProtocol`. All other messages are forwarded to the
-    /// current-turn sender stored in `current_turn_tx`.
+    /// Control messages (`control_request`, `control_response`,
+    /// `control_cancel_request`) are dispatched to the `ControlProtocol`; incoming
+    /// requests are handled on separate tasks. All other messages are broadcast to session
+    /// subscribers and forwarded to the current-turn sender stored in
+    /// `current_turn_tx`.
     ///
     /// This task runs for the lifetime of the connection. When `send_message()`
     /// is called, it installs a new sender in `current_turn_tx`; the router
     /// automatically starts delivering to the new turn's receiver.
+    ///
+    /// If the channel ends while the session is initialized (the CLI died
+    /// without `close()`), the router runs crash recovery and keeps routing
+    /// from the respawned CLI. Otherwise it ends the current turn and every
+    /// `SessionStream`.
     fn spawn_message_router(
-        mut rx: mpsc::UnboundedReceiver<Result<Value, ClawError>>,
-        control: Arc<ControlProtocol>,
-        current_turn_tx: CurrentTurnSender,
-    ) {
-        use crate::control::messages::{ControlResponse, IncomingControlRequest};
+        mut rx: MessageReceiver,
+        state: RouterState,
+    ) -> tokio::task::JoinHandle<()> {
         use tracing::{debug, warn};
 
         tokio::spawn(async move {
-            while let Some(msg) = rx.recv().await {
-                match msg {
-                    Ok(value) => {
-                        let msg_type = value.get("type").and_then(|v| v.as_str());
-
-                        match msg_type {
-                            Some("control_response") => {
-                                // Extract request_id from INSIDE the response object
-                                // (matches Python SDK: response.get("request_id"))
-                                let request_id = value
-                                    .get("response")
-                                    .and_then(|r| r.get("request_id"))
-                                    .and_then(|v| v.as_str())
-                                    .unwrap_or("")
-                                    .to_string();
-
-                                debug!(
-                                    request_id = %request_id,
-                                    "Received control_response"
-                                );
-
-                                if let Some(response_val) = value.get("response") {
-                                    match serde_json::from_value::<ControlResponse>(
-                                        response_val.clone(),
-                                    ) {
-                                        Ok(response) => {
-                                            control.handle_response(&request_id, response).await;
-                                        }
-                                        Err(e) => {
-                                            warn!("Failed to parse control response: {}", e);
-                                        }
-                                    }
-                                }
-                            }
-                            Some("control_request") => {
-                                let request_id = value
-                                    .get("request_id")
-                                    .and_then(|v| v.as_str())
-                                    .unwrap_or("")
-                                    .to_string();
-
-                                if let Some(request_val) = value.get("request") {
-                                    match serde_json::from_value::<IncomingControlRequest>(
-                                        request_val.clone(),
-                                    ) {
-                                        Ok(incoming) => {
-                                            control.handle_incoming(&request_id, incoming).await;
-                                        }
-                                        Err(e) => {
-                                            warn!(
-                                                "Failed to parse incoming control request: {}",
-                                                e
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                            _ => {
-                                // Forward non-control messages to the current turn's sender.
-                                // Lock briefly to read the sender, then release before sending.
-                                let sender = {
-                                    let guard = current_turn_tx.lock().await;
-                                    guard.clone()
-                                };
-                                if let Some(tx) = sender
-                                    && tx.send(Ok(value)).is_err()
-                                {
-                                    // Receiver was dropped (caller discarded the stream).
-                                    // Clear the slot so future sends are no-ops until
-                                    // send_message() installs a new one.
-                                    *current_turn_tx.lock().await = None;
-                                }
-                                // If no sender is installed (between turns), messages are discarded.
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        // Forward transport errors to the current turn's sender
-                        let sender = {
-                            let guard = current_turn_tx.lock().await;
-                            guard.clone()
-                        };
-                        if let Some(tx) = sender
-                            && tx.send(Err(e)).is_err()
-                        {
-                            *current_turn_tx.lock().await = None;
-                        }
+            let mut turns = TurnForwarder::new(&state);
+            loop {
+                let mut overflowed = false;
+                while let Some(msg) = turns.next(&mut rx).await {
+                    overflowed = matches!(msg, Err(ClawError::ChannelOverflow { .. }));
+                    Self::route_message(msg, &state, &mut turns).await;
+                }
+
+                // Under OverflowPolicy::Error the transport stops reading after
+                // reporting the overflow, which the turn has already received.
+                // The CLI did not crash, so do not respawn it.
+                if overflowed {
+                    warn!("Transport message channel overflowed, ending session");
+                    state.is_initialized.store(false, Ordering::SeqCst);
+                    state.control.abandon_all().await;
+                    let _ = state.control.transport().close().await;
+                    break;
+                }
+
+                // close() clears is_initialized before shutting down, so a set
+                // flag here means the CLI exited on its own
+                if !state.is_initialized.swap(false, Ordering::SeqCst) {
+                    break;
+                }
+                warn!("CLI process exited unexpectedly");
+
+                // Nothing written to the dead process will be answered, so fail
+                // waiting callers now rather than after the recovery backoff
+                state.control.abandon_all().await;
+
+                match Self::recover(&state, &mut turns).await {
+                    Some(new_rx) => rx = new_rx,
+                    None => {
+                        turns
+                            .end_current(ClawError::Connection(
+                                "CLI process exited unexpectedly".to_string(),
+                            ))
+                            .await;
+                        break;
                     }
                 }
             }
 
+            // Transport closed for good: end the current turn and every SessionStream
+            *state.current_turn_tx.lock().await = None;
+            state
+                .session_tx
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take();
+
+            // Streams still reading a backlog get the rest of it
+            turns.flush().await;
             debug!("Message routing task finished");
+        })
+    }
+
+    /// Route a single message read from the transport
+    async fn route_message(
+        msg: Result<Value, ClawError>,
+        state: &RouterState,
+        turns: &mut TurnForwarder,
+    ) {
+        use crate::control::messages::{ControlResponse, IncomingControlRequest};
+        use tracing::{debug, warn};
+
+        let value = match msg {
+            Ok(value) => value,
+            Err(e) => {
+                // Forward transport errors to the current turn's sender
+                turns.forward(Err(e)).await;
+                return;
+            }
+        };
+
+        match value.get("type").and_then(|v| v.as_str()) {
+            Some("control_response") => {
+                // Extract request_id from INSIDE the response object
+                // (matches Python SDK: response.get("request_id"))
+                let request_id = value
+                    .get("response")
+                    .and_then(|r| r.get("request_id"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                debug!(request_id = %request_id, "Received control_response");
+
+                if let Some(response_val) = value.get("response") {
+                    match serde_json::from_value::<ControlResponse>(response_val.clone()) {
+                        Ok(response) => {
+                            state.control.handle_response(&request_id, response).await;
+                        }
+                        Err(e) => {
+                            warn!("Failed to parse control response: {}", e);
+                        }
+                    }
+                }
+            }
+            Some("control_request") => {
+                let request_id = value
+                    .get("request_id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                if let Some(request_val) = value.get("request") {
+                    match serde_json::from_value::<IncomingControlRequest>(request_val.clone()) {
+                        Ok(incoming) => {
+                            // Handled on its own task so a slow handler cannot
+                            // block routing of responses it may be waiting for
+                            state.control.dispatch_incoming(request_id, incoming);
+                        }
+                        Err(e) => {
+                            warn!("Failed to parse incoming control request: {}", e);
+                        }
+                    }
+                }
+            }
+            Some("control_cancel_request") => {
+                // The CLI abandoned one of its requests (e.g. the turn was interrupted)
+                if let Some(request_id) = value.get("request_id").and_then(|v| v.as_str()) {
+                    let cancelled = state.control.cancel_incoming(request_id);
+                    debug!(request_id = %request_id, cancelled, "Received control_cancel_request");
+                }
+            }
+            _ => {
+                let message = Message::parse(value.clone());
+                {
+                    let mut session = state.session.lock().unwrap_or_else(|e| e.into_inner());
+                    // Remember the session ID so a crashed CLI can be resumed
+                    if let Some(id) = value.get("session_id").and_then(|v| v.as_str())
+                        && !id.is_empty()
+                    {
+                        session.session_id = Some(id.to_string());
+                    }
+                    if let Ok(message) = &message {
+                        session.record(message);
+                    }
+                }
+
+                // Session subscribers see every message, even between turns
+                match message {
+                    Ok(message) => broadcast_message(&state.session_tx, message),
+                    Err(e) => debug!("Failed to parse message for session state: {}", e),
+                }
+
+                // Forward non-control messages to the current turn's sender.
+                // If no sender is installed (between turns), messages are discarded.
+                turns.forward(Ok(value)).await;
+            }
+        }
+    }
+
+    /// Respawn the CLI with `--resume <session_id>` according to the recovery policy
+    ///
+    /// Returns the message receiver of the new CLI once it is initialized, or
+    /// `None` if recovery is disabled, the session ID is unknown, the client was
+    /// closed, or every attempt failed. On success a `SystemMessage::Reconnected`
+    /// is delivered to subscribers and to the interrupted turn, which then ends.
+    async fn recover(state: &RouterState, turns: &mut TurnForwarder) -> Option<MessageReceiver> {
+        use tracing::{info, warn};
+
+        let base = state.respawn_options.as_ref()?;
+        let policy = base.recovery.clone()?;
+        let session = state
+            .session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let Some(options) = resume_options(base, &session) else {
+            warn!("Cannot recover: the CLI never reported a session ID");
+            return None;
+        };
+        let session_id = session.session_id.unwrap_or_default();
+
+        for attempt in 1..=policy.max_attempts {
+            tokio::time::sleep(policy.backoff_for(attempt)).await;
+            if state.closed.load(Ordering::SeqCst) {
+                return None;
+            }
+
+            info!(session_id = %session_id, attempt, "Respawning CLI to resume session");
+            match Self::reconnect(state, &options, turns).await {
+                Ok(rx) => {
+                    if state.closed.load(Ordering::SeqCst) {
+                        let _ = state.control.transport().close().await;
+                        return None;
+                    }
+                    state.is_initialized.store(true, Ordering::SeqCst);
+
+                    let event = Message::System(SystemMessage::Reconnected {
+                        session_id: session_id.clone(),
+                        attempt,
+                    });
+                    if let Ok(value) = serde_json::to_value(&event) {
+                        turns.forward(Ok(value)).await;
+                    }
+                    broadcast_message(&state.session_tx, event);
+
+                    // The interrupted turn never completes on the new process
+                    *state.current_turn_tx.lock().await = None;
+                    return Some(rx);
+                }
+                Err(e) => warn!(attempt, "Crash recovery attempt failed: {}", e),
+            }
+        }
+
+        warn!(session_id = %session_id, "Crash recovery gave up");
+        None
+    }
+
+    /// Spawn a fresh CLI, swap it into the control protocol, and redo the initialize handshake
+    async fn reconnect(
+        state: &RouterState,
+        options: &ClaudeAgentOptions,
+        turns: &mut TurnForwarder,
+    ) -> Result<MessageReceiver, ClawError> {
+        // Make sure the old process is gone (e.g. it stopped writing rather than exiting)
+        let _ = state.control.transport().close().await;
+
+        let mut transport = subprocess_transport(options);
+        transport.connect().await?;
+        let mut rx = transport.messages();
+        let transport: Arc<dyn Transport> = Arc::new(transport);
+        state.control.replace_transport(transport.clone()).await;
+
+        // The initialize response arrives on the new channel, so keep routing
+        // while the handshake is in flight
+        let mut init = {
+            let control = state.control.clone();
+            let options = options.clone();
+            tokio::spawn(async move { control.initialize(&options).await })
+        };
+        let result = loop {
+            let msg = tokio::select! {
+                joined = &mut init => {
+                    break joined.unwrap_or_else(|e| {
+                        Err(ClawError::ControlError(format!("Initialization task failed: {}", e)))
+                    });
+                }
+                msg = turns.next(&mut rx) => msg,
+            };
+            match msg {
+                Some(msg) => Self::route_message(msg, state, turns).await,
+                None => {
+                    init.abort();
+                    break Err(ClawError::Connection(
+                        "CLI exited during initialization".to_string(),
+                    ));
+                }
+            }
+        };
+
+        match result {
+            Ok(_) => {
+                Self::restore_runtime_mcp_servers(state).await;
+                Ok(rx)
+            }
+            Err(e) => {
+                let _ = transport.close().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Re-add the session's runtime MCP servers to a respawned CLI
+    ///
+    /// The response arrives once the router resumes reading the new process,
+    /// so the request is sent from a separate task.
+    async fn restore_runtime_mcp_servers(state: &RouterState) {
+        use crate::control::messages::ControlRequest;
+        use tracing::warn;
+
+        let servers = state
+            .runtime_mcp_servers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .servers
+            .clone();
+        if servers.is_empty() {
+            return;
+        }
+        let control = state.control.clone();
+        tokio::spawn(async move {
+            let result = match control
+                .request(ControlRequest::McpSetServers { servers })
+                .await
+            {
+                Ok(response) => {
+                    parse_control_payload::<McpSetServersResponse>(response, "Restore MCP servers")
+                        .map(|_| ())
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("Failed to restore runtime MCP servers: {}", e);
+            }
         });
     }
 
@@ -829,6 +1635,11 @@ impl ClaudeClient {
     /// Sends a cancellation signal to stop ongoing processing. The CLI will finish
     /// the current operation and return control.
     ///
+    /// Waits for the CLI's acknowledgement for as long as the `interrupt`
+    /// deadline in [`ClaudeAgentOptions::control_timeouts`] allows; use
+    /// [`interrupt_with()`](Self::interrupt_with) for an explicit deadline or
+    /// cancellation.
+    ///
     /// # Errors
     ///
     /// - `ClawError::Connection` - Not connected
@@ -853,13 +1664,70 @@ impl ClaudeClient {
     /// # }
     /// ```
     pub async fn interrupt(&self) -> Result<(), ClawError> {
-        use crate::control::messages::{ControlRequest, ControlResponse};
+        use crate::control::messages::ControlRequest;
 
         let control = self.control.as_ref().ok_or_else(|| {
             ClawError::Connection("Not connected. Call connect() first.".to_string())
         })?;
 
         let response = control.request(ControlRequest::Interrupt).await?;
+        Self::interrupt_result(response)
+    }
+
+    /// Interrupt the current agent execution with an explicit deadline and cancellation
+    ///
+    /// Behaves like [`interrupt()`](Self::interrupt), but waits at most
+    /// `deadline` for the CLI's acknowledgement and stops waiting as soon as
+    /// `cancel` is cancelled.
+    ///
+    /// # Errors
+    ///
+    /// - `ClawError::Connection` - Not connected
+    /// - `ClawError::ControlTimeout` - No acknowledgement within `deadline`
+    /// - `ClawError::ControlCancelled` - `cancel` fired first
+    /// - `ClawError::ControlError` - Interrupt failed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// # let mut client = ClaudeClient::new(options)?;
+    /// # client.connect().await?;
+    /// let _stream = client.send_message("Write a very long essay").await?;
+    ///
+    /// // Give up on the acknowledgement when the app shuts down
+    /// let shutdown = CancellationToken::new();
+    /// client
+    ///     .interrupt_with(Duration::from_secs(2), shutdown.clone())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn interrupt_with(
+        &self,
+        deadline: std::time::Duration,
+        cancel: CancellationToken,
+    ) -> Result<(), ClawError> {
+        use crate::control::messages::ControlRequest;
+
+        let control = self.control.as_ref().ok_or_else(|| {
+            ClawError::Connection("Not connected. Call connect() first.".to_string())
+        })?;
+
+        let response = control
+            .request_with(ControlRequest::Interrupt, deadline, cancel)
+            .await?;
+        Self::interrupt_result(response)
+    }
+
+    fn interrupt_result(
+        response: crate::control::messages::ControlResponse,
+    ) -> Result<(), ClawError> {
+        use crate::control::messages::ControlResponse;
 
         match response {
             ControlResponse::Success { .. } => Ok(()),
@@ -913,7 +1781,13 @@ impl ClaudeClient {
             .await?;
 
         match response {
-            ControlResponse::Success { .. } => Ok(()),
+            ControlResponse::Success { .. } => {
+                self.session
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .permission_mode = Some(mode);
+                Ok(())
+            }
             ControlResponse::Error { error, .. } => Err(ClawError::ControlError(format!(
                 "Set permission mode failed: {}",
                 error
@@ -957,14 +1831,18 @@ impl ClaudeClient {
             ClawError::Connection("Not connected. Call connect() first.".to_string())
         })?;
 
+        let model = model.into();
         let response = control
             .request(ControlRequest::SetModel {
-                model: model.into(),
+                model: model.clone(),
             })
             .await?;
 
         match response {
-            ControlResponse::Success { .. } => Ok(()),
+            ControlResponse::Success { .. } => {
+                self.session.lock().unwrap_or_else(|e| e.into_inner()).model = Some(model);
+                Ok(())
+            }
             ControlResponse::Error { error, .. } => Err(ClawError::ControlError(format!(
                 "Set model failed: {}",
                 error
@@ -974,11 +1852,8 @@ impl ClaudeClient {
 
     /// Query MCP server connection status
     ///
-    /// Returns information about connected MCP servers, their tools, and status.
-    ///
-    /// # Returns
-    ///
-    /// JSON object with MCP server information
+    /// Returns the status of each configured MCP server, with its error (if the
+    /// connection failed) and the tools it exposes.
     ///
     /// # Errors
     ///
@@ -996,26 +1871,21 @@ impl ClaudeClient {
     /// # let mut client = ClaudeClient::new(options)?;
     /// # client.connect().await?;
     /// let status = client.mcp_status().await?;
-    /// println!("MCP Status: {}", status);
+    /// for server in status.unhealthy() {
+    ///     println!("{}: {:?} {:?}", server.name, server.status, server.error);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn mcp_status(&self) -> Result<serde_json::Value, ClawError> {
-        use crate::control::messages::{ControlRequest, ControlResponse};
+    pub async fn mcp_status(&self) -> Result<McpStatusResponse, ClawError> {
+        use crate::control::messages::ControlRequest;
 
         let control = self.control.as_ref().ok_or_else(|| {
             ClawError::Connection("Not connected. Call connect() first.".to_string())
         })?;
 
         let response = control.request(ControlRequest::McpStatus).await?;
-
-        match response {
-            ControlResponse::Success { data } => Ok(data),
-            ControlResponse::Error { error, .. } => Err(ClawError::ControlError(format!(
-                "MCP status query failed: {}",
-                error
-            ))),
-        }
+        parse_control_payload(response, "MCP status query")
     }
 
     /// Rewind file state to a specific message
@@ -1074,10 +1944,8 @@ impl ClaudeClient {
     /// Returns version and capability information from the connected Claude Code CLI.
     /// This is useful for runtime capability detection and debugging.
     ///
-    /// The returned `Value` is a JSON object with at least:
-    /// - `"version"` — the CLI version string (e.g., `"2.1.45"`)
-    ///
-    /// Additional fields may be present depending on the CLI version.
+    /// [`ServerInfo::version`] holds the CLI version string (e.g., `"2.1.45"`);
+    /// additional fields depend on the CLI version and are kept in `extra`.
     ///
     /// # Errors
     ///
@@ -1095,181 +1963,520 @@ impl ClaudeClient {
     /// # let mut client = ClaudeClient::new(options)?;
     /// # client.connect().await?;
     /// let info = client.get_server_info().await?;
-    /// println!("CLI version: {}", info["version"]);
+    /// println!("CLI version: {:?}", info.version);
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn get_server_info(&self) -> Result<serde_json::Value, ClawError> {
-        use crate::control::messages::{ControlRequest, ControlResponse};
+    pub async fn get_server_info(&self) -> Result<ServerInfo, ClawError> {
+        use crate::control::messages::ControlRequest;
 
         let control = self.control.as_ref().ok_or_else(|| {
             ClawError::Connection("Not connected. Call connect() first.".to_string())
         })?;
 
         let response = control.request(ControlRequest::GetServerInfo).await?;
-
-        match response {
-            ControlResponse::Success { data } => Ok(data),
-            ControlResponse::Error { error, .. } => Err(ClawError::ControlError(format!(
-                "Get server info failed: {}",
-                error
-            ))),
-        }
+        parse_control_payload(response, "Get server info")
     }
 
-    // Handler registration
-
-    /// Register a handler for can_use_tool permission requests
+    /// Snapshot of what the connected CLI offers and how healthy the session is
     ///
-    /// The handler will be invoked whenever the CLI asks for permission to use a tool.
-    /// This allows custom permission logic beyond the built-in permission modes.
+    /// Combines the response of the initialize handshake (commands, models,
+    /// output styles, account) with fresh [`get_server_info()`](Self::get_server_info)
+    /// and [`mcp_status()`](Self::mcp_status) queries, which run concurrently.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `handler` - Handler implementing `CanUseToolHandler` trait
+    /// - `ClawError::Connection` - Not connected (call `connect()` first)
+    /// - `ClawError::ControlTimeout` - A query timed out
+    /// - `ClawError::ControlError` - A query failed
+    /// - `ClawError::MessageParse` - A response did not have the expected shape
     ///
     /// # Example
     ///
     /// ```no_run
     /// # use rusty_claw::prelude::*;
-    /// # use std::sync::Arc;
-    /// # use async_trait::async_trait;
-    /// #
-    /// # struct MyHandler;
-    /// # #[async_trait]
-    /// # impl CanUseToolHandler for MyHandler {
-    /// #     async fn can_use_tool(&self, tool_name: &str, tool_input: &serde_json::Value) -> Result<rusty_claw::permissions::PermissionDecision, rusty_claw::error::ClawError> {
-    /// #         Ok(rusty_claw::permissions::PermissionDecision::Allow { updated_input: None })
-    /// #     }
-    /// # }
-    /// #
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// # let options = ClaudeAgentOptions::default();
     /// # let mut client = ClaudeClient::new(options)?;
     /// # client.connect().await?;
-    /// let handler = Arc::new(MyHandler);
-    /// client.register_can_use_tool_handler(handler).await;
+    /// let caps = client.capabilities().await?;
+    /// println!("CLI {:?}", caps.server_info.version);
+    /// for model in &caps.initialize.models {
+    ///     println!("model {} ({:?})", model.value, model.display_name);
+    /// }
+    /// for server in &caps.mcp_status.mcp_servers {
+    ///     println!("mcp {} connected={}", server.name, server.is_connected());
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn register_can_use_tool_handler(&self, handler: Arc<dyn CanUseToolHandler>) {
-        if let Some(control) = &self.control {
-            let mut handlers = control.handlers().await;
-            handlers.register_can_use_tool(handler);
-        }
+    pub async fn capabilities(&self) -> Result<Capabilities, ClawError> {
+        let control = self.control.as_ref().ok_or_else(|| {
+            ClawError::Connection("Not connected. Call connect() first.".to_string())
+        })?;
+
+        let (server_info, mcp_status) =
+            tokio::try_join!(self.get_server_info(), self.mcp_status())?;
+        Ok(Capabilities {
+            initialize: control.initialize_response().unwrap_or_default(),
+            server_info,
+            mcp_status,
+        })
     }
 
-    /// Register a hook callback handler
+    /// Add an external MCP server to the running session
     ///
-    /// Hooks allow you to intercept and respond to lifecycle events like tool use,
-    /// message processing, and error handling.
+    /// The CLI connects to the server and exposes its tools from the next turn
+    /// on, without restarting the conversation. Adding a server under a name that
+    /// was already added at runtime replaces it.
     ///
-    /// # Arguments
+    /// Connection failures do not fail the call; they are reported per server in
+    /// [`McpSetServersResponse::errors`].
     ///
-    /// * `hook_id` - Unique identifier for this hook
-    /// * `handler` - Handler implementing `HookHandler` trait
+    /// # Errors
+    ///
+    /// - `ClawError::Connection` - Not connected
+    /// - `ClawError::ControlTimeout` - Request timed out
+    /// - `ClawError::ControlError` - The CLI rejected the request
     ///
     /// # Example
     ///
     /// ```no_run
     /// # use rusty_claw::prelude::*;
-    /// # use std::sync::Arc;
-    /// # use async_trait::async_trait;
-    /// #
-    /// # struct MyHook;
-    /// # #[async_trait]
-    /// # impl HookHandler for MyHook {
-    /// #     async fn call(&self, _event: HookEvent, input: serde_json::Value) -> Result<serde_json::Value, rusty_claw::error::ClawError> {
-    /// #         Ok(serde_json::json!({"status": "ok"}))
-    /// #     }
-    /// # }
-    /// #
+    /// # use std::collections::HashMap;
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// # let options = ClaudeAgentOptions::default();
     /// # let mut client = ClaudeClient::new(options)?;
     /// # client.connect().await?;
-    /// let handler = Arc::new(MyHook);
-    /// client.register_hook("my_hook".to_string(), handler).await;
+    /// let server = McpServerConfig::Stdio(McpStdioServerConfig {
+    ///     command: "github-mcp".to_string(),
+    ///     args: vec![],
+    ///     env: HashMap::new(),
+    /// });
+    /// let result = client.add_mcp_server("github", server).await?;
+    /// if let Some(error) = result.errors.get("github") {
+    ///     eprintln!("github MCP server failed: {}", error);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn register_hook(&self, hook_id: String, handler: Arc<dyn HookHandler>) {
-        if let Some(control) = &self.control {
-            let mut handlers = control.handlers().await;
-            handlers.register_hook(hook_id, handler);
-        }
+    pub async fn add_mcp_server(
+        &self,
+        name: impl Into<String>,
+        config: McpServerConfig,
+    ) -> Result<McpSetServersResponse, ClawError> {
+        let name = name.into();
+        self.update_runtime_mcp_servers(|servers| {
+            servers.insert(name.clone(), RuntimeMcpServer::External(config.clone()));
+        })
+        .await
     }
 
-    /// Register an MCP message handler
+    /// Add an SDK-hosted MCP server to the running session
     ///
-    /// Handles MCP (Model Context Protocol) messages from the CLI, allowing you to
-    /// implement custom MCP server functionality.
+    /// The server runs in-process: its tools are called through `mcp_message`
+    /// requests, which are routed to it by name ahead of any
+    /// [`McpMessageHandler`]. If the CLI fails to add the server, the server
+    /// previously registered under the same name (if any) is restored.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `handler` - Handler implementing `McpMessageHandler` trait
+    /// - `ClawError::Connection` - Not connected
+    /// - `ClawError::ControlTimeout` - Request timed out
+    /// - `ClawError::ControlError` - The CLI rejected the request
     ///
     /// # Example
     ///
     /// ```no_run
     /// # use rusty_claw::prelude::*;
-    /// # use std::sync::Arc;
-    /// # use async_trait::async_trait;
-    /// #
-    /// # struct MyMcpHandler;
-    /// # #[async_trait]
-    /// # impl McpMessageHandler for MyMcpHandler {
-    /// #     async fn handle(&self, _server_name: &str, message: serde_json::Value) -> Result<serde_json::Value, rusty_claw::error::ClawError> {
-    /// #         Ok(serde_json::json!({"result": "ok"}))
-    /// #     }
-    /// # }
-    /// #
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// # let options = ClaudeAgentOptions::default();
     /// # let mut client = ClaudeClient::new(options)?;
     /// # client.connect().await?;
-    /// let handler = Arc::new(MyMcpHandler);
-    /// client.register_mcp_message_handler(handler).await;
+    /// let server = create_sdk_mcp_server("repo_tools", "1.0.0", vec![]);
+    /// client.add_sdk_mcp_server(server).await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn register_mcp_message_handler(&self, handler: Arc<dyn McpMessageHandler>) {
-        if let Some(control) = &self.control {
-            // Already connected: register directly on control protocol
+    pub async fn add_sdk_mcp_server(
+        &self,
+        server: impl Into<Arc<SdkMcpServerImpl>>,
+    ) -> Result<McpSetServersResponse, ClawError> {
+        let control = self.control.as_ref().ok_or_else(|| {
+            ClawError::Connection("Not connected. Call connect() first.".to_string())
+        })?;
+
+        // Register before the request: the CLI lists the server's tools right away
+        let server = server.into();
+        let name = server.name.clone();
+        let previous = control.handlers().await.register_sdk_mcp_server(server);
+
+        let result = self
+            .update_runtime_mcp_servers(|servers| {
+                servers.insert(
+                    name.clone(),
+                    RuntimeMcpServer::Sdk(SdkMcpServerRef { name: name.clone() }),
+                );
+            })
+            .await;
+        let added = matches!(&result, Ok(response) if !response.errors.contains_key(&name));
+        if !added {
             let mut handlers = control.handlers().await;
-            handlers.register_mcp_message(handler);
-        } else {
-            // Not yet connected: store for apply during connect(), before initialize()
-            if let Ok(mut guard) = self.pending_mcp_handler.lock() {
-                *guard = Some(handler);
+            match previous {
+                Some(previous) => {
+                    handlers.register_sdk_mcp_server(previous);
+                }
+                None => {
+                    handlers.remove_sdk_mcp_server(&name);
+                }
             }
         }
+        result
     }
-}
 
-/// Run a closure with a freshly connected `ClaudeClient`, ensuring `close()` is called on exit.
-///
-/// This is the idiomatic Rust alternative to Python's `async with ClaudeSDKClient() as client:`
-/// pattern. The client is connected before the closure runs and closed (even on error or panic)
-/// after it completes.
-///
-/// # Arguments
-///
-/// * `options` - Configuration for the Claude session
-/// * `f` - Async closure that receives a reference to the connected client
-///
-/// # Returns
-///
-/// The return value of the closure, or the first error encountered (connect, user, or close).
-///
-/// # Example
-///
-/// ```no_run
-/// use rusty_claw::client::with_client;
-/// use rusty_claw::prelude::*;
+    /// Remove an MCP server added with [`add_mcp_server()`](Self::add_mcp_server)
+    /// or [`add_sdk_mcp_server()`](Self::add_sdk_mcp_server)
+    ///
+    /// Servers configured at spawn time cannot be removed; disable them with
+    /// [`set_mcp_server_enabled()`](Self::set_mcp_server_enabled) instead.
+    ///
+    /// # Errors
+    ///
+    /// - `ClawError::Connection` - Not connected
+    /// - `ClawError::ControlTimeout` - Request timed out
+    /// - `ClawError::ControlError` - The server was not added at runtime, or
+    ///   the CLI rejected the request
+    pub async fn remove_mcp_server(&self, name: &str) -> Result<McpSetServersResponse, ClawError> {
+        let control = self.control.as_ref().ok_or_else(|| {
+            ClawError::Connection("Not connected. Call connect() first.".to_string())
+        })?;
+
+        let added = self
+            .runtime_mcp_servers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .servers
+            .contains_key(name);
+        if !added {
+            return Err(ClawError::ControlError(format!(
+                "MCP server '{}' was not added at runtime",
+                name
+            )));
+        }
+
+        let response = self
+            .update_runtime_mcp_servers(|servers| {
+                servers.remove(name);
+            })
+            .await?;
+        control.handlers().await.remove_sdk_mcp_server(name);
+        Ok(response)
+    }
+
+    /// Reconnect an MCP server
+    ///
+    /// Useful after a server failed to start or crashed (see
+    /// [`mcp_status()`](Self::mcp_status)). Works for spawn-time and runtime servers.
+    ///
+    /// # Errors
+    ///
+    /// - `ClawError::Connection` - Not connected
+    /// - `ClawError::ControlTimeout` - Request timed out
+    /// - `ClawError::ControlError` - Reconnect failed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// # let mut client = ClaudeClient::new(options)?;
+    /// # client.connect().await?;
+    /// let status = client.mcp_status().await?;
+    /// for server in status.unhealthy() {
+    ///     client.reconnect_mcp_server(&server.name).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reconnect_mcp_server(&self, name: impl Into<String>) -> Result<(), ClawError> {
+        use crate::control::messages::{ControlRequest, ControlResponse};
+
+        let control = self.control.as_ref().ok_or_else(|| {
+            ClawError::Connection("Not connected. Call connect() first.".to_string())
+        })?;
+
+        let response = control
+            .request(ControlRequest::McpReconnect {
+                server_name: name.into(),
+            })
+            .await?;
+
+        match response {
+            ControlResponse::Success { .. } => Ok(()),
+            ControlResponse::Error { error, .. } => Err(ClawError::ControlError(format!(
+                "MCP reconnect failed: {}",
+                error
+            ))),
+        }
+    }
+
+    /// Enable or disable an MCP server
+    ///
+    /// A disabled server is disconnected and its tools are hidden from the model,
+    /// but its configuration is kept so it can be enabled again. Works for
+    /// spawn-time and runtime servers.
+    ///
+    /// # Errors
+    ///
+    /// - `ClawError::Connection` - Not connected
+    /// - `ClawError::ControlTimeout` - Request timed out
+    /// - `ClawError::ControlError` - Toggle failed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// # let mut client = ClaudeClient::new(options)?;
+    /// # client.connect().await?;
+    /// client.set_mcp_server_enabled("github", false).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_mcp_server_enabled(
+        &self,
+        name: impl Into<String>,
+        enabled: bool,
+    ) -> Result<(), ClawError> {
+        use crate::control::messages::{ControlRequest, ControlResponse};
+
+        let control = self.control.as_ref().ok_or_else(|| {
+            ClawError::Connection("Not connected. Call connect() first.".to_string())
+        })?;
+
+        let response = control
+            .request(ControlRequest::McpToggle {
+                server_name: name.into(),
+                enabled,
+            })
+            .await?;
+
+        match response {
+            ControlResponse::Success { .. } => Ok(()),
+            ControlResponse::Error { error, .. } => Err(ClawError::ControlError(format!(
+                "MCP toggle failed: {}",
+                error
+            ))),
+        }
+    }
+
+    /// Send the runtime MCP server set with `change` applied, keeping it on success
+    ///
+    /// Servers the CLI reports errors for are dropped from the kept set. If
+    /// another update was committed while the request was in flight, `change`
+    /// is applied to the newer set and sent again, so neither update is lost.
+    async fn update_runtime_mcp_servers(
+        &self,
+        mut change: impl FnMut(&mut HashMap<String, RuntimeMcpServer>),
+    ) -> Result<McpSetServersResponse, ClawError> {
+        use crate::control::messages::ControlRequest;
+        use tracing::debug;
+
+        let control = self.control.as_ref().ok_or_else(|| {
+            ClawError::Connection("Not connected. Call connect() first.".to_string())
+        })?;
+
+        loop {
+            let (mut servers, version) = {
+                let registry = self
+                    .runtime_mcp_servers
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                (registry.servers.clone(), registry.version)
+            };
+            change(&mut servers);
+
+            let response = control
+                .request(ControlRequest::McpSetServers {
+                    servers: servers.clone(),
+                })
+                .await?;
+            let result: McpSetServersResponse = parse_control_payload(response, "Set MCP servers")?;
+            servers.retain(|name, _| !result.errors.contains_key(name));
+
+            let mut registry = self
+                .runtime_mcp_servers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if registry.version == version {
+                registry.servers = servers;
+                registry.version += 1;
+                return Ok(result);
+            }
+            debug!("Runtime MCP servers changed during mcp_set_servers, resending");
+        }
+    }
+
+    // Handler registration
+
+    /// Register a handler for can_use_tool permission requests
+    ///
+    /// The handler will be invoked whenever the CLI asks for permission to use a tool.
+    /// This allows custom permission logic beyond the built-in permission modes.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Handler implementing `CanUseToolHandler` trait
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// #
+    /// # struct MyHandler;
+    /// # #[async_trait]
+    /// # impl CanUseToolHandler for MyHandler {
+    /// #     async fn can_use_tool(&self, tool_name: &str, tool_input: &serde_json::Value, _context: &rusty_claw::permissions::ToolPermissionContext) -> Result<rusty_claw::permissions::PermissionDecision, rusty_claw::error::ClawError> {
+    /// #         Ok(rusty_claw::permissions::PermissionDecision::Allow { updated_input: None, updated_permissions: vec![] })
+    /// #     }
+    /// # }
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// # let mut client = ClaudeClient::new(options)?;
+    /// # client.connect().await?;
+    /// let handler = Arc::new(MyHandler);
+    /// client.register_can_use_tool_handler(handler).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn register_can_use_tool_handler(&self, handler: Arc<dyn CanUseToolHandler>) {
+        if let Some(control) = &self.control {
+            let mut handlers = control.handlers().await;
+            handlers.register_can_use_tool(handler);
+        }
+    }
+
+    /// Register a hook callback handler
+    ///
+    /// Hooks allow you to intercept and respond to lifecycle events like tool use,
+    /// message processing, and error handling.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook_id` - Unique identifier for this hook
+    /// * `handler` - Handler implementing `HookHandler` trait
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// #
+    /// # struct MyHook;
+    /// # #[async_trait]
+    /// # impl HookHandler for MyHook {
+    /// #     async fn call(&self, _event: HookEvent, input: serde_json::Value) -> Result<serde_json::Value, rusty_claw::error::ClawError> {
+    /// #         Ok(serde_json::json!({"status": "ok"}))
+    /// #     }
+    /// # }
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// # let mut client = ClaudeClient::new(options)?;
+    /// # client.connect().await?;
+    /// let handler = Arc::new(MyHook);
+    /// client.register_hook("my_hook".to_string(), handler).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn register_hook(&self, hook_id: String, handler: Arc<dyn HookHandler>) {
+        if let Some(control) = &self.control {
+            let mut handlers = control.handlers().await;
+            handlers.register_hook(hook_id, handler);
+        }
+    }
+
+    /// Register an MCP message handler
+    ///
+    /// Handles MCP (Model Context Protocol) messages from the CLI, allowing you to
+    /// implement custom MCP server functionality.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Handler implementing `McpMessageHandler` trait
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rusty_claw::prelude::*;
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// #
+    /// # struct MyMcpHandler;
+    /// # #[async_trait]
+    /// # impl McpMessageHandler for MyMcpHandler {
+    /// #     async fn handle(&self, _server_name: &str, message: serde_json::Value) -> Result<serde_json::Value, rusty_claw::error::ClawError> {
+    /// #         Ok(serde_json::json!({"result": "ok"}))
+    /// #     }
+    /// # }
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let options = ClaudeAgentOptions::default();
+    /// # let mut client = ClaudeClient::new(options)?;
+    /// # client.connect().await?;
+    /// let handler = Arc::new(MyMcpHandler);
+    /// client.register_mcp_message_handler(handler).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn register_mcp_message_handler(&self, handler: Arc<dyn McpMessageHandler>) {
+        if let Some(control) = &self.control {
+            // Already connected: register directly on control protocol
+            let mut handlers = control.handlers().await;
+            handlers.register_mcp_message(handler);
+        } else {
+            // Not yet connected: store for apply during connect(), before initialize()
+            if let Ok(mut guard) = self.pending_mcp_handler.lock() {
+                *guard = Some(handler);
+            }
+        }
+    }
+}
+
+/// Run a closure with a freshly connected `ClaudeClient`, ensuring `close()` is called on exit.
+///
+/// This is the idiomatic Rust alternative to Python's `async with ClaudeSDKClient() as client:`
+/// pattern. The client is connected before the closure runs and closed (even on error or panic)
+/// after it completes.
+///
+/// # Arguments
+///
+/// * `options` - Configuration for the Claude session
+/// * `f` - Async closure that receives a reference to the connected client
+///
+/// # Returns
+///
+/// The return value of the closure, or the first error encountered (connect, user, or close).
+///
+/// # Example
+///
+/// ```no_run
+/// use rusty_claw::client::with_client;
+/// use rusty_claw::prelude::*;
 ///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -1314,8 +2521,8 @@ where
 ///
 /// # Control Message Routing
 ///
-/// Control protocol messages (`control_request`, `control_response`) are
-/// handled transparently by a background routing task spawned during
+/// Control protocol messages (`control_request`, `control_response`,
+/// `control_cancel_request`) are handled transparently by a background routing task spawned during
 /// [`ClaudeClient::connect()`]. The `ResponseStream` never sees control
 /// messages - they are filtered before reaching this stream.
 ///
@@ -1348,7 +2555,10 @@ where
 /// ```
 pub struct ResponseStream {
     /// Receiver for per-turn user-facing messages
-    rx: mpsc::UnboundedReceiver<Result<Value, ClawError>>,
+    rx: MessageReceiver,
+
+    /// Error that ended the turn after the channel filled, yielded last
+    end: TurnEnd,
 
     /// Whether the stream has completed (Result message received or channel closed)
     is_complete: bool,
@@ -1356,9 +2566,11 @@ pub struct ResponseStream {
 
 impl ResponseStream {
     /// Create a new response stream backed by a per-turn receiver
-    fn new(rx: mpsc::UnboundedReceiver<Result<Value, ClawError>>) -> Self {
+    #[cfg(test)]
+    fn new(rx: MessageReceiver) -> Self {
         Self {
             rx,
+            end: TurnEnd::default(),
             is_complete: false,
         }
     }
@@ -1426,6 +2638,50 @@ impl ResponseStream {
 
         Ok(messages)
     }
+
+    /// Read this turn until its result and deserialize the structured output into `T`
+    ///
+    /// The client must have been configured for structured output, typically with
+    /// [`ClaudeAgentOptionsBuilder::output_schema::<T>()`](crate::options::ClaudeAgentOptionsBuilder::output_schema).
+    ///
+    /// # Errors
+    ///
+    /// - The first `ClawError` encountered while reading the stream
+    /// - `ClawError::StructuredOutput` if the output is missing or does not
+    ///   match `T`; the error carries the raw payload
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rusty_claw::prelude::*;
+    /// use schemars::JsonSchema;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct FileList {
+    ///     files: Vec<String>,
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let options = ClaudeAgentOptions::builder()
+    ///     .output_schema::<FileList>()
+    ///     .build();
+    /// let mut client = ClaudeClient::new(options)?;
+    /// client.connect().await?;
+    ///
+    /// let listing: FileList = client
+    ///     .send_message("List the Rust files in src/")
+    ///     .await?
+    ///     .structured()
+    ///     .await?;
+    /// println!("{:?}", listing.files);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn structured<T: serde::de::DeserializeOwned>(self) -> Result<T, ClawError> {
+        crate::query::collect_structured(self).await
+    }
 }
 
 impl Stream for ResponseStream {
@@ -1440,26 +2696,174 @@ impl Stream for ResponseStream {
         // Poll the receiver - control messages are already filtered out by
         // the background message routing task spawned during connect()
         match Pin::new(&mut self.rx).poll_recv(cx) {
-            Poll::Ready(Some(Ok(value))) => {
-                match serde_json::from_value::<Message>(value.clone()) {
-                    Ok(message) => Poll::Ready(Some(Ok(message))),
-                    Err(e) => Poll::Ready(Some(Err(ClawError::MessageParse {
-                        reason: format!("Failed to parse message: {}", e),
-                        raw: value.to_string(),
-                    }))),
-                }
-            }
+            Poll::Ready(Some(Ok(value))) => Poll::Ready(Some(Message::parse(value))),
             Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
             Poll::Ready(None) => {
-                // Stream ended
+                // Stream ended; an error that ended the turn comes last
                 self.is_complete = true;
-                Poll::Ready(None)
+                let end = self.end.lock().unwrap_or_else(|e| e.into_inner()).take();
+                Poll::Ready(end.map(Err))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Stream of every message in a session, returned by [`ClaudeClient::subscribe()`]
+///
+/// Unlike [`ResponseStream`], a `SessionStream` is not scoped to one turn: it
+/// keeps yielding messages across turns until the client is closed or the CLI
+/// exits. Each subscriber receives its own copy of every message.
+///
+/// # Errors
+///
+/// Yields [`ClawError::SubscriberLagged`] when the subscriber fell behind and
+/// messages were dropped; the stream continues afterwards.
+pub struct SessionStream {
+    inner: BroadcastStream<Message>,
+}
+
+impl SessionStream {
+    fn new(rx: broadcast::Receiver<Message>) -> Self {
+        Self {
+            inner: BroadcastStream::new(rx),
+        }
+    }
+}
+
+impl Stream for SessionStream {
+    type Item = Result<Message, ClawError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::new(&mut self.inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(message))) => Poll::Ready(Some(Ok(message))),
+            Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(skipped)))) => {
+                Poll::Ready(Some(Err(ClawError::SubscriberLagged { skipped })))
             }
+            Poll::Ready(None) => Poll::Ready(None),
             Poll::Pending => Poll::Pending,
         }
     }
 }
 
+/// What the connected CLI offers, returned by [`ClaudeClient::capabilities()`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Capabilities {
+    /// Response of the initialize handshake (commands, models, output styles, account)
+    pub initialize: InitializeResponse,
+    /// CLI version and server details
+    pub server_info: ServerInfo,
+    /// Status of each configured MCP server
+    pub mcp_status: McpStatusResponse,
+}
+
+/// Deserialize the payload of a successful control response
+///
+/// `what` names the operation in error messages (e.g., "Get server info").
+fn parse_control_payload<T: serde::de::DeserializeOwned>(
+    response: crate::control::messages::ControlResponse,
+    what: &str,
+) -> Result<T, ClawError> {
+    use crate::control::messages::ControlResponse;
+
+    let payload = match response {
+        ControlResponse::Error { error, .. } => {
+            return Err(ClawError::ControlError(format!(
+                "{} failed: {}",
+                what, error
+            )));
+        }
+        response => response.success_payload().unwrap_or_default(),
+    };
+    serde_json::from_value(payload.clone()).map_err(|e| ClawError::MessageParse {
+        reason: format!("{} returned an unexpected response: {}", what, e),
+        raw: payload.to_string(),
+    })
+}
+
+/// Snapshot of an interactive session, returned by [`ClaudeClient::session()`]
+#[derive(Debug, Clone, Default)]
+pub struct SessionState {
+    /// Session ID reported by the CLI (or the `resume` option before it reports one)
+    pub session_id: Option<String>,
+    /// Active model
+    pub model: Option<String>,
+    /// Tools available in the session
+    pub tools: Vec<ToolInfo>,
+    /// MCP servers and their connection status
+    pub mcp_servers: Vec<McpServerInfo>,
+    /// Current permission mode
+    pub permission_mode: Option<PermissionMode>,
+    /// Number of completed turns (one per `ResultMessage`)
+    pub turn_count: u32,
+    /// Token usage summed over all results
+    pub usage: UsageInfo,
+    /// Cost in USD summed over all results
+    pub total_cost_usd: f64,
+    /// Latest rate limit status reported by the CLI for each limit
+    ///
+    /// Events without a `rateLimitType` are kept under
+    /// [`RateLimitType::Unknown`].
+    pub rate_limits: HashMap<RateLimitType, RateLimitEvent>,
+}
+
+impl SessionState {
+    /// Initial state derived from the client options
+    fn new(options: &ClaudeAgentOptions) -> Self {
+        Self {
+            session_id: options.resume.clone(),
+            model: options.model.clone(),
+            permission_mode: options.permission_mode.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Update the snapshot from a message the CLI sent
+    fn record(&mut self, message: &Message) {
+        match message {
+            Message::System(SystemMessage::Init {
+                session_id,
+                tools,
+                mcp_servers,
+                model,
+                permission_mode,
+                ..
+            }) => {
+                self.session_id = Some(session_id.clone());
+                self.tools = tools.clone();
+                self.mcp_servers = mcp_servers.clone();
+                if let Some(model) = model {
+                    self.model = Some(model.clone());
+                }
+                if let Some(mode) = permission_mode {
+                    self.permission_mode = Some(mode.clone());
+                }
+            }
+            Message::System(SystemMessage::Reconnected { session_id, .. }) => {
+                self.session_id = Some(session_id.clone());
+            }
+            Message::RateLimitEvent(event) => {
+                let limit = event
+                    .rate_limit_info
+                    .rate_limit_type
+                    .unwrap_or(RateLimitType::Unknown);
+                self.rate_limits.insert(limit, event.clone());
+            }
+            Message::Result(ResultMessage::InputRequired) => {}
+            Message::Result(result) => self.record_result(result.total_cost_usd(), result.usage()),
+            _ => {}
+        }
+    }
+
+    fn record_result(&mut self, cost: Option<f64>, usage: Option<&UsageInfo>) {
+        self.turn_count += 1;
+        self.total_cost_usd += cost.unwrap_or(0.0);
+        if let Some(usage) = usage {
+            self.usage += usage;
+        }
+    }
+}
+
 /// Alias for [`ClaudeClient`] matching the Python SDK's `ClaudeSDKClient` class name.
 ///
 /// The Python SDK uses `ClaudeSDKClient` as the primary client class name.
@@ -1479,6 +2883,8 @@ pub type ClaudeSDKClient = ClaudeClient;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
 
     #[test]
     fn test_new_client() {
@@ -1496,7 +2902,7 @@ mod tests {
 
     #[test]
     fn test_response_stream_not_complete_initially() {
-        let (_tx, rx) = mpsc::unbounded_channel();
+        let (_tx, rx) = mpsc::channel(16);
         let stream = ResponseStream::new(rx);
         assert!(!stream.is_complete());
     }
@@ -1512,6 +2918,26 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_send_content_without_connect() {
+        let options = ClaudeAgentOptions::default();
+        let client = ClaudeClient::new(options).unwrap();
+        let result = client
+            .send_content(vec![UserContentBlock::text("test")])
+            .await;
+        assert!(matches!(result, Err(ClawError::Connection(_))));
+    }
+
+    #[tokio::test]
+    async fn test_send_input_without_connect() {
+        let options = ClaudeAgentOptions::default();
+        let client = ClaudeClient::new(options).unwrap();
+        let result = client
+            .send_input(UserInput::text("test").with_parent_tool_use_id("toolu_1"))
+            .await;
+        assert!(matches!(result, Err(ClawError::Connection(_))));
+    }
+
     #[tokio::test]
     async fn test_interrupt_without_connect() {
         let options = ClaudeAgentOptions::default();
@@ -1546,23 +2972,54 @@ mod tests {
         let result = client.mcp_status().await;
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), ClawError::Connection(_)));
-    }
+    }
+
+    #[tokio::test]
+    async fn test_rewind_files_without_connect() {
+        let options = ClaudeAgentOptions::default();
+        let client = ClaudeClient::new(options).unwrap();
+        let result = client.rewind_files("msg_123").await;
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), ClawError::Connection(_)));
+    }
+
+    #[tokio::test]
+    async fn test_get_server_info_without_connect() {
+        let options = ClaudeAgentOptions::default();
+        let client = ClaudeClient::new(options).unwrap();
+        let result = client.get_server_info().await;
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), ClawError::Connection(_)));
+    }
+
+    #[tokio::test]
+    async fn test_mcp_server_management_without_connect() {
+        let client = ClaudeClient::new(ClaudeAgentOptions::default()).unwrap();
+        let config = McpServerConfig::Stdio(crate::options::McpStdioServerConfig {
+            command: "github-mcp".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+        });
+        let result = client.add_mcp_server("github", config).await;
+        assert!(matches!(result.unwrap_err(), ClawError::Connection(_)));
+
+        let server = SdkMcpServerImpl::new("calc", "1.0.0");
+        let result = client.add_sdk_mcp_server(server).await;
+        assert!(matches!(result.unwrap_err(), ClawError::Connection(_)));
 
-    #[tokio::test]
-    async fn test_rewind_files_without_connect() {
-        let options = ClaudeAgentOptions::default();
-        let client = ClaudeClient::new(options).unwrap();
-        let result = client.rewind_files("msg_123").await;
-        assert!(result.is_err());
+        let result = client.remove_mcp_server("github").await;
+        assert!(matches!(result.unwrap_err(), ClawError::Connection(_)));
+        let result = client.reconnect_mcp_server("github").await;
+        assert!(matches!(result.unwrap_err(), ClawError::Connection(_)));
+        let result = client.set_mcp_server_enabled("github", false).await;
         assert!(matches!(result.unwrap_err(), ClawError::Connection(_)));
     }
 
     #[tokio::test]
-    async fn test_get_server_info_without_connect() {
+    async fn test_capabilities_without_connect() {
         let options = ClaudeAgentOptions::default();
         let client = ClaudeClient::new(options).unwrap();
-        let result = client.get_server_info().await;
-        assert!(result.is_err());
+        let result = client.capabilities().await;
         assert!(matches!(result.unwrap_err(), ClawError::Connection(_)));
     }
 
@@ -1584,6 +3041,12 @@ mod tests {
         assert_send::<ResponseStream>();
     }
 
+    #[test]
+    fn test_session_stream_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<SessionStream>();
+    }
+
     #[test]
     fn test_response_stream_is_unpin() {
         fn assert_unpin<T: Unpin>() {}
@@ -1632,9 +3095,11 @@ mod tests {
                 &self,
                 _tool_name: &str,
                 _tool_input: &serde_json::Value,
+                _context: &crate::permissions::ToolPermissionContext,
             ) -> Result<crate::permissions::PermissionDecision, ClawError> {
                 Ok(crate::permissions::PermissionDecision::Allow {
                     updated_input: None,
+                    updated_permissions: vec![],
                 })
             }
         }
@@ -1691,11 +3156,11 @@ mod tests {
         let current_turn_tx: CurrentTurnSender = Arc::new(Mutex::new(None));
 
         // Simulate first send_message: install sender 1
-        let (tx1, rx1) = mpsc::unbounded_channel::<Result<Value, ClawError>>();
+        let (tx1, rx1) = turn_channel(&ChannelConfig::default());
         *current_turn_tx.lock().await = Some(tx1);
 
         // Simulate second send_message: install sender 2 (replaces sender 1)
-        let (tx2, rx2) = mpsc::unbounded_channel::<Result<Value, ClawError>>();
+        let (tx2, rx2) = turn_channel(&ChannelConfig::default());
         *current_turn_tx.lock().await = Some(tx2);
 
         // tx1 is now orphaned (its corresponding entry was replaced in the slot).
@@ -1713,12 +3178,14 @@ mod tests {
         // Verify that sending through the slot reaches rx2
         {
             let guard = current_turn_tx.lock().await;
-            if let Some(tx) = guard.as_ref() {
-                tx.send(Ok(serde_json::json!({"type": "system"}))).unwrap();
+            if let Some(turn) = guard.as_ref() {
+                turn.tx
+                    .try_send(Ok(serde_json::json!({"type": "system"})))
+                    .unwrap();
             }
         }
         let mut rx2 = rx2;
-        let received = rx2.try_recv().unwrap();
+        let received = rx2.rx.try_recv().unwrap();
         assert!(received.is_ok());
     }
 
@@ -1727,7 +3194,7 @@ mod tests {
     async fn test_receive_response_collects_until_result() {
         use crate::messages::Message;
 
-        let (tx, rx) = mpsc::unbounded_channel();
+        let (tx, rx) = mpsc::channel(16);
 
         // Send some messages including a final Result
         let assistant_json = serde_json::json!({
@@ -1754,10 +3221,10 @@ mod tests {
             "usage": {"input_tokens": 10, "output_tokens": 5, "cache_creation_input_tokens": 0, "cache_read_input_tokens": 0}
         });
 
-        tx.send(Ok(assistant_json)).unwrap();
-        tx.send(Ok(result_json)).unwrap();
+        tx.try_send(Ok(assistant_json)).unwrap();
+        tx.try_send(Ok(result_json)).unwrap();
         // Send a third message that should NOT be collected (after Result)
-        tx.send(Ok(serde_json::json!({"type": "system", "subtype": "init", "session_id": "x", "tools": [], "mcp_servers": []}))).unwrap();
+        tx.try_send(Ok(serde_json::json!({"type": "system", "subtype": "init", "session_id": "x", "tools": [], "mcp_servers": []}))).unwrap();
 
         let stream = ResponseStream::new(rx);
         let messages = stream.receive_response().await.unwrap();
@@ -1784,6 +3251,923 @@ mod tests {
         }
     }
 
+    /// Builds the response payload for a control request, or `None` to never answer
+    type Responder = Arc<dyn Fn(&Value) -> Option<Value> + Send + Sync>;
+
+    /// Transport standing in for a CLI that answers control requests
+    struct FakeCli {
+        stdout: Arc<std::sync::Mutex<Option<MessageSender>>>,
+        rx: std::sync::Mutex<Option<MessageReceiver>>,
+        requests: Arc<std::sync::Mutex<Vec<Value>>>,
+        responder: Responder,
+    }
+
+    /// Test-side handle of a [`FakeCli`]
+    #[derive(Clone)]
+    struct FakeCliHandle {
+        stdout: Arc<std::sync::Mutex<Option<MessageSender>>>,
+        requests: Arc<std::sync::Mutex<Vec<Value>>>,
+    }
+
+    impl FakeCli {
+        /// CLI answering every control request with an empty success payload
+        fn new() -> Self {
+            Self::with_responder(|_| Some(serde_json::json!({})))
+        }
+
+        fn with_responder(
+            responder: impl Fn(&Value) -> Option<Value> + Send + Sync + 'static,
+        ) -> Self {
+            let (tx, rx) = mpsc::channel(64);
+            Self {
+                stdout: Arc::new(std::sync::Mutex::new(Some(tx))),
+                rx: std::sync::Mutex::new(Some(rx)),
+                requests: Arc::new(std::sync::Mutex::new(Vec::new())),
+                responder: Arc::new(responder),
+            }
+        }
+
+        fn handle(&self) -> FakeCliHandle {
+            FakeCliHandle {
+                stdout: self.stdout.clone(),
+                requests: self.requests.clone(),
+            }
+        }
+    }
+
+    impl FakeCliHandle {
+        /// Emit a message on the CLI's stdout
+        fn send(&self, value: Value) {
+            if let Some(tx) = self.stdout.lock().unwrap().as_ref() {
+                tx.try_send(Ok(value)).unwrap();
+            }
+        }
+
+        /// End stdout as if the process died
+        fn crash(&self) {
+            self.stdout.lock().unwrap().take();
+        }
+
+        /// Bodies of the control requests received with `subtype`
+        fn requests(&self, subtype: &str) -> Vec<Value> {
+            self.envelopes(subtype)
+                .into_iter()
+                .map(|envelope| envelope["request"].clone())
+                .collect()
+        }
+
+        /// Full `control_request` lines received with `subtype`
+        fn envelopes(&self, subtype: &str) -> Vec<Value> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|r| r["request"]["subtype"] == subtype)
+                .cloned()
+                .collect()
+        }
+
+        /// Answer a control request with a success payload
+        fn reply(&self, request_id: &Value, payload: Value) {
+            self.send(serde_json::json!({
+                "type": "control_response",
+                "response": {
+                    "subtype": "success",
+                    "request_id": request_id,
+                    "response": payload
+                }
+            }));
+        }
+
+        /// Wait until a control request with `subtype` has been received
+        async fn wait_for_request(&self, subtype: &str) -> Value {
+            loop {
+                if let Some(request) = self.requests(subtype).pop() {
+                    return request;
+                }
+                tokio::task::yield_now().await;
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for FakeCli {
+        async fn connect(&mut self) -> Result<(), ClawError> {
+            Ok(())
+        }
+
+        async fn write(&self, data: &[u8]) -> Result<(), ClawError> {
+            let value: Value = serde_json::from_slice(data)?;
+            if value["type"] != "control_request" {
+                return Ok(());
+            }
+            self.requests.lock().unwrap().push(value.clone());
+            if let Some(payload) = (self.responder)(&value["request"]) {
+                self.handle().reply(&value["request_id"], payload);
+            }
+            Ok(())
+        }
+
+        fn messages(&self) -> MessageReceiver {
+            self.rx.lock().unwrap().take().unwrap()
+        }
+
+        async fn end_input(&self) -> Result<(), ClawError> {
+            Ok(())
+        }
+
+        async fn close(&self) -> Result<(), ClawError> {
+            self.handle().crash();
+            Ok(())
+        }
+
+        fn is_ready(&self) -> bool {
+            self.stdout.lock().unwrap().is_some()
+        }
+    }
+
+    /// Client connected to a [`FakeCli`] that answers with `responder`
+    async fn connect_fake(
+        options: ClaudeAgentOptions,
+        cli: FakeCli,
+    ) -> (ClaudeClient, FakeCliHandle) {
+        let handle = cli.handle();
+        let mut client = ClaudeClient::with_transport(options, Box::new(cli)).unwrap();
+        client.connect().await.unwrap();
+        (client, handle)
+    }
+
+    /// interrupt_with() honors its own deadline and cancellation token
+    #[tokio::test]
+    async fn test_interrupt_with_deadline_and_cancellation() {
+        // The CLI never acknowledges interrupts
+        let cli = FakeCli::with_responder(|request| {
+            (request["subtype"] != "interrupt").then(|| serde_json::json!({}))
+        });
+        let (client, _cli) = connect_fake(ClaudeAgentOptions::default(), cli).await;
+
+        let err = client
+            .interrupt_with(Duration::from_millis(1), CancellationToken::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClawError::ControlTimeout { ref subtype } if subtype == "interrupt"));
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let err = client
+            .interrupt_with(Duration::from_secs(3600), cancel)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, ClawError::ControlCancelled { ref subtype } if subtype == "interrupt")
+        );
+    }
+
+    /// close() cancels permission handlers still waiting on the user
+    #[tokio::test]
+    async fn test_close_cancels_in_flight_handlers() {
+        use crate::permissions::{PermissionDecision, ToolPermissionContext};
+
+        struct PendingHandler {
+            tokens: mpsc::UnboundedSender<CancellationToken>,
+        }
+
+        #[async_trait::async_trait]
+        impl CanUseToolHandler for PendingHandler {
+            async fn can_use_tool(
+                &self,
+                _tool_name: &str,
+                _tool_input: &Value,
+                context: &ToolPermissionContext,
+            ) -> Result<PermissionDecision, ClawError> {
+                self.tokens.send(context.cancellation.clone()).unwrap();
+                std::future::pending().await
+            }
+        }
+
+        let (mut client, cli) = connect_fake(ClaudeAgentOptions::default(), FakeCli::new()).await;
+        let (tokens, mut received) = mpsc::unbounded_channel();
+        client
+            .register_can_use_tool_handler(Arc::new(PendingHandler { tokens }))
+            .await;
+
+        cli.send(serde_json::json!({
+            "type": "control_request",
+            "request_id": "prompt",
+            "request": { "subtype": "can_use_tool", "tool_name": "Bash", "input": { "command": "ls" } }
+        }));
+        let token = received.recv().await.unwrap();
+        assert!(!token.is_cancelled());
+
+        client.close().await.unwrap();
+        assert!(token.is_cancelled());
+    }
+
+    /// close() then connect() on the same client gets working recovery state,
+    /// subscribers and crash handling
+    #[tokio::test]
+    async fn test_reconnect_after_close_then_crash() {
+        use tokio_stream::StreamExt;
+
+        let (mut client, _first) =
+            connect_fake(ClaudeAgentOptions::default(), FakeCli::new()).await;
+        client.close().await.unwrap();
+        assert!(client.subscribe().next().await.is_none());
+
+        let cli = FakeCli::new();
+        let second = cli.handle();
+        client.pre_transport = Some(Box::new(cli));
+        client.connect().await.unwrap();
+        assert!(!client.closed.load(Ordering::SeqCst));
+
+        let mut events = client.subscribe();
+        let mut turn = client.send_message("hello").await.unwrap();
+        second.send(serde_json::json!({
+            "type": "system", "subtype": "init", "session_id": "s2", "tools": [], "mcp_servers": []
+        }));
+        assert!(matches!(events.next().await, Some(Ok(Message::System(_)))));
+        assert!(matches!(turn.next().await, Some(Ok(Message::System(_)))));
+
+        second.crash();
+        assert!(matches!(
+            turn.next().await,
+            Some(Err(ClawError::Connection(_)))
+        ));
+        assert!(events.next().await.is_none());
+        assert!(!client.is_connected());
+    }
+
+    /// Requests waiting on a crashed CLI fail right away, not after the recovery backoff
+    #[tokio::test]
+    async fn test_crash_fails_pending_requests_before_backoff() {
+        use crate::control::messages::ControlRequest;
+        use crate::options::RecoveryPolicy;
+
+        let cli = FakeCli::with_responder(|_| None);
+        let handle = cli.handle();
+        let rx = cli.messages();
+        let control = Arc::new(ControlProtocol::new(Arc::new(cli)));
+
+        let client =
+            ClaudeClient::new(ClaudeAgentOptions::builder().resume("sess-1").build()).unwrap();
+        client.is_initialized.store(true, Ordering::SeqCst);
+        let hour = Duration::from_secs(3600);
+        let respawn = ClaudeAgentOptions::builder()
+            .recovery(RecoveryPolicy::new(1).with_backoff(hour, hour))
+            .build();
+        ClaudeClient::spawn_message_router(rx, client.router_state(control.clone(), Some(respawn)));
+
+        let waiting = tokio::spawn({
+            let control = control.clone();
+            async move { control.request(ControlRequest::McpStatus).await }
+        });
+        handle.wait_for_request("mcp_status").await;
+        handle.crash();
+
+        let result = tokio::time::timeout(Duration::from_secs(5), waiting)
+            .await
+            .expect("request still waiting during recovery backoff")
+            .unwrap();
+        assert!(matches!(result, Err(ClawError::ControlError(_))));
+    }
+
+    /// CLI that rejects every MCP server whose name starts with "bad"
+    fn mcp_cli() -> FakeCli {
+        FakeCli::with_responder(|request| {
+            if request["subtype"] != "mcp_set_servers" {
+                return Some(serde_json::json!({}));
+            }
+            let servers = request["servers"].as_object().unwrap();
+            let (bad, good): (Vec<&String>, Vec<&String>) =
+                servers.keys().partition(|name| name.starts_with("bad"));
+            let errors: serde_json::Map<String, Value> = bad
+                .into_iter()
+                .map(|name| (name.clone(), Value::from("failed to start")))
+                .collect();
+            Some(serde_json::json!({"added": good, "errors": errors}))
+        })
+    }
+
+    fn runtime_mcp_names(client: &ClaudeClient) -> Vec<String> {
+        let registry = client.runtime_mcp_servers.lock().unwrap();
+        let mut names: Vec<String> = registry.servers.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    fn stdio_server(command: &str) -> McpServerConfig {
+        use crate::options::McpStdioServerConfig;
+
+        McpServerConfig::Stdio(McpStdioServerConfig {
+            command: command.to_string(),
+            args: vec![],
+            env: HashMap::new(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_runtime_mcp_servers_drop_rejected_servers() {
+        let (client, handle) = connect_fake(ClaudeAgentOptions::default(), mcp_cli()).await;
+
+        client
+            .add_mcp_server("github", stdio_server("github-mcp"))
+            .await
+            .unwrap();
+        let result = client
+            .add_mcp_server("bad-db", stdio_server("db-mcp"))
+            .await
+            .unwrap();
+        assert_eq!(result.errors["bad-db"], "failed to start");
+        assert_eq!(runtime_mcp_names(&client), vec!["github"]);
+
+        // The rejected server is not sent again with the next update
+        client
+            .add_mcp_server("docs", stdio_server("docs-mcp"))
+            .await
+            .unwrap();
+        let last = handle.requests("mcp_set_servers").pop().unwrap();
+        let mut sent: Vec<&String> = last["servers"].as_object().unwrap().keys().collect();
+        sent.sort();
+        assert_eq!(sent, vec!["docs", "github"]);
+    }
+
+    #[tokio::test]
+    async fn test_remove_runtime_mcp_server() {
+        let (client, handle) = connect_fake(ClaudeAgentOptions::default(), mcp_cli()).await;
+
+        client
+            .add_mcp_server("github", stdio_server("github-mcp"))
+            .await
+            .unwrap();
+        client.remove_mcp_server("github").await.unwrap();
+        assert!(runtime_mcp_names(&client).is_empty());
+        let last = handle.requests("mcp_set_servers").pop().unwrap();
+        assert_eq!(last["servers"], serde_json::json!({}));
+
+        assert!(matches!(
+            client.remove_mcp_server("github").await,
+            Err(ClawError::ControlError(_))
+        ));
+        assert_eq!(handle.requests("mcp_set_servers").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_sdk_mcp_server_restores_previous_registration() {
+        let (client, _handle) = connect_fake(ClaudeAgentOptions::default(), mcp_cli()).await;
+        let control = client.control.clone().unwrap();
+        control
+            .handlers()
+            .await
+            .register_sdk_mcp_server(SdkMcpServerImpl::new("bad-calc", "1.0.0"));
+
+        let result = client
+            .add_sdk_mcp_server(SdkMcpServerImpl::new("bad-calc", "2.0.0"))
+            .await
+            .unwrap();
+        assert!(result.errors.contains_key("bad-calc"));
+
+        let handlers = control.handlers().await;
+        assert_eq!(handlers.sdk_mcp_servers["bad-calc"].version, "1.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_mcp_updates_are_not_lost() {
+        // Leave the first mcp_set_servers unanswered until the second commits
+        let (client, handle) = connect_fake(
+            ClaudeAgentOptions::default(),
+            FakeCli::with_responder(|request| {
+                let first = request["subtype"] == "mcp_set_servers"
+                    && request["servers"].as_object().unwrap().contains_key("a")
+                    && request["servers"].as_object().unwrap().len() == 1;
+                (!first).then(|| serde_json::json!({}))
+            }),
+        )
+        .await;
+        let client = Arc::new(client);
+
+        let first = tokio::spawn({
+            let client = client.clone();
+            async move { client.add_mcp_server("a", stdio_server("a-mcp")).await }
+        });
+        let stalled = handle.wait_for_request("mcp_set_servers").await;
+        assert_eq!(stalled["servers"].as_object().unwrap().len(), 1);
+
+        // Not blocked by the update in flight
+        client
+            .add_mcp_server("b", stdio_server("b-mcp"))
+            .await
+            .unwrap();
+        assert_eq!(runtime_mcp_names(&client), vec!["b"]);
+
+        let envelope = handle.envelopes("mcp_set_servers").remove(0);
+        handle.reply(&envelope["request_id"], serde_json::json!({}));
+        first.await.unwrap().unwrap();
+
+        // The stale update was redone on top of "b"
+        assert_eq!(runtime_mcp_names(&client), vec!["a", "b"]);
+        let last = handle.requests("mcp_set_servers").pop().unwrap();
+        assert_eq!(last["servers"].as_object().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_runtime_mcp_servers_are_resent_after_recovery() {
+        let (client, handle) = connect_fake(ClaudeAgentOptions::default(), mcp_cli()).await;
+        client
+            .add_mcp_server("github", stdio_server("github-mcp"))
+            .await
+            .unwrap();
+        client
+            .add_sdk_mcp_server(SdkMcpServerImpl::new("calc", "1.0.0"))
+            .await
+            .unwrap();
+
+        // As reconnect() does once the respawned CLI is initialized
+        let state = client.router_state(client.control.clone().unwrap(), None);
+        ClaudeClient::restore_runtime_mcp_servers(&state).await;
+        while handle.requests("mcp_set_servers").len() < 3 {
+            tokio::task::yield_now().await;
+        }
+
+        let resent = handle.requests("mcp_set_servers").pop().unwrap();
+        assert_eq!(resent["servers"]["calc"]["type"], "sdk");
+        assert_eq!(resent["servers"]["github"]["command"], "github-mcp");
+        assert_eq!(runtime_mcp_names(&client), vec!["calc", "github"]);
+    }
+
+    #[test]
+    fn test_resume_options_keep_session_settings() {
+        let base = ClaudeAgentOptions::builder()
+            .model("claude-sonnet-4")
+            .permission_mode(PermissionMode::Default)
+            .continue_conversation(true)
+            .build();
+        let mut session = SessionState::new(&base);
+        assert!(resume_options(&base, &session).is_none());
+
+        // As after set_model() / set_permission_mode()
+        session.session_id = Some("sess-1".to_string());
+        session.model = Some("claude-opus-4".to_string());
+        session.permission_mode = Some(PermissionMode::AcceptEdits);
+
+        let options = resume_options(&base, &session).unwrap();
+        assert_eq!(options.resume.as_deref(), Some("sess-1"));
+        assert!(!options.continue_conversation);
+        assert_eq!(options.model.as_deref(), Some("claude-opus-4"));
+        assert_eq!(options.permission_mode, Some(PermissionMode::AcceptEdits));
+    }
+
+    /// Start a router for `client` over an unconnected transport, returning the
+    /// sender that stands in for the CLI's stdout
+    fn spawn_test_router(
+        client: &ClaudeClient,
+        respawn_options: Option<ClaudeAgentOptions>,
+    ) -> MessageSender {
+        use crate::transport::SubprocessCLITransport;
+
+        let (tx, rx) = mpsc::channel(16);
+        let transport: Arc<dyn Transport> = Arc::new(SubprocessCLITransport::new(None, vec![]));
+        let control = Arc::new(ControlProtocol::new(transport));
+        ClaudeClient::spawn_message_router(rx, client.router_state(control, respawn_options));
+        tx
+    }
+
+    #[tokio::test]
+    async fn test_router_tracks_session_id() {
+        use tokio_stream::StreamExt;
+
+        let client =
+            ClaudeClient::new(ClaudeAgentOptions::builder().resume("sess-old").build()).unwrap();
+        assert_eq!(client.session().session_id.as_deref(), Some("sess-old"));
+
+        let tx = spawn_test_router(&client, None);
+        tx.try_send(Ok(serde_json::json!({
+            "type": "system", "subtype": "init", "session_id": "sess-new", "tools": [], "mcp_servers": []
+        })))
+        .unwrap();
+        drop(tx);
+
+        // The subscriber stream ends once the router has drained the channel
+        let mut events = client.subscribe();
+        while events.next().await.is_some() {}
+        assert_eq!(client.session().session_id.as_deref(), Some("sess-new"));
+    }
+
+    #[test]
+    fn test_session_state_before_connect_reflects_options() {
+        let options = ClaudeAgentOptions::builder()
+            .resume("sess-1")
+            .model("claude-sonnet-4")
+            .permission_mode(PermissionMode::Plan)
+            .build();
+        let session = ClaudeClient::new(options).unwrap().session();
+        assert_eq!(session.session_id.as_deref(), Some("sess-1"));
+        assert_eq!(session.model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(session.permission_mode, Some(PermissionMode::Plan));
+        assert_eq!(session.turn_count, 0);
+        assert_eq!(session.total_cost_usd, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_router_updates_session_state() {
+        use tokio_stream::StreamExt;
+
+        let client = ClaudeClient::new(ClaudeAgentOptions::default()).unwrap();
+        let tx = spawn_test_router(&client, None);
+        for value in [
+            serde_json::json!({
+                "type": "system",
+                "subtype": "init",
+                "session_id": "sess-7",
+                "tools": ["Read", "Bash"],
+                "mcp_servers": [{"name": "files", "status": "connected"}],
+                "model": "claude-opus-4",
+                "permissionMode": "acceptEdits"
+            }),
+            serde_json::json!({
+                "type": "result",
+                "subtype": "success",
+                "result": "one",
+                "total_cost_usd": 0.25,
+                "usage": {"input_tokens": 100, "output_tokens": 10}
+            }),
+            serde_json::json!({
+                "type": "result",
+                "subtype": "error",
+                "error": "max turns",
+                "total_cost_usd": 0.5,
+                "usage": {"input_tokens": 50, "output_tokens": 5}
+            }),
+            serde_json::json!({
+                "type": "result",
+                "subtype": "error_max_budget_usd",
+                "total_cost_usd": 0.25,
+                "usage": {"input_tokens": 10, "output_tokens": 1, "cache_read_input_tokens": 40}
+            }),
+        ] {
+            tx.try_send(Ok(value)).unwrap();
+        }
+        drop(tx);
+
+        let mut events = client.subscribe();
+        while events.next().await.is_some() {}
+
+        let session = client.session();
+        assert_eq!(session.session_id.as_deref(), Some("sess-7"));
+        assert_eq!(session.model.as_deref(), Some("claude-opus-4"));
+        assert_eq!(session.permission_mode, Some(PermissionMode::AcceptEdits));
+        assert_eq!(session.tools.len(), 2);
+        assert_eq!(session.mcp_servers[0].name, "files");
+        assert_eq!(session.turn_count, 3);
+        assert_eq!(session.total_cost_usd, 1.0);
+        assert_eq!(
+            session.usage,
+            UsageInfo {
+                input_tokens: 160,
+                output_tokens: 16,
+                cache_read_input_tokens: Some(40),
+                ..Default::default()
+            }
+        );
+    }
+
+    /// Under OverflowPolicy::Error a full turn channel yields the buffered
+    /// messages, then ChannelOverflow, then ends
+    #[tokio::test]
+    async fn test_turn_channel_overflow_error_policy() {
+        use crate::options::OverflowPolicy;
+        use tokio_stream::StreamExt;
+
+        let options = ClaudeAgentOptions::builder()
+            .channel_capacity(1)
+            .overflow_policy(OverflowPolicy::Error)
+            .build();
+        let client = ClaudeClient::new(options).unwrap();
+        let (turn, mut stream) = turn_channel(&client.options.channel);
+        *client.current_turn_tx.lock().await = Some(turn);
+        let mut events = client.subscribe();
+
+        let tx = spawn_test_router(&client, None);
+        for retry_after in 1..=3 {
+            tx.send(Ok(
+                serde_json::json!({"type": "rate_limit_event", "retry_after": retry_after}),
+            ))
+            .await
+            .unwrap();
+        }
+        // Once the third message reaches subscribers, the second was already
+        // forwarded. The subscriber shares the capacity of 1, so it lags first.
+        while let Some(event) = events.next().await {
+            if let Ok(Message::RateLimitEvent(event)) = event
+                && event.extra["retry_after"] == 3
+            {
+                break;
+            }
+        }
+
+        assert!(matches!(
+            stream.next().await,
+            Some(Ok(Message::RateLimitEvent(_)))
+        ));
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(ClawError::ChannelOverflow { capacity: 1 }))
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    /// A CLI exit without close() surfaces an error on the in-flight turn
+    #[tokio::test]
+    async fn test_unexpected_exit_ends_turn_with_error() {
+        use tokio_stream::StreamExt;
+
+        let client = ClaudeClient::new(ClaudeAgentOptions::default()).unwrap();
+        client.is_initialized.store(true, Ordering::SeqCst);
+        let (turn, mut turn_rx) = turn_channel(&ChannelConfig::default());
+        *client.current_turn_tx.lock().await = Some(turn);
+
+        // Recovery is enabled but cannot run: no session ID was ever reported
+        let options = ClaudeAgentOptions::builder()
+            .recovery(crate::options::RecoveryPolicy::new(1))
+            .build();
+        let tx = spawn_test_router(&client, Some(options));
+        drop(tx);
+
+        assert!(matches!(
+            turn_rx.next().await,
+            Some(Err(ClawError::Connection(_)))
+        ));
+        assert!(turn_rx.next().await.is_none());
+        assert!(!client.is_initialized.load(Ordering::SeqCst));
+    }
+
+    /// A transport that stopped on overflow ends the turn with ChannelOverflow
+    /// instead of being treated as a crash
+    #[tokio::test]
+    async fn test_transport_overflow_is_not_a_crash() {
+        use tokio_stream::StreamExt;
+
+        let client =
+            ClaudeClient::new(ClaudeAgentOptions::builder().resume("sess-1").build()).unwrap();
+        client.is_initialized.store(true, Ordering::SeqCst);
+        let (turn, mut turn_rx) = turn_channel(&ChannelConfig::default());
+        *client.current_turn_tx.lock().await = Some(turn);
+
+        let options = ClaudeAgentOptions::builder()
+            .recovery(crate::options::RecoveryPolicy::new(1))
+            .build();
+        let tx = spawn_test_router(&client, Some(options));
+        tx.send(Err(ClawError::ChannelOverflow { capacity: 4 }))
+            .await
+            .unwrap();
+        drop(tx);
+
+        assert!(matches!(
+            turn_rx.next().await,
+            Some(Err(ClawError::ChannelOverflow { capacity: 4 }))
+        ));
+        assert!(turn_rx.next().await.is_none());
+        assert!(!client.is_initialized.load(Ordering::SeqCst));
+    }
+
+    /// A full, undrained turn does not stop control responses from being routed
+    #[tokio::test]
+    async fn test_full_turn_does_not_block_control_responses() {
+        let options = ClaudeAgentOptions::builder().channel_capacity(1).build();
+        let (client, cli) = connect_fake(options, FakeCli::new()).await;
+
+        let _turn = client.send_message("hello").await.unwrap();
+        for _ in 0..4 {
+            cli.send(serde_json::json!({"type": "rate_limit_event"}));
+        }
+
+        tokio::time::timeout(Duration::from_secs(5), client.interrupt())
+            .await
+            .expect("interrupt() stuck behind the undrained turn")
+            .unwrap();
+    }
+
+    /// Under OverflowPolicy::Block a full turn stops the router from reading
+    /// the transport, so backpressure reaches the stdout reader; the turn
+    /// still receives every message in order
+    #[tokio::test]
+    async fn test_full_turn_applies_backpressure() {
+        use tokio::sync::mpsc::error::TrySendError;
+        use tokio_stream::StreamExt;
+
+        let client =
+            ClaudeClient::new(ClaudeAgentOptions::builder().channel_capacity(1).build()).unwrap();
+        let (turn, mut stream) = turn_channel(&client.options.channel);
+        *client.current_turn_tx.lock().await = Some(turn);
+
+        let tx = spawn_test_router(&client, None);
+        let mut sent = 0;
+        while sent < 100 {
+            let event = serde_json::json!({"type": "rate_limit_event", "retry_after": sent});
+            match tx.try_send(Ok(event)) {
+                Ok(()) => sent += 1,
+                Err(TrySendError::Full(_)) => break,
+                Err(TrySendError::Closed(_)) => panic!("router stopped"),
+            }
+            tokio::task::yield_now().await;
+        }
+        assert!(sent < 100, "router kept reading past a full turn");
+        drop(tx);
+
+        for expected in 0..sent {
+            match stream.next().await {
+                Some(Ok(Message::RateLimitEvent(event))) => {
+                    assert_eq!(event.extra["retry_after"], expected)
+                }
+                other => panic!("Expected RateLimitEvent, got {:?}", other),
+            }
+        }
+        assert!(stream.next().await.is_none());
+    }
+
+    /// An error that ends a full turn does not wait for the consumer; the
+    /// stream yields it after the buffered messages
+    #[tokio::test]
+    async fn test_turn_end_error_follows_buffered_messages() {
+        use tokio_stream::StreamExt;
+
+        let client =
+            ClaudeClient::new(ClaudeAgentOptions::builder().channel_capacity(1).build()).unwrap();
+        client.is_initialized.store(true, Ordering::SeqCst);
+        let (turn, mut stream) = turn_channel(&client.options.channel);
+        *client.current_turn_tx.lock().await = Some(turn);
+
+        let tx = spawn_test_router(&client, None);
+        for retry_after in 0..3 {
+            tx.send(Ok(
+                serde_json::json!({"type": "rate_limit_event", "retry_after": retry_after}),
+            ))
+            .await
+            .unwrap();
+        }
+        drop(tx);
+
+        for expected in 0..3 {
+            match stream.next().await {
+                Some(Ok(Message::RateLimitEvent(event))) => {
+                    assert_eq!(event.extra["retry_after"], expected)
+                }
+                other => panic!("Expected RateLimitEvent, got {:?}", other),
+            }
+        }
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(ClawError::Connection(_)))
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    /// Messages are broadcast to every subscriber, including between turns,
+    /// and subscriber streams end when the transport closes.
+    #[tokio::test]
+    async fn test_subscribe_receives_all_session_messages() {
+        use tokio_stream::StreamExt;
+
+        let client = ClaudeClient::new(ClaudeAgentOptions::default()).unwrap();
+        let mut log = client.subscribe();
+        let mut ui = client.subscribe();
+
+        let tx = spawn_test_router(&client, None);
+
+        // No turn is active, so these would be dropped for a ResponseStream
+        tx.try_send(Ok(serde_json::json!({
+            "type": "system", "subtype": "init", "session_id": "s1", "tools": [], "mcp_servers": []
+        })))
+        .unwrap();
+        tx.try_send(Ok(
+            serde_json::json!({"type": "rate_limit_event", "retry_after": 3}),
+        ))
+        .unwrap();
+        drop(tx);
+
+        for stream in [&mut log, &mut ui] {
+            assert!(matches!(stream.next().await, Some(Ok(Message::System(_)))));
+            assert!(matches!(
+                stream.next().await,
+                Some(Ok(Message::RateLimitEvent(_)))
+            ));
+            assert!(stream.next().await.is_none());
+        }
+
+        // Subscribing after the session ended yields a closed stream
+        assert!(client.subscribe().next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_stream_reports_lag() {
+        use tokio_stream::StreamExt;
+
+        let (tx, rx) = broadcast::channel(1);
+        let mut stream = SessionStream::new(rx);
+        for _ in 0..3 {
+            tx.send(Message::RateLimitEvent(RateLimitEvent::default()))
+                .unwrap();
+        }
+
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(ClawError::SubscriberLagged { skipped: 2 }))
+        ));
+        assert!(matches!(
+            stream.next().await,
+            Some(Ok(Message::RateLimitEvent(_)))
+        ));
+    }
+
+    /// Client with `policy` whose router has recorded a rejected limit resetting in `secs`
+    fn rate_limited_client(policy: RateLimitPolicy, secs: u64) -> ClaudeClient {
+        let options = ClaudeAgentOptions::builder()
+            .rate_limit_policy(policy)
+            .build();
+        let client = ClaudeClient::new(options).unwrap();
+        record_rate_limit(&client, "five_hour", "rejected", secs);
+        client
+    }
+
+    /// Record a `limit` event with `status` resetting in `secs`
+    fn record_rate_limit(client: &ClaudeClient, limit: &str, status: &str, secs: u64) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let event: Message = serde_json::from_value(serde_json::json!({
+            "type": "rate_limit_event",
+            "rate_limit_info": {
+                "status": status,
+                "rateLimitType": limit,
+                "resetsAt": now + secs
+            }
+        }))
+        .unwrap();
+        client.record_session(&event);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_policy_waits_for_reset() {
+        let client = rate_limited_client(RateLimitPolicy::wait(Duration::from_secs(3600)), 60);
+        let start = tokio::time::Instant::now();
+        client.wait_for_rate_limit().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_secs(58));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_policy_fails_fast() {
+        let client = rate_limited_client(RateLimitPolicy::FailFast, 120);
+        match client.wait_for_rate_limit().await {
+            Err(ClawError::RateLimited { limit, retry_after }) => {
+                assert_eq!(limit, "five_hour");
+                assert!(retry_after > Duration::from_secs(100));
+            }
+            other => panic!("Expected RateLimited, got {:?}", other),
+        }
+
+        // A wait longer than max_wait also fails instead of sleeping
+        let client = rate_limited_client(RateLimitPolicy::wait(Duration::from_secs(10)), 120);
+        assert!(matches!(
+            client.wait_for_rate_limit().await,
+            Err(ClawError::RateLimited { .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_policy_tracks_each_limit_type() {
+        let client = rate_limited_client(RateLimitPolicy::wait(Duration::from_secs(3600)), 60);
+        record_rate_limit(&client, "seven_day", "rejected", 600);
+
+        // An allowed five_hour update leaves the seven_day rejection in place
+        record_rate_limit(&client, "five_hour", "allowed", 0);
+        assert_eq!(client.session().rate_limits.len(), 2);
+        let start = tokio::time::Instant::now();
+        client.wait_for_rate_limit().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_secs(598));
+
+        // With several blocking limits the latest reset wins
+        let client = rate_limited_client(RateLimitPolicy::FailFast, 60);
+        record_rate_limit(&client, "seven_day_opus", "rejected", 600);
+        match client.wait_for_rate_limit().await {
+            Err(ClawError::RateLimited { limit, retry_after }) => {
+                assert_eq!(limit, "seven_day_opus");
+                assert!(retry_after > Duration::from_secs(500));
+            }
+            other => panic!("Expected RateLimited, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_policy_ignores_non_blocking_limits() {
+        // Reset already passed
+        let client = rate_limited_client(RateLimitPolicy::FailFast, 0);
+        client.wait_for_rate_limit().await.unwrap();
+
+        // No policy configured
+        let client = ClaudeClient::new(ClaudeAgentOptions::default()).unwrap();
+        client.wait_for_rate_limit().await.unwrap();
+    }
+
     /// Test with_transport constructor
     #[test]
     fn test_with_transport_constructor() {
@@ -11,7 +11,7 @@
 //!
 //! ```
 //! use rusty_claw::control::handlers::{CanUseToolHandler, ControlHandlers};
-//! use rusty_claw::permissions::PermissionDecision;
+//! use rusty_claw::permissions::{PermissionDecision, ToolPermissionContext};
 //! use rusty_claw::error::ClawError;
 //! use async_trait::async_trait;
 //! use std::sync::Arc;
@@ -24,12 +24,13 @@
 //!         &self,
 //!         tool_name: &str,
 //!         _tool_input: &serde_json::Value,
+//!         _context: &ToolPermissionContext,
 //!     ) -> Result<PermissionDecision, ClawError> {
 //!         // Only allow Read and Grep tools
 //!         if matches!(tool_name, "Read" | "Grep") {
-//!             Ok(PermissionDecision::Allow { updated_input: None })
+//!             Ok(PermissionDecision::allow())
 //!         } else {
-//!             Ok(PermissionDecision::Deny { interrupt: false })
+//!             Ok(PermissionDecision::deny("Only Read and Grep are allowed"))
 //!         }
 //!     }
 //! }
@@ -44,8 +45,9 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::error::ClawError;
+use crate::mcp_server::SdkMcpServerImpl;
 use crate::options::HookEvent;
-use crate::permissions::PermissionDecision;
+use crate::permissions::{PermissionDecision, ToolPermissionContext};
 
 /// Handler for can_use_tool permission callbacks
 ///
@@ -65,7 +67,7 @@ use crate::permissions::PermissionDecision;
 ///
 /// ```
 /// use rusty_claw::control::handlers::CanUseToolHandler;
-/// use rusty_claw::permissions::PermissionDecision;
+/// use rusty_claw::permissions::{PermissionDecision, ToolPermissionContext};
 /// use rusty_claw::error::ClawError;
 /// use async_trait::async_trait;
 ///
@@ -77,12 +79,13 @@ use crate::permissions::PermissionDecision;
 ///         &self,
 ///         tool_name: &str,
 ///         _tool_input: &serde_json::Value,
+///         _context: &ToolPermissionContext,
 ///     ) -> Result<PermissionDecision, ClawError> {
 ///         // Only allow read-only tools
 ///         if matches!(tool_name, "Read" | "Grep" | "Glob") {
-///             Ok(PermissionDecision::Allow { updated_input: None })
+///             Ok(PermissionDecision::allow())
 ///         } else {
-///             Ok(PermissionDecision::Deny { interrupt: false })
+///             Ok(PermissionDecision::deny("This session is read-only"))
 ///         }
 ///     }
 /// }
@@ -95,18 +98,21 @@ pub trait CanUseToolHandler: Send + Sync {
     ///
     /// * `tool_name` - Name of the tool being invoked (e.g., "Bash", "Read")
     /// * `tool_input` - Tool input parameters as JSON
+    /// * `context` - Rule suggestions, blocked path, tool use ID, and agent ID
+    ///   sent by the CLI
     ///
     /// # Returns
     ///
-    /// * `Ok(PermissionDecision::Allow { updated_input })` - Allow tool execution,
-    ///   optionally with a modified input value
-    /// * `Ok(PermissionDecision::Deny { interrupt })` - Deny tool execution,
-    ///   optionally interrupting the entire session
-    /// * `Err(...)` - Handler error (tool will be denied)
+    /// * `Ok(PermissionDecision::Allow { updated_input, updated_permissions })` - Allow
+    ///   tool execution, optionally with a modified input value and permission rule updates
+    /// * `Ok(PermissionDecision::Deny { message, interrupt, updated_permissions })` - Deny
+    ///   tool execution with a message for the model, optionally interrupting the entire session
+    /// * `Err(...)` - Handler error (tool is denied with the error text as the message)
     async fn can_use_tool(
         &self,
         tool_name: &str,
         tool_input: &Value,
+        context: &ToolPermissionContext,
     ) -> Result<PermissionDecision, ClawError>;
 }
 
@@ -219,7 +225,9 @@ pub trait McpMessageHandler: Send + Sync {
 ///
 /// - **can_use_tool**: Allow all tools by default
 /// - **hooks**: Return error if hook is invoked
-/// - **mcp_message**: Return error if MCP message is received
+/// - **mcp_message**: Return error if MCP message is received for a server that
+///   is neither registered with [`register_sdk_mcp_server`](Self::register_sdk_mcp_server)
+///   nor handled by an [`McpMessageHandler`]
 ///
 /// # Example
 ///
@@ -241,6 +249,11 @@ pub struct ControlHandlers {
 
     /// Handler for MCP message routing
     pub(crate) mcp_message: Option<Arc<dyn McpMessageHandler>>,
+
+    /// SDK-hosted MCP servers added during the session, keyed by name
+    ///
+    /// Checked before `mcp_message` when routing MCP messages.
+    pub(crate) sdk_mcp_servers: HashMap<String, Arc<SdkMcpServerImpl>>,
 }
 
 impl ControlHandlers {
@@ -258,7 +271,7 @@ impl ControlHandlers {
     ///
     /// ```
     /// use rusty_claw::control::handlers::{CanUseToolHandler, ControlHandlers};
-    /// use rusty_claw::permissions::PermissionDecision;
+    /// use rusty_claw::permissions::{PermissionDecision, ToolPermissionContext};
     /// use rusty_claw::error::ClawError;
     /// use async_trait::async_trait;
     /// use std::sync::Arc;
@@ -271,11 +284,12 @@ impl ControlHandlers {
     ///         &self,
     ///         tool_name: &str,
     ///         _tool_input: &serde_json::Value,
+    ///         _context: &ToolPermissionContext,
     ///     ) -> Result<PermissionDecision, ClawError> {
     ///         if tool_name != "Bash" {
-    ///             Ok(PermissionDecision::Allow { updated_input: None })
+    ///             Ok(PermissionDecision::allow())
     ///         } else {
-    ///             Ok(PermissionDecision::Deny { interrupt: false })
+    ///             Ok(PermissionDecision::deny("Bash is disabled"))
     ///         }
     ///     }
     /// }
@@ -360,6 +374,37 @@ impl ControlHandlers {
     pub fn register_mcp_message(&mut self, handler: Arc<dyn McpMessageHandler>) {
         self.mcp_message = Some(handler);
     }
+
+    /// Register an SDK-hosted MCP server
+    ///
+    /// MCP messages addressed to `server.name` are routed to this server
+    /// instead of the [`McpMessageHandler`]. Replaces any server registered
+    /// under the same name and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusty_claw::control::handlers::ControlHandlers;
+    /// use rusty_claw::mcp_server::SdkMcpServerImpl;
+    ///
+    /// let mut handlers = ControlHandlers::new();
+    /// handlers.register_sdk_mcp_server(SdkMcpServerImpl::new("calc", "1.0.0"));
+    /// assert!(handlers.remove_sdk_mcp_server("calc"));
+    /// ```
+    pub fn register_sdk_mcp_server(
+        &mut self,
+        server: impl Into<Arc<SdkMcpServerImpl>>,
+    ) -> Option<Arc<SdkMcpServerImpl>> {
+        let server = server.into();
+        self.sdk_mcp_servers.insert(server.name.clone(), server)
+    }
+
+    /// Remove an SDK-hosted MCP server
+    ///
+    /// Returns `true` if a server with that name was registered.
+    pub fn remove_sdk_mcp_server(&mut self, name: &str) -> bool {
+        self.sdk_mcp_servers.remove(name).is_some()
+    }
 }
 
 #[cfg(test)]
@@ -377,13 +422,15 @@ mod tests {
             &self,
             tool_name: &str,
             _tool_input: &Value,
+            _context: &ToolPermissionContext,
         ) -> Result<PermissionDecision, ClawError> {
             if tool_name == "Read" {
                 Ok(PermissionDecision::Allow {
                     updated_input: None,
+                    updated_permissions: vec![],
                 })
             } else {
-                Ok(PermissionDecision::Deny { interrupt: false })
+                Ok(PermissionDecision::deny("Only Read is allowed"))
             }
         }
     }
@@ -414,11 +461,17 @@ mod tests {
     async fn test_can_use_tool_handler() {
         let handler = MockCanUseToolHandler;
         assert!(matches!(
-            handler.can_use_tool("Read", &json!({})).await.unwrap(),
+            handler
+                .can_use_tool("Read", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap(),
             PermissionDecision::Allow { .. }
         ));
         assert!(matches!(
-            handler.can_use_tool("Bash", &json!({})).await.unwrap(),
+            handler
+                .can_use_tool("Bash", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap(),
             PermissionDecision::Deny { .. }
         ));
     }
@@ -475,6 +528,17 @@ mod tests {
         assert!(handlers.mcp_message.is_some());
     }
 
+    #[test]
+    fn test_handlers_register_sdk_mcp_server() {
+        use crate::mcp_server::SdkMcpServerImpl;
+
+        let mut handlers = ControlHandlers::new();
+        handlers.register_sdk_mcp_server(SdkMcpServerImpl::new("calc", "1.0.0"));
+        assert!(handlers.sdk_mcp_servers.contains_key("calc"));
+        assert!(handlers.remove_sdk_mcp_server("calc"));
+        assert!(!handlers.remove_sdk_mcp_server("calc"));
+    }
+
     #[tokio::test]
     async fn test_permission_decision_allow_with_updated_input() {
         #[derive(Debug)]
@@ -486,21 +550,27 @@ mod tests {
                 &self,
                 _tool_name: &str,
                 _tool_input: &Value,
+                _context: &ToolPermissionContext,
             ) -> Result<PermissionDecision, ClawError> {
                 // Return sanitized input
                 Ok(PermissionDecision::Allow {
                     updated_input: Some(json!({ "command": "echo safe" })),
+                    updated_permissions: vec![],
                 })
             }
         }
 
         let handler = SanitizingHandler;
         let decision = handler
-            .can_use_tool("Bash", &json!({ "command": "rm -rf /" }))
+            .can_use_tool(
+                "Bash",
+                &json!({ "command": "rm -rf /" }),
+                &ToolPermissionContext::default(),
+            )
             .await
             .unwrap();
         match decision {
-            PermissionDecision::Allow { updated_input } => {
+            PermissionDecision::Allow { updated_input, .. } => {
                 assert!(updated_input.is_some());
                 assert_eq!(updated_input.unwrap()["command"], "echo safe");
             }
@@ -519,16 +589,27 @@ mod tests {
                 &self,
                 _tool_name: &str,
                 _tool_input: &Value,
+                _context: &ToolPermissionContext,
             ) -> Result<PermissionDecision, ClawError> {
-                Ok(PermissionDecision::Deny { interrupt: true })
+                Ok(PermissionDecision::Deny {
+                    message: "Dangerous command detected".to_string(),
+                    interrupt: true,
+                    updated_permissions: vec![],
+                })
             }
         }
 
         let handler = InterruptingHandler;
-        let decision = handler.can_use_tool("Bash", &json!({})).await.unwrap();
+        let decision = handler
+            .can_use_tool("Bash", &json!({}), &ToolPermissionContext::default())
+            .await
+            .unwrap();
         match decision {
-            PermissionDecision::Deny { interrupt } => {
+            PermissionDecision::Deny {
+                message, interrupt, ..
+            } => {
                 assert!(interrupt);
+                assert_eq!(message, "Dangerous command detected");
             }
             PermissionDecision::Allow { .. } => panic!("Expected Deny"),
         }
@@ -4,6 +4,9 @@
 //! - [`ControlRequest`] - Outgoing requests from SDK to CLI
 //! - [`ControlResponse`] - Responses to control requests
 //! - [`IncomingControlRequest`] - Incoming requests from CLI to SDK
+//! - [`InitializeResponse`], [`ServerInfo`], [`McpStatusResponse`], [`McpSetServersResponse`] -
+//!   Typed payloads of successful `initialize`, `get_server_info`, `mcp_status`, and
+//!   `mcp_set_servers` responses
 //!
 //! # Message Flow
 //!
@@ -38,7 +41,73 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
-use crate::options::{AgentDefinition, HookEvent, HookMatcher};
+use crate::messages::{McpServerStatus, ToolInfo};
+use crate::options::{AgentDefinition, HookEvent, HookMatcher, McpServerConfig};
+use crate::permissions::ToolPermissionContext;
+
+/// Hook matcher entry as sent to the CLI in the `initialize` request
+///
+/// Each entry tells the CLI which tools a hook applies to and which
+/// SDK-side callback IDs to invoke (via `hook_callback` requests) when it fires.
+///
+/// # Example
+/// ```json
+/// {
+///   "matcher": "Bash",
+///   "hookCallbackIds": ["hook_0"],
+///   "timeout": 5.0
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HookMatcherConfig {
+    /// Tool name pattern understood by the CLI (`None` matches all tools)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub matcher: Option<String>,
+
+    /// Callback IDs the CLI sends back in `hook_callback` requests
+    #[serde(rename = "hookCallbackIds", default)]
+    pub hook_callback_ids: Vec<String>,
+
+    /// Hook timeout in seconds
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timeout: Option<f64>,
+}
+
+impl HookMatcherConfig {
+    /// Build the wire form of a [`HookMatcher`] with the given callback IDs
+    ///
+    /// Glob patterns (`mcp__*`) are translated to the anchored regex syntax the
+    /// CLI matches tool names with, and `timeout_ms` is converted to seconds.
+    pub fn from_matcher(matcher: &HookMatcher, hook_callback_ids: Vec<String>) -> Self {
+        Self {
+            matcher: matcher.tool_name.as_deref().map(glob_to_cli_pattern),
+            hook_callback_ids,
+            timeout: matcher.timeout_ms.map(|ms| ms as f64 / 1000.0),
+        }
+    }
+}
+
+/// Translate a `HookMatcher` glob into a CLI matcher pattern.
+///
+/// Plain names and the lone `*` wildcard are passed through unchanged.
+fn glob_to_cli_pattern(pattern: &str) -> String {
+    if pattern == "*" || !pattern.contains('*') {
+        return pattern.to_string();
+    }
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '\\' | '.' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '^' | '$' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            _ => regex.push(c),
+        }
+    }
+    regex.push('$');
+    regex
+}
 
 /// Outgoing control requests from SDK to Claude CLI
 ///
@@ -68,15 +137,17 @@ pub enum ControlRequest {
     /// ```json
     /// {
     ///   "subtype": "initialize",
-    ///   "hooks": {},
+    ///   "hooks": {
+    ///     "PreToolUse": [{ "matcher": "Bash", "hookCallbackIds": ["hook_0"] }]
+    ///   },
     ///   "agents": {},
-    ///   "sdk_mcp_servers": []
+    ///   "sdkMcpServers": []
     /// }
     /// ```
     Initialize {
         /// Hook event matchers for callback registration
         #[serde(skip_serializing_if = "HashMap::is_empty", default)]
-        hooks: HashMap<HookEvent, Vec<HookMatcher>>,
+        hooks: HashMap<HookEvent, Vec<HookMatcherConfig>>,
 
         /// Agent definitions for spawning subagents
         #[serde(skip_serializing_if = "HashMap::is_empty", default)]
@@ -160,6 +231,154 @@ pub enum ControlRequest {
     /// Returns version and capability information from the connected CLI process.
     /// The response data includes at least a `"version"` field.
     GetServerInfo,
+
+    /// Replace the set of MCP servers added during the session
+    ///
+    /// Servers configured at spawn time (`--mcp-config`, settings files) are
+    /// not affected. Servers missing from `servers` that were added by an
+    /// earlier `mcp_set_servers` request are disconnected and removed.
+    ///
+    /// # Example
+    /// ```json
+    /// {
+    ///   "subtype": "mcp_set_servers",
+    ///   "servers": {
+    ///     "github": { "type": "stdio", "command": "github-mcp" },
+    ///     "calc": { "type": "sdk", "name": "calc" }
+    ///   }
+    /// }
+    /// ```
+    McpSetServers {
+        /// Complete set of session-scoped servers, keyed by name
+        servers: HashMap<String, RuntimeMcpServer>,
+    },
+
+    /// Reconnect an MCP server (e.g., after it failed or crashed)
+    ///
+    /// # Example
+    /// ```json
+    /// {
+    ///   "subtype": "mcp_reconnect",
+    ///   "serverName": "github"
+    /// }
+    /// ```
+    McpReconnect {
+        /// Name of the server to reconnect
+        #[serde(rename = "serverName")]
+        server_name: String,
+    },
+
+    /// Enable or disable an MCP server without removing its configuration
+    ///
+    /// Disabled servers are disconnected and their tools are hidden from the model.
+    ///
+    /// # Example
+    /// ```json
+    /// {
+    ///   "subtype": "mcp_toggle",
+    ///   "serverName": "github",
+    ///   "enabled": false
+    /// }
+    /// ```
+    McpToggle {
+        /// Name of the server to toggle
+        #[serde(rename = "serverName")]
+        server_name: String,
+        /// Whether the server should be enabled
+        enabled: bool,
+    },
+}
+
+impl ControlRequest {
+    /// Subtype of this request, without its payload
+    pub fn kind(&self) -> ControlSubtype {
+        match self {
+            ControlRequest::Initialize { .. } => ControlSubtype::Initialize,
+            ControlRequest::Interrupt => ControlSubtype::Interrupt,
+            ControlRequest::SetPermissionMode { .. } => ControlSubtype::SetPermissionMode,
+            ControlRequest::SetModel { .. } => ControlSubtype::SetModel,
+            ControlRequest::McpStatus => ControlSubtype::McpStatus,
+            ControlRequest::RewindFiles { .. } => ControlSubtype::RewindFiles,
+            ControlRequest::GetServerInfo => ControlSubtype::GetServerInfo,
+            ControlRequest::McpSetServers { .. } => ControlSubtype::McpSetServers,
+            ControlRequest::McpReconnect { .. } => ControlSubtype::McpReconnect,
+            ControlRequest::McpToggle { .. } => ControlSubtype::McpToggle,
+        }
+    }
+
+    /// Wire subtype of this request (e.g., `"interrupt"`)
+    pub fn subtype(&self) -> &'static str {
+        self.kind().as_str()
+    }
+}
+
+/// Subtype of an outgoing [`ControlRequest`]
+///
+/// Keys per-request settings such as
+/// [`ControlTimeouts`](crate::options::ControlTimeouts), so a misspelled
+/// subtype is a compile error rather than a silently ignored setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControlSubtype {
+    /// `initialize`
+    Initialize,
+    /// `interrupt`
+    Interrupt,
+    /// `set_permission_mode`
+    SetPermissionMode,
+    /// `set_model`
+    SetModel,
+    /// `mcp_status`
+    McpStatus,
+    /// `rewind_files`
+    RewindFiles,
+    /// `get_server_info`
+    GetServerInfo,
+    /// `mcp_set_servers`
+    McpSetServers,
+    /// `mcp_reconnect`
+    McpReconnect,
+    /// `mcp_toggle`
+    McpToggle,
+}
+
+impl ControlSubtype {
+    /// Wire name of the subtype (e.g., `"interrupt"`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ControlSubtype::Initialize => "initialize",
+            ControlSubtype::Interrupt => "interrupt",
+            ControlSubtype::SetPermissionMode => "set_permission_mode",
+            ControlSubtype::SetModel => "set_model",
+            ControlSubtype::McpStatus => "mcp_status",
+            ControlSubtype::RewindFiles => "rewind_files",
+            ControlSubtype::GetServerInfo => "get_server_info",
+            ControlSubtype::McpSetServers => "mcp_set_servers",
+            ControlSubtype::McpReconnect => "mcp_reconnect",
+            ControlSubtype::McpToggle => "mcp_toggle",
+        }
+    }
+}
+
+/// MCP server configuration sent in an `mcp_set_servers` request
+///
+/// External servers use their [`McpServerConfig`] wire form; SDK-hosted
+/// servers are referenced by name and served in-process through
+/// `mcp_message` requests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuntimeMcpServer {
+    /// Stdio, SSE, or HTTP server managed by the CLI
+    External(McpServerConfig),
+    /// SDK-hosted server, serialized as `{"type": "sdk", "name": "..."}`
+    Sdk(SdkMcpServerRef),
+}
+
+/// Reference to an SDK-hosted MCP server in an `mcp_set_servers` request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "sdk")]
+pub struct SdkMcpServerRef {
+    /// Server name, used by the CLI to route `mcp_message` requests
+    pub name: String,
 }
 
 /// Response to a control request
@@ -223,14 +442,23 @@ pub enum IncomingControlRequest {
     /// Request permission to use a tool
     ///
     /// The CLI asks the SDK whether a specific tool invocation should be allowed.
-    /// The SDK should respond with `Success { data: { "allowed": true/false } }`.
+    /// The SDK responds with `Success { data }`, where `data` has `"behavior": "allow"`
+    /// and the `updatedInput` to run, or `"behavior": "deny"` and a `message`
+    /// for the model. Either may include `updatedPermissions`.
     ///
     /// # Example Request
     /// ```json
     /// {
     ///   "subtype": "can_use_tool",
     ///   "tool_name": "Bash",
-    ///   "tool_input": { "command": "ls -la" }
+    ///   "input": { "command": "npm test" },
+    ///   "tool_use_id": "toolu_01",
+    ///   "permission_suggestions": [{
+    ///     "type": "addRules",
+    ///     "rules": [{ "toolName": "Bash", "ruleContent": "npm test" }],
+    ///     "behavior": "allow",
+    ///     "destination": "session"
+    ///   }]
     /// }
     /// ```
     ///
@@ -238,7 +466,14 @@ pub enum IncomingControlRequest {
     /// ```json
     /// {
     ///   "subtype": "success",
-    ///   "allowed": true
+    ///   "behavior": "allow",
+    ///   "updatedInput": { "command": "npm test" },
+    ///   "updatedPermissions": [{
+    ///     "type": "addRules",
+    ///     "rules": [{ "toolName": "Bash", "ruleContent": "npm test" }],
+    ///     "behavior": "allow",
+    ///     "destination": "session"
+    ///   }]
     /// }
     /// ```
     CanUseTool {
@@ -246,7 +481,12 @@ pub enum IncomingControlRequest {
         tool_name: String,
 
         /// Tool input parameters
+        #[serde(alias = "input")]
         tool_input: Value,
+
+        /// Rule suggestions, blocked path, tool use ID, and agent ID
+        #[serde(flatten)]
+        context: ToolPermissionContext,
     },
 
     /// Invoke a registered hook callback
@@ -273,12 +513,14 @@ pub enum IncomingControlRequest {
     /// ```
     HookCallback {
         /// Unique hook identifier
+        #[serde(alias = "callback_id")]
         hook_id: String,
 
         /// Event that triggered the hook
         hook_event: HookEvent,
 
         /// Hook input data
+        #[serde(alias = "input")]
         hook_input: Value,
     },
 
@@ -319,6 +561,231 @@ pub enum IncomingControlRequest {
     },
 }
 
+impl ControlResponse {
+    /// Payload of a successful response
+    ///
+    /// The CLI nests the payload under `response` next to the `request_id`; this
+    /// unwraps it, falling back to the flat data (minus `request_id`) for CLIs that
+    /// reply without nesting. Returns `None` for error responses.
+    pub fn success_payload(&self) -> Option<Value> {
+        match self {
+            ControlResponse::Success { data } => Some(match data.get("response") {
+                Some(payload) if payload.is_object() => payload.clone(),
+                _ => {
+                    let mut data = data.clone();
+                    if let Some(map) = data.as_object_mut() {
+                        map.remove("request_id");
+                    }
+                    data
+                }
+            }),
+            ControlResponse::Error { .. } => None,
+        }
+    }
+}
+
+/// Response to the `initialize` control request
+///
+/// Describes what the connected CLI offers: slash commands, models, output
+/// styles, and the signed-in account. Fields the SDK does not model are kept
+/// in `extra`.
+///
+/// # Example
+/// ```json
+/// {
+///   "commands": [{ "name": "review", "description": "Review a PR", "argumentHint": "<pr>" }],
+///   "models": [{ "value": "sonnet", "displayName": "Sonnet", "description": "Balanced" }],
+///   "output_style": "default",
+///   "available_output_styles": ["default", "explanatory"],
+///   "account": { "email": "dev@example.com", "subscriptionType": "max" }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InitializeResponse {
+    /// Available slash commands
+    #[serde(default)]
+    pub commands: Vec<SlashCommand>,
+    /// Models the session can switch to
+    #[serde(default)]
+    pub models: Vec<ModelInfo>,
+    /// Active output style
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_style: Option<String>,
+    /// Output styles the session can switch to
+    #[serde(default)]
+    pub available_output_styles: Vec<String>,
+    /// Signed-in account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<AccountInfo>,
+    /// Additional fields from the CLI
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
+/// A slash command offered by the CLI
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommand {
+    /// Command name without the leading `/`
+    pub name: String,
+    /// What the command does
+    #[serde(default)]
+    pub description: String,
+    /// Hint for the command's arguments (e.g., `<file>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+}
+
+/// A model offered by the CLI
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    /// Model identifier to pass to `set_model()` (e.g., "sonnet")
+    pub value: String,
+    /// Human-readable name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Short description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Account the CLI is signed in with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    /// Account email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Organization name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    /// Subscription type (e.g., "pro", "max")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_type: Option<String>,
+    /// Where the auth token came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_source: Option<String>,
+    /// Where the API key came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_source: Option<String>,
+}
+
+/// Response to the `get_server_info` control request
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServerInfo {
+    /// CLI version (e.g., "2.1.45")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Additional fields from the CLI
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
+/// Response to the `mcp_status` control request
+///
+/// # Example
+/// ```json
+/// {
+///   "mcpServers": [
+///     {
+///       "name": "github",
+///       "status": "connected",
+///       "serverInfo": { "name": "github-mcp", "version": "1.2.0" },
+///       "tools": [{ "name": "create_issue", "description": "Open an issue" }]
+///     },
+///     { "name": "jira", "status": "failed", "error": "connection refused" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpStatusResponse {
+    /// Status of each configured MCP server
+    #[serde(default, rename = "mcpServers")]
+    pub mcp_servers: Vec<McpServerState>,
+    /// Additional fields from the CLI
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
+impl McpStatusResponse {
+    /// Look up a server by name
+    pub fn server(&self, name: &str) -> Option<&McpServerState> {
+        self.mcp_servers.iter().find(|s| s.name == name)
+    }
+
+    /// Servers that are not connected
+    pub fn unhealthy(&self) -> impl Iterator<Item = &McpServerState> {
+        self.mcp_servers.iter().filter(|s| !s.is_connected())
+    }
+}
+
+/// Status of one MCP server in an [`McpStatusResponse`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerState {
+    /// Server name from the MCP configuration
+    pub name: String,
+    /// Connection status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<McpServerStatus>,
+    /// Name and version the server reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_info: Option<McpServerVersion>,
+    /// Why the connection failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Tools the server exposes
+    #[serde(default)]
+    pub tools: Vec<ToolInfo>,
+    /// Additional fields from the CLI (e.g., `config`, `scope`)
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
+impl McpServerState {
+    /// Whether the server is connected
+    pub fn is_connected(&self) -> bool {
+        self.status == Some(McpServerStatus::Connected)
+    }
+}
+
+/// Name and version an MCP server reported during its handshake
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpServerVersion {
+    /// Server name
+    pub name: String,
+    /// Server version
+    #[serde(default)]
+    pub version: String,
+}
+
+/// Response to the `mcp_set_servers` control request
+///
+/// # Example
+/// ```json
+/// {
+///   "added": ["github"],
+///   "removed": ["jira"],
+///   "errors": { "linear": "command not found: linear-mcp" }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpSetServersResponse {
+    /// Servers that were added
+    #[serde(default)]
+    pub added: Vec<String>,
+    /// Servers that were removed
+    #[serde(default)]
+    pub removed: Vec<String>,
+    /// Servers that could not be configured, with the reason
+    #[serde(default)]
+    pub errors: HashMap<String, String>,
+    /// Additional fields from the CLI
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -365,6 +832,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_hook_matcher_config_from_matcher() {
+        let config = HookMatcherConfig::from_matcher(
+            &HookMatcher::tool("Bash").with_timeout_ms(1500),
+            vec!["hook_0".to_string()],
+        );
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["matcher"], "Bash");
+        assert_eq!(json["hookCallbackIds"], json!(["hook_0"]));
+        assert_eq!(json["timeout"], 1.5);
+
+        let config = HookMatcherConfig::from_matcher(&HookMatcher::all(), vec![]);
+        let json = serde_json::to_value(&config).unwrap();
+        assert!(json.get("matcher").is_none());
+        assert!(json.get("timeout").is_none());
+    }
+
+    #[test]
+    fn test_hook_matcher_config_glob_translation() {
+        let config = HookMatcherConfig::from_matcher(&HookMatcher::tool("mcp__*"), vec![]);
+        assert_eq!(config.matcher.as_deref(), Some("^mcp__.*$"));
+
+        let config = HookMatcherConfig::from_matcher(&HookMatcher::tool("*"), vec![]);
+        assert_eq!(config.matcher.as_deref(), Some("*"));
+    }
+
+    #[test]
+    fn test_incoming_hook_callback_accepts_cli_field_names() {
+        let json = json!({
+            "subtype": "hook_callback",
+            "callback_id": "hook_0",
+            "hook_event": "PreToolUse",
+            "input": { "tool_name": "Bash" }
+        });
+
+        let req: IncomingControlRequest = serde_json::from_value(json).unwrap();
+        match req {
+            IncomingControlRequest::HookCallback {
+                hook_id,
+                hook_input,
+                ..
+            } => {
+                assert_eq!(hook_id, "hook_0");
+                assert_eq!(hook_input["tool_name"], "Bash");
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     #[test]
     fn test_control_request_interrupt() {
         let req = ControlRequest::Interrupt;
@@ -419,6 +935,119 @@ mod tests {
         assert_eq!(json["subtype"], "get_server_info");
     }
 
+    #[test]
+    fn test_control_request_mcp_set_servers() {
+        use crate::options::McpStdioServerConfig;
+
+        let mut servers = HashMap::new();
+        servers.insert(
+            "github".to_string(),
+            RuntimeMcpServer::External(McpServerConfig::Stdio(McpStdioServerConfig {
+                command: "github-mcp".to_string(),
+                args: vec![],
+                env: HashMap::new(),
+            })),
+        );
+        servers.insert(
+            "calc".to_string(),
+            RuntimeMcpServer::Sdk(SdkMcpServerRef {
+                name: "calc".to_string(),
+            }),
+        );
+        let json = serde_json::to_value(ControlRequest::McpSetServers { servers }).unwrap();
+        assert_eq!(json["subtype"], "mcp_set_servers");
+        assert_eq!(json["servers"]["github"]["type"], "stdio");
+        assert_eq!(json["servers"]["github"]["command"], "github-mcp");
+        assert_eq!(
+            json["servers"]["calc"],
+            json!({"type": "sdk", "name": "calc"})
+        );
+
+        // Both forms deserialize back into the right variant
+        let parsed: ControlRequest = serde_json::from_value(json).unwrap();
+        match parsed {
+            ControlRequest::McpSetServers { servers } => {
+                assert!(matches!(
+                    servers["github"],
+                    RuntimeMcpServer::External(McpServerConfig::Stdio(_))
+                ));
+                assert!(matches!(&servers["calc"], RuntimeMcpServer::Sdk(r) if r.name == "calc"));
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_control_request_mcp_reconnect_and_toggle() {
+        let json = serde_json::to_value(ControlRequest::McpReconnect {
+            server_name: "github".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            json!({"subtype": "mcp_reconnect", "serverName": "github"})
+        );
+
+        let json = serde_json::to_value(ControlRequest::McpToggle {
+            server_name: "github".to_string(),
+            enabled: false,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            json!({"subtype": "mcp_toggle", "serverName": "github", "enabled": false})
+        );
+    }
+
+    #[test]
+    fn test_mcp_set_servers_response() {
+        let response: McpSetServersResponse = serde_json::from_value(json!({
+            "added": ["github"],
+            "errors": {"linear": "command not found"}
+        }))
+        .unwrap();
+        assert_eq!(response.added, vec!["github"]);
+        assert!(response.removed.is_empty());
+        assert_eq!(response.errors["linear"], "command not found");
+    }
+
+    #[test]
+    fn test_control_request_subtype_matches_wire() {
+        let requests = [
+            ControlRequest::Initialize {
+                hooks: HashMap::new(),
+                agents: HashMap::new(),
+                sdk_mcp_servers: vec![],
+            },
+            ControlRequest::Interrupt,
+            ControlRequest::SetPermissionMode {
+                mode: "default".to_string(),
+            },
+            ControlRequest::SetModel {
+                model: "claude-sonnet-4".to_string(),
+            },
+            ControlRequest::McpStatus,
+            ControlRequest::RewindFiles {
+                user_message_id: "msg_1".to_string(),
+            },
+            ControlRequest::GetServerInfo,
+            ControlRequest::McpSetServers {
+                servers: HashMap::new(),
+            },
+            ControlRequest::McpReconnect {
+                server_name: "github".to_string(),
+            },
+            ControlRequest::McpToggle {
+                server_name: "github".to_string(),
+                enabled: true,
+            },
+        ];
+        for req in requests {
+            let json = serde_json::to_value(&req).unwrap();
+            assert_eq!(json["subtype"], req.subtype());
+        }
+    }
+
     #[test]
     fn test_control_response_success() {
         let resp = ControlResponse::Success {
@@ -466,6 +1095,7 @@ mod tests {
         let req = IncomingControlRequest::CanUseTool {
             tool_name: "Bash".to_string(),
             tool_input: json!({ "command": "ls -la" }),
+            context: ToolPermissionContext::default(),
         };
 
         let json = serde_json::to_value(&req).unwrap();
@@ -474,6 +1104,42 @@ mod tests {
         assert_eq!(json["tool_input"]["command"], "ls -la");
     }
 
+    #[test]
+    fn test_incoming_can_use_tool_from_cli() {
+        let req: IncomingControlRequest = serde_json::from_value(json!({
+            "subtype": "can_use_tool",
+            "tool_name": "Read",
+            "input": { "file_path": "/etc/hosts" },
+            "permission_suggestions": [{
+                "type": "addDirectories",
+                "directories": ["/etc"],
+                "destination": "session"
+            }],
+            "blocked_path": "/etc/hosts",
+            "tool_use_id": "toolu_01"
+        }))
+        .unwrap();
+
+        match req {
+            IncomingControlRequest::CanUseTool {
+                tool_name,
+                tool_input,
+                context,
+            } => {
+                assert_eq!(tool_name, "Read");
+                assert_eq!(tool_input["file_path"], "/etc/hosts");
+                assert_eq!(context.blocked_path.as_deref(), Some("/etc/hosts"));
+                assert_eq!(context.tool_use_id.as_deref(), Some("toolu_01"));
+                assert_eq!(context.agent_id, None);
+                assert_eq!(
+                    context.suggestions,
+                    vec![crate::permissions::PermissionUpdate::add_directory_in_session("/etc")]
+                );
+            }
+            _ => panic!("Expected CanUseTool"),
+        }
+    }
+
     #[test]
     fn test_incoming_control_request_hook_callback() {
         let req = IncomingControlRequest::HookCallback {
@@ -509,6 +1175,7 @@ mod tests {
         let req = IncomingControlRequest::CanUseTool {
             tool_name: "Read".to_string(),
             tool_input: json!({ "file_path": "/tmp/test.txt" }),
+            context: ToolPermissionContext::default(),
         };
 
         let json = serde_json::to_string(&req).unwrap();
@@ -518,6 +1185,7 @@ mod tests {
             IncomingControlRequest::CanUseTool {
                 tool_name,
                 tool_input,
+                ..
             } => {
                 assert_eq!(tool_name, "Read");
                 assert_eq!(tool_input["file_path"], "/tmp/test.txt");
@@ -525,4 +1193,69 @@ mod tests {
             _ => panic!("Wrong variant"),
         }
     }
+
+    #[test]
+    fn test_success_payload_unwraps_nested_response() {
+        let nested = ControlResponse::Success {
+            data: json!({"request_id": "req_1", "response": {"version": "2.1.45"}}),
+        };
+        assert_eq!(nested.success_payload(), Some(json!({"version": "2.1.45"})));
+
+        let flat = ControlResponse::Success {
+            data: json!({"request_id": "req_1", "version": "2.1.45"}),
+        };
+        assert_eq!(flat.success_payload(), Some(json!({"version": "2.1.45"})));
+
+        let error = ControlResponse::Error {
+            error: "nope".to_string(),
+            extra: json!({}),
+        };
+        assert_eq!(error.success_payload(), None);
+    }
+
+    #[test]
+    fn test_mcp_status_response() {
+        let status: McpStatusResponse = serde_json::from_value(json!({
+            "mcpServers": [
+                {
+                    "name": "github",
+                    "status": "connected",
+                    "serverInfo": {"name": "github-mcp", "version": "1.2.0"},
+                    "tools": [{"name": "create_issue", "description": "Open an issue"}],
+                    "scope": "project"
+                },
+                {"name": "jira", "status": "failed", "error": "connection refused"},
+                {"name": "linear", "status": "needs-auth"}
+            ]
+        }))
+        .unwrap();
+
+        let github = status.server("github").unwrap();
+        assert!(github.is_connected());
+        assert_eq!(github.server_info.as_ref().unwrap().version, "1.2.0");
+        assert_eq!(github.tools[0].name, "create_issue");
+        assert_eq!(github.extra["scope"], "project");
+
+        let unhealthy: Vec<_> = status.unhealthy().map(|s| s.name.as_str()).collect();
+        assert_eq!(unhealthy, vec!["jira", "linear"]);
+        assert_eq!(
+            status.server("jira").unwrap().error.as_deref(),
+            Some("connection refused")
+        );
+        assert_eq!(
+            status.server("linear").unwrap().status,
+            Some(McpServerStatus::NeedsAuth)
+        );
+    }
+
+    #[test]
+    fn test_server_info_keeps_extra_fields() {
+        let info: ServerInfo =
+            serde_json::from_value(json!({"version": "2.1.45", "platform": "linux"})).unwrap();
+        assert_eq!(info.version.as_deref(), Some("2.1.45"));
+        assert_eq!(info.extra["platform"], "linux");
+
+        let empty: InitializeResponse = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(empty, InitializeResponse::default());
+    }
 }
@@ -60,20 +60,28 @@
 
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Semaphore};
 use uuid::Uuid;
 
 use crate::control::handlers::ControlHandlers;
-use crate::control::messages::{ControlRequest, ControlResponse, IncomingControlRequest};
+use crate::control::messages::{
+    ControlRequest, ControlResponse, HookMatcherConfig, IncomingControlRequest, InitializeResponse,
+};
 use crate::control::pending::PendingRequests;
 use crate::error::ClawError;
-use crate::options::ClaudeAgentOptions;
+use crate::hooks::HookCallbackAdapter;
+use crate::options::{ClaudeAgentOptions, ControlTimeouts, HookEvent};
+use crate::permissions::PermissionDecision;
 use crate::transport::Transport;
+use std::collections::HashMap;
 
 pub mod handlers;
 pub mod messages;
 pub mod pending;
 
+/// Token for cancelling a pending [`ControlProtocol::request_with`] call
+pub use tokio_util::sync::CancellationToken;
+
 /// Control protocol for bidirectional communication with Claude CLI
 ///
 /// The `ControlProtocol` manages:
@@ -105,16 +113,31 @@ pub mod pending;
 /// # }
 /// ```
 pub struct ControlProtocol {
-    /// Transport for sending/receiving messages
-    transport: Arc<dyn Transport>,
+    /// Transport for sending/receiving messages (replaced on crash recovery)
+    transport: std::sync::RwLock<Arc<dyn Transport>>,
 
     /// Pending outgoing requests awaiting responses
     pending: PendingRequests,
 
     /// Registered handlers for incoming requests
     handlers: Arc<Mutex<ControlHandlers>>,
+
+    /// Response deadlines used by request()
+    timeouts: ControlTimeouts,
+
+    /// Response of the last successful initialize handshake
+    initialize_response: std::sync::Mutex<Option<InitializeResponse>>,
+
+    /// Limits how many incoming requests are handled at once
+    incoming_limit: Arc<Semaphore>,
+
+    /// Cancellation tokens of incoming requests still being handled, keyed by request ID
+    in_flight: std::sync::Mutex<HashMap<String, CancellationToken>>,
 }
 
+/// Default number of incoming control requests handled at once
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 16;
+
 impl ControlProtocol {
     /// Create a new control protocol instance
     ///
@@ -139,12 +162,85 @@ impl ControlProtocol {
     /// ```
     pub fn new(transport: Arc<dyn Transport>) -> Self {
         Self {
-            transport,
+            transport: std::sync::RwLock::new(transport),
             pending: PendingRequests::new(),
             handlers: Arc::new(Mutex::new(ControlHandlers::new())),
+            timeouts: ControlTimeouts::default(),
+            initialize_response: std::sync::Mutex::new(None),
+            incoming_limit: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_REQUESTS)),
+            in_flight: std::sync::Mutex::new(HashMap::new()),
         }
     }
 
+    /// Use `timeouts` instead of the defaults for [`request()`](Self::request)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rusty_claw::prelude::*;
+    /// use rusty_claw::options::ControlTimeouts;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut transport = SubprocessCLITransport::new(None, vec![]);
+    /// transport.connect().await?;
+    /// let control = ControlProtocol::new(Arc::new(transport))
+    ///     .with_timeouts(
+    ///         ControlTimeouts::default().with_timeout(ControlSubtype::Interrupt, Duration::from_secs(2)),
+    ///     );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_timeouts(mut self, timeouts: ControlTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Handle at most `limit` incoming requests at once in
+    /// [`dispatch_incoming()`](Self::dispatch_incoming) (clamped to at least 1)
+    ///
+    /// Defaults to [`DEFAULT_MAX_CONCURRENT_REQUESTS`].
+    pub fn with_max_concurrent_requests(mut self, limit: usize) -> Self {
+        self.incoming_limit = Arc::new(Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Current transport
+    pub(crate) fn transport(&self) -> Arc<dyn Transport> {
+        self.transport
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Swap in a freshly connected transport after the previous one died
+    ///
+    /// Registered handlers are kept. Requests still waiting on the old
+    /// transport can never be answered, so they are cancelled, and so are
+    /// incoming requests from the old process that are still being handled.
+    pub(crate) async fn replace_transport(&self, transport: Arc<dyn Transport>) {
+        *self.transport.write().unwrap_or_else(|e| e.into_inner()) = transport;
+        self.abandon_all().await;
+    }
+
+    /// Give up on every request tied to the current transport
+    ///
+    /// Outgoing requests still waiting for a response fail with a closed
+    /// response channel instead of waiting for their deadline, and incoming
+    /// requests still being handled are cancelled. Called as soon as the CLI
+    /// is known to be gone.
+    pub(crate) async fn abandon_all(&self) {
+        self.pending.cancel_all().await;
+        self.cancel_all_incoming();
+    }
+
+    /// Watch the number of outgoing requests still waiting for a response
+    pub(crate) fn awaiting_responses(&self) -> tokio::sync::watch::Receiver<usize> {
+        self.pending.watch_len()
+    }
+
     /// Get a mutable reference to the handler registry
     ///
     /// Use this to register handlers for can_use_tool, hooks, and MCP messages.
@@ -164,8 +260,12 @@ impl ControlProtocol {
     ///         &self,
     ///         _tool_name: &str,
     ///         _tool_input: &serde_json::Value,
+    ///         _context: &ToolPermissionContext,
     ///     ) -> Result<rusty_claw::permissions::PermissionDecision, ClawError> {
-    ///         Ok(rusty_claw::permissions::PermissionDecision::Allow { updated_input: None })
+    ///         Ok(rusty_claw::permissions::PermissionDecision::Allow {
+    ///             updated_input: None,
+    ///             updated_permissions: vec![],
+    ///         })
     ///     }
     /// }
     ///
@@ -191,13 +291,18 @@ impl ControlProtocol {
     /// from `ClaudeAgentOptions`. This must be called before the CLI can
     /// process user messages.
     ///
+    /// Each entry in [`ClaudeAgentOptions::hook_callbacks`] is assigned a
+    /// callback ID (`hook_0`, `hook_1`, ...), registered as a hook handler, and
+    /// advertised to the CLI under its event's matchers.
+    ///
     /// # Arguments
     ///
     /// * `options` - Session configuration (hooks, agents, MCP servers, etc.)
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - Initialization successful
+    /// * `Ok(InitializeResponse)` - Initialization successful; the response is also
+    ///   kept for [`initialize_response()`](Self::initialize_response)
     /// * `Err(ClawError::ControlError)` - CLI returned an error
     /// * `Err(ClawError::ControlTimeout)` - CLI did not respond in time
     ///
@@ -222,13 +327,41 @@ impl ControlProtocol {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn initialize(&self, options: &ClaudeAgentOptions) -> Result<(), ClawError> {
+    pub async fn initialize(
+        &self,
+        options: &ClaudeAgentOptions,
+    ) -> Result<InitializeResponse, ClawError> {
         // Match the official SDK's initialize format:
         // - hooks and agents are sent in the control request
         // - permissions and can_use_tool are set via CLI flags, not here
         // - sdk_mcp_servers are advertised here so the CLI knows about them
+        let mut hooks: HashMap<HookEvent, Vec<HookMatcherConfig>> = HashMap::new();
+        for (event, matchers) in &options.hooks {
+            hooks.entry(event.clone()).or_default().extend(
+                matchers
+                    .iter()
+                    .map(|m| HookMatcherConfig::from_matcher(m, Vec::new())),
+            );
+        }
+        {
+            let mut handlers = self.handlers.lock().await;
+            for (index, registration) in options.hook_callbacks.iter().enumerate() {
+                let callback_id = format!("hook_{}", index);
+                hooks.entry(registration.event.clone()).or_default().push(
+                    HookMatcherConfig::from_matcher(
+                        &registration.matcher,
+                        vec![callback_id.clone()],
+                    ),
+                );
+                handlers.register_hook(
+                    callback_id,
+                    Arc::new(HookCallbackAdapter::new(registration.clone())),
+                );
+            }
+        }
+
         let request = ControlRequest::Initialize {
-            hooks: options.hooks.clone(),
+            hooks,
             agents: options.agents.clone(),
             sdk_mcp_servers: options
                 .sdk_mcp_servers
@@ -237,20 +370,44 @@ impl ControlProtocol {
                 .collect(),
         };
 
-        match self.request(request).await? {
-            ControlResponse::Success { .. } => Ok(()),
-            ControlResponse::Error { error, .. } => Err(ClawError::ControlError(format!(
-                "Initialization failed: {}",
-                error
-            ))),
-        }
+        let payload = match self.request(request).await? {
+            ControlResponse::Error { error, .. } => {
+                return Err(ClawError::ControlError(format!(
+                    "Initialization failed: {}",
+                    error
+                )));
+            }
+            response => response.success_payload().unwrap_or_default(),
+        };
+
+        // A response we cannot read must not fail the handshake that just succeeded
+        let init: InitializeResponse = serde_json::from_value(payload).unwrap_or_else(|e| {
+            tracing::warn!("Failed to parse initialize response: {}", e);
+            InitializeResponse::default()
+        });
+        *self
+            .initialize_response
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(init.clone());
+        Ok(init)
+    }
+
+    /// Response of the last successful [`initialize()`](Self::initialize), if any
+    pub fn initialize_response(&self) -> Option<InitializeResponse> {
+        self.initialize_response
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
     }
 
     /// Send a control request and wait for the response
     ///
     /// Generates a unique request ID, sends the request to the CLI, and waits
-    /// up to 30 seconds for a response. The response is delivered via the
-    /// [`handle_response`](Self::handle_response) method.
+    /// for a response for as long as [`ControlTimeouts`] allows the request's
+    /// subtype (60 seconds by default, 10 for `interrupt`). The response is
+    /// delivered via the [`handle_response`](Self::handle_response) method.
+    /// Use [`request_with()`](Self::request_with) for an explicit deadline or
+    /// cancellation.
     ///
     /// # Arguments
     ///
@@ -259,7 +416,8 @@ impl ControlProtocol {
     /// # Returns
     ///
     /// * `Ok(ControlResponse)` - CLI responded successfully
-    /// * `Err(ClawError::ControlTimeout)` - CLI did not respond within 30 seconds
+    /// * `Err(ClawError::ControlTimeout)` - CLI did not respond within the
+    ///   deadline configured for the request's subtype (see [`ControlTimeouts`])
     /// * `Err(ClawError::ControlError)` - Response channel was closed
     /// * `Err(ClawError::Connection)` - Failed to write request to CLI
     ///
@@ -281,6 +439,55 @@ impl ControlProtocol {
     /// # }
     /// ```
     pub async fn request(&self, request: ControlRequest) -> Result<ControlResponse, ClawError> {
+        let deadline = self.timeouts.for_subtype(request.kind());
+        self.request_with(request, deadline, CancellationToken::new())
+            .await
+    }
+
+    /// Send a control request with an explicit deadline and cancellation token
+    ///
+    /// Behaves like [`request()`](Self::request), but waits at most `deadline`
+    /// and stops waiting as soon as `cancel` is cancelled. Either way the
+    /// pending entry is removed, so a late response from the CLI is ignored.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ControlResponse)` - CLI responded successfully
+    /// * `Err(ClawError::ControlTimeout)` - CLI did not respond within `deadline`
+    /// * `Err(ClawError::ControlCancelled)` - `cancel` fired first
+    /// * `Err(ClawError::ControlError)` - Response channel was closed
+    /// * `Err(ClawError::Connection)` - Failed to write request to CLI
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rusty_claw::prelude::*;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut transport = SubprocessCLITransport::new(None, vec![]);
+    /// transport.connect().await?;
+    /// let control = ControlProtocol::new(Arc::new(transport));
+    ///
+    /// // Hand a clone of the token to the UI's stop button
+    /// let cancel = CancellationToken::new();
+    /// let stop_button = cancel.clone();
+    /// # drop(stop_button);
+    /// let response = control
+    ///     .request_with(ControlRequest::Interrupt, Duration::from_secs(5), cancel)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_with(
+        &self,
+        request: ControlRequest,
+        deadline: Duration,
+        cancel: CancellationToken,
+    ) -> Result<ControlResponse, ClawError> {
+        let subtype = request.subtype();
         let id = Uuid::new_v4().to_string();
         let (tx, rx) = tokio::sync::oneshot::channel();
         self.pending.insert(id.clone(), tx).await;
@@ -295,7 +502,8 @@ impl ControlProtocol {
         // Send to CLI (NDJSON requires trailing newline)
         // Clean up the pending entry on any send failure: the CLI will never respond
         // to a request it never received, so leaving the entry in the map would leak it
-        // (cancel() is only called on timeout, which never fires after early return).
+        // (cancel() is otherwise only called on timeout or cancellation, which never
+        // fire after early return).
         let mut bytes = match serde_json::to_vec(&msg) {
             Ok(b) => b,
             Err(e) => {
@@ -304,7 +512,7 @@ impl ControlProtocol {
             }
         };
         bytes.push(b'\n');
-        if let Err(e) = self.transport.write(&bytes).await {
+        if let Err(e) = self.transport().write(&bytes).await {
             self.pending.cancel(&id).await;
             return Err(ClawError::Connection(format!(
                 "Failed to send control request: {}",
@@ -312,17 +520,24 @@ impl ControlProtocol {
             )));
         }
 
-        // Wait for response with timeout (60s to accommodate MCP server startup)
-        match tokio::time::timeout(Duration::from_secs(60), rx).await {
-            Ok(Ok(response)) => Ok(response),
-            Ok(Err(_)) => Err(ClawError::ControlError(
-                "Response channel closed".to_string(),
-            )),
-            Err(_) => {
-                // Timeout - clean up pending entry
+        tokio::select! {
+            result = tokio::time::timeout(deadline, rx) => match result {
+                Ok(Ok(response)) => Ok(response),
+                Ok(Err(_)) => Err(ClawError::ControlError(
+                    "Response channel closed".to_string(),
+                )),
+                Err(_) => {
+                    // Timeout - clean up pending entry
+                    self.pending.cancel(&id).await;
+                    Err(ClawError::ControlTimeout {
+                        subtype: subtype.to_string(),
+                    })
+                }
+            },
+            _ = cancel.cancelled() => {
                 self.pending.cancel(&id).await;
-                Err(ClawError::ControlTimeout {
-                    subtype: "control_request".to_string(),
+                Err(ClawError::ControlCancelled {
+                    subtype: subtype.to_string(),
                 })
             }
         }
@@ -367,7 +582,9 @@ impl ControlProtocol {
     /// Routes the request to the appropriate registered handler:
     /// - **can_use_tool** → [`CanUseToolHandler`](handlers::CanUseToolHandler)
     /// - **hook_callback** → [`HookHandler`](handlers::HookHandler)
-    /// - **mcp_message** → [`McpMessageHandler`](handlers::McpMessageHandler)
+    /// - **mcp_message** → SDK server registered with
+    ///   [`register_sdk_mcp_server`](handlers::ControlHandlers::register_sdk_mcp_server),
+    ///   else [`McpMessageHandler`](handlers::McpMessageHandler)
     ///
     /// If no handler is registered:
     /// - **can_use_tool**: Allow all tools (default: permissive)
@@ -399,56 +616,144 @@ impl ControlProtocol {
     /// let request = IncomingControlRequest::CanUseTool {
     ///     tool_name: "Bash".to_string(),
     ///     tool_input: json!({ "command": "ls" }),
+    ///     context: ToolPermissionContext::default(),
     /// };
     /// control.handle_incoming("req_123", request).await;
     /// # Ok(())
     /// # }
     /// ```
     pub async fn handle_incoming(&self, request_id: &str, request: IncomingControlRequest) {
+        let response = self.incoming_response(request).await;
+        self.send_incoming_response(request_id, response).await;
+    }
+
+    /// Handle an incoming control request on its own task
+    ///
+    /// Unlike [`handle_incoming()`](Self::handle_incoming), this returns
+    /// immediately, so a slow handler (e.g., a permission prompt waiting on a
+    /// human) does not hold up routing of other messages, including responses
+    /// to requests the handler itself sends. At most the configured number of
+    /// requests (see [`with_max_concurrent_requests()`](Self::with_max_concurrent_requests))
+    /// are handled at once; the rest wait for a slot.
+    ///
+    /// The request can be aborted with [`cancel_incoming()`](Self::cancel_incoming)
+    /// until its response is sent. A `can_use_tool` handler sees the
+    /// cancellation through
+    /// [`ToolPermissionContext::cancellation`](crate::permissions::ToolPermissionContext::cancellation).
+    pub fn dispatch_incoming(
+        self: &Arc<Self>,
+        request_id: String,
+        mut request: IncomingControlRequest,
+    ) {
+        use tracing::debug;
+
+        let cancel = CancellationToken::new();
+        if let IncomingControlRequest::CanUseTool { context, .. } = &mut request {
+            context.cancellation = cancel.clone();
+        }
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(request_id.clone(), cancel.clone());
+
+        let control = self.clone();
+        tokio::spawn(async move {
+            let handle = async {
+                // The semaphore is never closed, so acquiring cannot fail
+                let _permit = control.incoming_limit.clone().acquire_owned().await;
+                control.incoming_response(request).await
+            };
+            // Check cancellation first so a cancelled request never answers,
+            // even if its handler finished in the same poll
+            let response = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => None,
+                response = handle => Some(response),
+            };
+            control
+                .in_flight
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&request_id);
+
+            match response {
+                Some(response) => control.send_incoming_response(&request_id, response).await,
+                None => debug!(request_id = %request_id, "Incoming control request cancelled"),
+            }
+        });
+    }
+
+    /// Abort an incoming request started with [`dispatch_incoming()`](Self::dispatch_incoming)
+    ///
+    /// Called when the CLI sends a `control_cancel_request`, e.g. because the
+    /// turn was interrupted while a permission prompt was open. The handler's
+    /// future is dropped and no response is sent.
+    ///
+    /// Returns `true` if the request was still in flight.
+    pub fn cancel_incoming(&self, request_id: &str) -> bool {
+        let token = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(request_id);
+        match token {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Abort every incoming request that is still being handled
+    fn cancel_all_incoming(&self) {
+        let tokens: Vec<_> = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain()
+            .map(|(_, token)| token)
+            .collect();
+        for token in tokens {
+            token.cancel();
+        }
+    }
+
+    /// Run the registered handler for an incoming request and build its response
+    async fn incoming_response(&self, request: IncomingControlRequest) -> ControlResponse {
         use serde_json::json;
-        use tracing::error;
 
-        let response = match request {
+        match request {
             IncomingControlRequest::CanUseTool {
                 tool_name,
                 tool_input,
+                context,
             } => {
                 // Clone handler Arc and drop lock before awaiting to avoid deadlock
                 let handler = {
                     let handlers = self.handlers.lock().await;
                     handlers.can_use_tool.clone()
                 };
-                if let Some(handler) = handler {
-                    match handler.can_use_tool(&tool_name, &tool_input).await {
-                        Ok(decision) => {
-                            use crate::permissions::PermissionDecision;
-                            match decision {
-                                PermissionDecision::Allow { updated_input } => {
-                                    let mut data = json!({ "allowed": true });
-                                    if let Some(input) = updated_input {
-                                        data["updatedInput"] = input;
-                                    }
-                                    ControlResponse::Success { data }
-                                }
-                                PermissionDecision::Deny { interrupt } => {
-                                    ControlResponse::Success {
-                                        data: json!({
-                                            "allowed": false,
-                                            "interrupt": interrupt,
-                                        }),
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => ControlResponse::Error {
-                            error: e.to_string(),
-                            extra: json!({}),
-                        },
-                    }
-                } else {
+                let Some(handler) = handler else {
                     // Default: allow all tools
-                    ControlResponse::Success {
-                        data: json!({ "allowed": true }),
+                    return ControlResponse::Success {
+                        data: PermissionDecision::allow().into_response(tool_input),
+                    };
+                };
+                match handler
+                    .can_use_tool(&tool_name, &tool_input, &context)
+                    .await
+                {
+                    Ok(decision) => ControlResponse::Success {
+                        data: decision.into_response(tool_input),
+                    },
+                    // A failing handler denies the tool, so the model is told
+                    // why instead of the CLI seeing a protocol error
+                    Err(e) => {
+                        tracing::warn!("can_use_tool handler failed for {}: {}", tool_name, e);
+                        ControlResponse::Success {
+                            data: PermissionDecision::deny(e.to_string()).into_response(tool_input),
+                        }
                     }
                 }
             }
@@ -483,13 +788,21 @@ impl ControlProtocol {
                 server_name,
                 message,
             } => {
-                // Clone handler Arc and drop lock before awaiting to avoid deadlock
-                let handler = {
+                // Clone handler Arcs and drop lock before awaiting to avoid deadlock
+                let (server, handler) = {
                     let handlers = self.handlers.lock().await;
-                    handlers.mcp_message.clone()
+                    (
+                        handlers.sdk_mcp_servers.get(&server_name).cloned(),
+                        handlers.mcp_message.clone(),
+                    )
                 };
-                if let Some(handler) = handler {
-                    match handler.handle(&server_name, message).await {
+                let result = match (server, handler) {
+                    (Some(server), _) => Some(server.handle_jsonrpc(message).await),
+                    (None, Some(handler)) => Some(handler.handle(&server_name, message).await),
+                    (None, None) => None,
+                };
+                if let Some(result) = result {
+                    match result {
                         Ok(result) => ControlResponse::Success {
                             // Wrap in mcp_response (matches Python SDK format)
                             data: json!({"mcp_response": result}),
@@ -506,7 +819,13 @@ impl ControlProtocol {
                     }
                 }
             }
-        };
+        }
+    }
+
+    /// Send the response to an incoming request back to the CLI
+    async fn send_incoming_response(&self, request_id: &str, response: ControlResponse) {
+        use serde_json::json;
+        use tracing::error;
 
         // Send response back to CLI (matches Python SDK format).
         // Python SDK format:
@@ -548,7 +867,7 @@ impl ControlProtocol {
         match serde_json::to_vec(&msg) {
             Ok(mut bytes) => {
                 bytes.push(b'\n'); // NDJSON requires trailing newline
-                if let Err(e) = self.transport.write(&bytes).await {
+                if let Err(e) = self.transport().write(&bytes).await {
                     error!("Failed to send control response: {}", e);
                 }
             }
@@ -563,23 +882,25 @@ impl ControlProtocol {
 mod tests {
     use super::*;
     use crate::control::handlers::{CanUseToolHandler, HookHandler, McpMessageHandler};
+    use crate::control::messages::ControlSubtype;
     use crate::options::HookEvent;
+    use crate::permissions::ToolPermissionContext;
+    use crate::transport::MessageReceiver;
     use async_trait::async_trait;
     use serde_json::{Value, json};
     use std::sync::Arc;
     use tokio::sync::Mutex as TokioMutex;
-    use tokio::sync::mpsc;
+    use tokio::sync::{Notify, mpsc};
 
     // Mock transport for testing
-    #[allow(clippy::type_complexity)]
     struct MockTransport {
         sent: Arc<TokioMutex<Vec<Vec<u8>>>>,
-        receiver: Arc<TokioMutex<Option<mpsc::UnboundedReceiver<Result<Value, ClawError>>>>>,
+        receiver: Arc<TokioMutex<Option<MessageReceiver>>>,
     }
 
     impl MockTransport {
         fn new() -> Self {
-            let (_sender, receiver) = mpsc::unbounded_channel();
+            let (_sender, receiver) = mpsc::channel(1);
             Self {
                 sent: Arc::new(TokioMutex::new(Vec::new())),
                 receiver: Arc::new(TokioMutex::new(Some(receiver))),
@@ -589,6 +910,17 @@ mod tests {
         async fn get_sent(&self) -> Vec<Vec<u8>> {
             self.sent.lock().await.clone()
         }
+
+        /// Wait until at least `count` messages were written
+        async fn wait_for_sent(&self, count: usize) -> Vec<Vec<u8>> {
+            loop {
+                let sent = self.get_sent().await;
+                if sent.len() >= count {
+                    return sent;
+                }
+                tokio::task::yield_now().await;
+            }
+        }
     }
 
     #[async_trait]
@@ -602,7 +934,7 @@ mod tests {
             Ok(())
         }
 
-        fn messages(&self) -> mpsc::UnboundedReceiver<Result<Value, ClawError>> {
+        fn messages(&self) -> MessageReceiver {
             // SAFETY: This is a test mock. We use blocking_lock which is safe
             // in test contexts where we control the async runtime.
             self.receiver.blocking_lock().take().unwrap()
@@ -631,13 +963,19 @@ mod tests {
             &self,
             tool_name: &str,
             _tool_input: &Value,
+            _context: &ToolPermissionContext,
         ) -> Result<crate::permissions::PermissionDecision, ClawError> {
-            if tool_name == "Read" {
+            if tool_name == "Broken" {
+                Err(ClawError::ToolExecution("policy lookup failed".to_string()))
+            } else if tool_name == "Read" {
                 Ok(crate::permissions::PermissionDecision::Allow {
                     updated_input: None,
+                    updated_permissions: vec![],
                 })
             } else {
-                Ok(crate::permissions::PermissionDecision::Deny { interrupt: false })
+                Ok(crate::permissions::PermissionDecision::deny(
+                    "Only Read is allowed",
+                ))
             }
         }
     }
@@ -664,6 +1002,67 @@ mod tests {
         }
     }
 
+    /// Allows once `gate` has a permit; signals `entered` when called and
+    /// `exited` when its future completes or is dropped
+    struct GatedCanUseToolHandler {
+        gate: Arc<Semaphore>,
+        entered: Arc<Notify>,
+        exited: Arc<Notify>,
+    }
+
+    impl GatedCanUseToolHandler {
+        fn new(gate: Arc<Semaphore>) -> Self {
+            Self {
+                gate,
+                entered: Arc::new(Notify::new()),
+                exited: Arc::new(Notify::new()),
+            }
+        }
+    }
+
+    struct NotifyOnDrop(Arc<Notify>);
+
+    impl Drop for NotifyOnDrop {
+        fn drop(&mut self) {
+            self.0.notify_one();
+        }
+    }
+
+    #[async_trait]
+    impl CanUseToolHandler for GatedCanUseToolHandler {
+        async fn can_use_tool(
+            &self,
+            _tool_name: &str,
+            _tool_input: &Value,
+            _context: &ToolPermissionContext,
+        ) -> Result<crate::permissions::PermissionDecision, ClawError> {
+            let _exited = NotifyOnDrop(self.exited.clone());
+            self.entered.notify_one();
+            let _permit = self.gate.acquire().await.unwrap();
+            Ok(crate::permissions::PermissionDecision::Allow {
+                updated_input: None,
+                updated_permissions: vec![],
+            })
+        }
+    }
+
+    fn can_use_tool_request() -> IncomingControlRequest {
+        IncomingControlRequest::CanUseTool {
+            tool_name: "Bash".to_string(),
+            tool_input: json!({ "command": "ls" }),
+            context: ToolPermissionContext::default(),
+        }
+    }
+
+    fn sent_request_ids(sent: &[Vec<u8>]) -> Vec<String> {
+        sent.iter()
+            .map(|bytes| {
+                let msg: Value = serde_json::from_slice(bytes).unwrap();
+                msg["response"]["request_id"].as_str().unwrap().to_string()
+            })
+            .collect()
+    }
+
     #[tokio::test]
     async fn test_request_success() {
         let transport = Arc::new(MockTransport::new());
@@ -708,6 +1107,53 @@ mod tests {
         }
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_request_timeout_names_subtype() {
+        let transport = Arc::new(MockTransport::new());
+        let control = ControlProtocol::new(transport.clone() as Arc<dyn Transport>).with_timeouts(
+            ControlTimeouts::default()
+                .with_timeout(ControlSubtype::SetModel, Duration::from_secs(3)),
+        );
+
+        let started = tokio::time::Instant::now();
+        let err = control
+            .request(ControlRequest::SetModel {
+                model: "claude-opus-4".to_string(),
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ClawError::ControlTimeout { ref subtype } if subtype == "set_model"));
+        assert_eq!(started.elapsed(), Duration::from_secs(3));
+        assert!(control.pending.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_request_with_cancellation() {
+        let transport = Arc::new(MockTransport::new());
+        let control = ControlProtocol::new(transport.clone() as Arc<dyn Transport>);
+        let cancel = CancellationToken::new();
+
+        // Cancel once the request is on the wire
+        let stop = cancel.clone();
+        let sent = transport.clone();
+        tokio::spawn(async move {
+            sent.wait_for_sent(1).await;
+            stop.cancel();
+        });
+
+        let err = control
+            .request_with(ControlRequest::Interrupt, Duration::from_secs(60), cancel)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, ClawError::ControlCancelled { ref subtype } if subtype == "interrupt")
+        );
+        assert_eq!(transport.get_sent().await.len(), 1);
+        assert!(control.pending.is_empty().await);
+    }
+
     #[tokio::test]
     async fn test_initialize_success() {
         let transport = Arc::new(MockTransport::new());
@@ -736,6 +1182,49 @@ mod tests {
         control_clone.initialize(&options).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_initialize_returns_typed_response() {
+        let transport = Arc::new(MockTransport::new());
+        let control = Arc::new(ControlProtocol::new(transport.clone() as Arc<dyn Transport>));
+        assert!(control.initialize_response().is_none());
+
+        let control_for_response = control.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+            let sent = transport.get_sent().await;
+            let msg: Value = serde_json::from_slice(&sent[0]).unwrap();
+            let request_id = msg["request_id"].as_str().unwrap().to_string();
+
+            // Shape of the routed CLI response: payload nested under `response`
+            let data = json!({
+                "request_id": request_id,
+                "response": {
+                    "commands": [{"name": "review", "description": "Review a PR", "argumentHint": "<pr>"}],
+                    "models": [{"value": "sonnet", "displayName": "Sonnet"}],
+                    "output_style": "default",
+                    "available_output_styles": ["default", "explanatory"],
+                    "account": {"email": "dev@example.com", "subscriptionType": "max"}
+                }
+            });
+            control_for_response
+                .handle_response(&request_id, ControlResponse::Success { data })
+                .await;
+        });
+
+        let init = control
+            .initialize(&ClaudeAgentOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(init.commands[0].argument_hint.as_deref(), Some("<pr>"));
+        assert_eq!(init.models[0].display_name.as_deref(), Some("Sonnet"));
+        assert_eq!(init.available_output_styles.len(), 2);
+        assert_eq!(
+            init.account.as_ref().unwrap().subscription_type.as_deref(),
+            Some("max")
+        );
+        assert_eq!(control.initialize_response(), Some(init));
+    }
+
     #[tokio::test]
     async fn test_initialize_error() {
         let transport = Arc::new(MockTransport::new());
@@ -777,6 +1266,113 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_initialize_registers_hook_callbacks() {
+        use crate::hooks::{HookCallback, HookContext, HookInput, HookResponse};
+        use crate::options::HookMatcher;
+
+        struct DenyAll;
+
+        #[async_trait]
+        impl HookCallback for DenyAll {
+            async fn call(
+                &self,
+                _input: HookInput,
+                _tool_use_id: Option<&str>,
+                _context: &HookContext,
+            ) -> Result<HookResponse, ClawError> {
+                Ok(HookResponse::deny("blocked"))
+            }
+        }
+
+        let transport = Arc::new(MockTransport::new());
+        let control = Arc::new(ControlProtocol::new(transport.clone() as Arc<dyn Transport>));
+
+        let transport_clone = transport.clone();
+        let control_for_response = control.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+            let sent = transport_clone.get_sent().await;
+            let msg: Value = serde_json::from_slice(&sent[0]).unwrap();
+            let request_id = msg["request_id"].as_str().unwrap().to_string();
+            control_for_response
+                .handle_response(&request_id, ControlResponse::Success { data: json!({}) })
+                .await;
+        });
+
+        let options = ClaudeAgentOptions::builder()
+            .hook(
+                HookEvent::PreToolUse,
+                HookMatcher::tool("Bash").with_timeout_ms(5000),
+                DenyAll,
+            )
+            .build();
+        control.initialize(&options).await.unwrap();
+
+        let sent = transport.get_sent().await;
+        let msg: Value = serde_json::from_slice(&sent[0]).unwrap();
+        let matchers = &msg["request"]["hooks"]["PreToolUse"];
+        assert_eq!(matchers[0]["matcher"], "Bash");
+        assert_eq!(matchers[0]["hookCallbackIds"], json!(["hook_0"]));
+        assert_eq!(matchers[0]["timeout"], 5.0);
+
+        // The CLI invokes the callback by the ID it was given
+        let request = IncomingControlRequest::HookCallback {
+            hook_id: "hook_0".to_string(),
+            hook_event: HookEvent::PreToolUse,
+            hook_input: json!({"tool_name": "Bash", "tool_input": {"command": "ls"}}),
+        };
+        control.handle_incoming("req_2", request).await;
+
+        let sent = transport.get_sent().await;
+        let msg: Value = serde_json::from_slice(&sent[1]).unwrap();
+        assert_eq!(msg["response"]["subtype"], "success");
+        let output = &msg["response"]["response"];
+        assert_eq!(output["continue"], true);
+        assert_eq!(output["hookSpecificOutput"]["permissionDecision"], "deny");
+    }
+
+    #[tokio::test]
+    async fn test_replace_transport_keeps_handlers_and_cancels_pending() {
+        let old = Arc::new(MockTransport::new());
+        let control = Arc::new(ControlProtocol::new(old.clone() as Arc<dyn Transport>));
+        {
+            let mut handlers = control.handlers().await;
+            handlers.register_can_use_tool(Arc::new(MockCanUseToolHandler));
+        }
+
+        // A request written to the old transport never gets an answer
+        let waiting = {
+            let control = control.clone();
+            tokio::spawn(async move { control.request(ControlRequest::Interrupt).await })
+        };
+        while old.get_sent().await.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let new = Arc::new(MockTransport::new());
+        control
+            .replace_transport(new.clone() as Arc<dyn Transport>)
+            .await;
+        assert!(matches!(
+            waiting.await.unwrap(),
+            Err(ClawError::ControlError(_))
+        ));
+
+        // Responses now go to the new transport, using the same handlers
+        let request = IncomingControlRequest::CanUseTool {
+            tool_name: "Read".to_string(),
+            tool_input: json!({}),
+            context: ToolPermissionContext::default(),
+        };
+        control.handle_incoming("req_1", request).await;
+        assert_eq!(old.get_sent().await.len(), 1);
+        let sent = new.get_sent().await;
+        assert_eq!(sent.len(), 1);
+        let msg: Value = serde_json::from_slice(&sent[0]).unwrap();
+        assert_eq!(msg["response"]["response"]["behavior"], "allow");
+    }
+
     #[tokio::test]
     async fn test_handle_incoming_can_use_tool_with_handler() {
         let transport = Arc::new(MockTransport::new());
@@ -792,6 +1388,7 @@ mod tests {
         let request = IncomingControlRequest::CanUseTool {
             tool_name: "Read".to_string(),
             tool_input: json!({}),
+            context: ToolPermissionContext::default(),
         };
         control.handle_incoming("req_1", request).await;
 
@@ -807,7 +1404,7 @@ mod tests {
         assert_eq!(msg["response"]["subtype"], "success");
         assert_eq!(msg["response"]["request_id"], "req_1");
         // Data is nested inside response.response (matches Python SDK format)
-        assert_eq!(msg["response"]["response"]["allowed"], true);
+        assert_eq!(msg["response"]["response"]["behavior"], "allow");
     }
 
     #[tokio::test]
@@ -819,12 +1416,139 @@ mod tests {
         let request = IncomingControlRequest::CanUseTool {
             tool_name: "Bash".to_string(),
             tool_input: json!({}),
+            context: ToolPermissionContext::default(),
         };
         control.handle_incoming("req_1", request).await;
 
         let sent = transport.get_sent().await;
         let msg: Value = serde_json::from_slice(&sent[0]).unwrap();
-        assert_eq!(msg["response"]["response"]["allowed"], true);
+        assert_eq!(msg["response"]["response"]["behavior"], "allow");
+    }
+
+    #[tokio::test]
+    async fn test_handle_incoming_can_use_tool_deny_message() {
+        let transport = Arc::new(MockTransport::new());
+        let control = ControlProtocol::new(transport.clone() as Arc<dyn Transport>);
+        {
+            let mut handlers = control.handlers().await;
+            handlers.register_can_use_tool(Arc::new(MockCanUseToolHandler));
+        }
+
+        let request = IncomingControlRequest::CanUseTool {
+            tool_name: "Bash".to_string(),
+            tool_input: json!({ "command": "curl example.com" }),
+            context: ToolPermissionContext::default(),
+        };
+        control.handle_incoming("req_1", request).await;
+
+        let sent = transport.get_sent().await;
+        let msg: Value = serde_json::from_slice(&sent[0]).unwrap();
+        assert_eq!(
+            msg["response"]["response"],
+            json!({
+                "behavior": "deny",
+                "message": "Only Read is allowed",
+                "interrupt": false
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_incoming_can_use_tool_handler_error_denies() {
+        let transport = Arc::new(MockTransport::new());
+        let control = ControlProtocol::new(transport.clone() as Arc<dyn Transport>);
+        {
+            let mut handlers = control.handlers().await;
+            handlers.register_can_use_tool(Arc::new(MockCanUseToolHandler));
+        }
+
+        let request = IncomingControlRequest::CanUseTool {
+            tool_name: "Broken".to_string(),
+            tool_input: json!({}),
+            context: ToolPermissionContext::default(),
+        };
+        control.handle_incoming("req_1", request).await;
+
+        let sent = transport.get_sent().await;
+        let msg: Value = serde_json::from_slice(&sent[0]).unwrap();
+        assert_eq!(msg["response"]["subtype"], "success");
+        assert_eq!(msg["response"]["response"]["behavior"], "deny");
+        assert_eq!(
+            msg["response"]["response"]["message"],
+            ClawError::ToolExecution("policy lookup failed".to_string()).to_string()
+        );
+    }
+
+    /// Permission handler that accepts every suggestion it is given
+    struct AcceptSuggestionsHandler {
+        seen: Arc<std::sync::Mutex<Option<ToolPermissionContext>>>,
+    }
+
+    #[async_trait]
+    impl CanUseToolHandler for AcceptSuggestionsHandler {
+        async fn can_use_tool(
+            &self,
+            _tool_name: &str,
+            _tool_input: &Value,
+            context: &ToolPermissionContext,
+        ) -> Result<crate::permissions::PermissionDecision, ClawError> {
+            *self.seen.lock().unwrap() = Some(context.clone());
+            Ok(crate::permissions::PermissionDecision::Allow {
+                updated_input: None,
+                updated_permissions: context.suggestions.clone(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_incoming_can_use_tool_context_and_updates() {
+        use crate::permissions::{PermissionRuleValue, PermissionUpdate};
+
+        let transport = Arc::new(MockTransport::new());
+        let control = ControlProtocol::new(transport.clone() as Arc<dyn Transport>);
+        let seen = Arc::new(std::sync::Mutex::new(None));
+        {
+            let mut handlers = control.handlers().await;
+            handlers
+                .register_can_use_tool(Arc::new(AcceptSuggestionsHandler { seen: seen.clone() }));
+        }
+
+        let request: IncomingControlRequest = serde_json::from_value(json!({
+            "subtype": "can_use_tool",
+            "tool_name": "Bash",
+            "input": {"command": "npm test"},
+            "permission_suggestions": [{
+                "type": "addRules",
+                "rules": [{"toolName": "Bash", "ruleContent": "npm test"}],
+                "behavior": "allow",
+                "destination": "session"
+            }],
+            "tool_use_id": "toolu_01",
+            "agent_id": "agent_1"
+        }))
+        .unwrap();
+        control.handle_incoming("req_1", request).await;
+
+        let context = seen.lock().unwrap().clone().unwrap();
+        assert_eq!(context.tool_use_id.as_deref(), Some("toolu_01"));
+        assert_eq!(context.agent_id.as_deref(), Some("agent_1"));
+        assert_eq!(
+            context.suggestions,
+            vec![PermissionUpdate::allow_in_session(
+                PermissionRuleValue::new("Bash").with_content("npm test")
+            )]
+        );
+
+        let sent = transport.get_sent().await;
+        let msg: Value = serde_json::from_slice(&sent[0]).unwrap();
+        let data = &msg["response"]["response"];
+        assert_eq!(data["behavior"], "allow");
+        assert_eq!(data["updatedPermissions"][0]["type"], "addRules");
+        assert_eq!(
+            data["updatedPermissions"][0]["rules"][0]["ruleContent"],
+            "npm test"
+        );
+        assert_eq!(data["updatedPermissions"][0]["destination"], "session");
     }
 
     #[tokio::test]
@@ -881,4 +1605,170 @@ mod tests {
             "test_server"
         );
     }
+
+    #[tokio::test]
+    async fn test_dispatch_incoming_does_not_block_other_requests() {
+        let transport = Arc::new(MockTransport::new());
+        let control = Arc::new(ControlProtocol::new(transport.clone() as Arc<dyn Transport>));
+        let gate = Arc::new(Semaphore::new(0));
+        {
+            let mut handlers = control.handlers().await;
+            handlers.register_can_use_tool(Arc::new(GatedCanUseToolHandler::new(gate.clone())));
+            handlers.register_mcp_message(Arc::new(MockMcpHandler));
+        }
+
+        control.dispatch_incoming("slow".to_string(), can_use_tool_request());
+        control.dispatch_incoming(
+            "fast".to_string(),
+            IncomingControlRequest::McpMessage {
+                server_name: "test_server".to_string(),
+                message: json!({ "method": "test" }),
+            },
+        );
+
+        // The slow handler cannot answer before the gate opens
+        let sent = transport.wait_for_sent(1).await;
+        assert_eq!(sent_request_ids(&sent), vec!["fast"]);
+
+        gate.add_permits(1);
+        let sent = transport.wait_for_sent(2).await;
+        assert_eq!(sent_request_ids(&sent), vec!["fast", "slow"]);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_incoming_respects_concurrency_limit() {
+        let transport = Arc::new(MockTransport::new());
+        let control = Arc::new(
+            ControlProtocol::new(transport.clone() as Arc<dyn Transport>)
+                .with_max_concurrent_requests(1),
+        );
+        let gate = Arc::new(Semaphore::new(0));
+        let handler = Arc::new(GatedCanUseToolHandler::new(gate.clone()));
+        {
+            let mut handlers = control.handlers().await;
+            handlers.register_can_use_tool(handler.clone());
+            handlers.register_mcp_message(Arc::new(MockMcpHandler));
+        }
+
+        control.dispatch_incoming("slow".to_string(), can_use_tool_request());
+        handler.entered.notified().await;
+        control.dispatch_incoming(
+            "queued".to_string(),
+            IncomingControlRequest::McpMessage {
+                server_name: "test_server".to_string(),
+                message: json!({ "method": "test" }),
+            },
+        );
+
+        // The only slot is taken by the slow request, so the queued one,
+        // which needs no gate, still answers second
+        assert_eq!(control.incoming_limit.available_permits(), 0);
+        gate.add_permits(1);
+        let sent = transport.wait_for_sent(2).await;
+        assert_eq!(sent_request_ids(&sent), vec!["slow", "queued"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_incoming_aborts_handler() {
+        let transport = Arc::new(MockTransport::new());
+        let control = Arc::new(ControlProtocol::new(transport.clone() as Arc<dyn Transport>));
+        let gate = Arc::new(Semaphore::new(0));
+        let handler = Arc::new(GatedCanUseToolHandler::new(gate.clone()));
+        control
+            .handlers()
+            .await
+            .register_can_use_tool(handler.clone());
+
+        control.dispatch_incoming("prompt".to_string(), can_use_tool_request());
+        handler.entered.notified().await;
+        assert!(control.cancel_incoming("prompt"));
+        assert!(!control.cancel_incoming("prompt"));
+        assert!(!control.cancel_incoming("unknown"));
+
+        // The handler was dropped: releasing the gate produces no response
+        handler.exited.notified().await;
+        gate.add_permits(1);
+        assert!(transport.get_sent().await.is_empty());
+        assert_eq!(gate.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_incoming_cancels_handler_token() {
+        /// Hands the request's cancellation token to the test, then waits forever
+        struct TokenHandler {
+            tokens: mpsc::UnboundedSender<CancellationToken>,
+        }
+
+        #[async_trait]
+        impl CanUseToolHandler for TokenHandler {
+            async fn can_use_tool(
+                &self,
+                _tool_name: &str,
+                _tool_input: &Value,
+                context: &ToolPermissionContext,
+            ) -> Result<crate::permissions::PermissionDecision, ClawError> {
+                self.tokens.send(context.cancellation.clone()).unwrap();
+                std::future::pending().await
+            }
+        }
+
+        let transport = Arc::new(MockTransport::new());
+        let control = Arc::new(ControlProtocol::new(transport.clone() as Arc<dyn Transport>));
+        let (tokens, mut received) = mpsc::unbounded_channel();
+        control
+            .handlers()
+            .await
+            .register_can_use_tool(Arc::new(TokenHandler { tokens }));
+
+        control.dispatch_incoming("prompt".to_string(), can_use_tool_request());
+        let token = received.recv().await.unwrap();
+        assert!(!token.is_cancelled());
+        control.cancel_incoming("prompt");
+        assert!(token.is_cancelled());
+
+        // Abandoning the transport (crash or close) cancels every request
+        control.dispatch_incoming("other".to_string(), can_use_tool_request());
+        let token = received.recv().await.unwrap();
+        control.abandon_all().await;
+        assert!(token.is_cancelled());
+        assert!(transport.get_sent().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handle_incoming_mcp_message_prefers_sdk_server() {
+        use crate::mcp_server::SdkMcpServerImpl;
+
+        let transport = Arc::new(MockTransport::new());
+        let control = ControlProtocol::new(transport.clone() as Arc<dyn Transport>);
+        {
+            let mut handlers = control.handlers().await;
+            handlers.register_mcp_message(Arc::new(MockMcpHandler));
+            handlers.register_sdk_mcp_server(SdkMcpServerImpl::new("calc", "2.0.0"));
+        }
+
+        let request = IncomingControlRequest::McpMessage {
+            server_name: "calc".to_string(),
+            message: json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"}),
+        };
+        control.handle_incoming("req_1", request).await;
+
+        let sent = transport.get_sent().await;
+        let msg: Value = serde_json::from_slice(&sent[0]).unwrap();
+        assert_eq!(msg["response"]["subtype"], "success");
+        let mcp_response = &msg["response"]["response"]["mcp_response"];
+        assert_eq!(mcp_response["result"]["serverInfo"]["name"], "calc");
+
+        // Other servers still go to the McpMessageHandler
+        let request = IncomingControlRequest::McpMessage {
+            server_name: "other".to_string(),
+            message: json!({"method": "test"}),
+        };
+        control.handle_incoming("req_2", request).await;
+        let sent = transport.get_sent().await;
+        let msg: Value = serde_json::from_slice(&sent[1]).unwrap();
+        assert_eq!(
+            msg["response"]["response"]["mcp_response"]["server"],
+            "other"
+        );
+    }
 }
@@ -47,7 +47,7 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{Mutex, oneshot};
+use tokio::sync::{Mutex, oneshot, watch};
 
 use crate::control::messages::ControlResponse;
 
@@ -65,6 +65,8 @@ use crate::control::messages::ControlResponse;
 pub struct PendingRequests {
     /// Map of request_id → response sender
     inner: Arc<Mutex<HashMap<String, oneshot::Sender<ControlResponse>>>>,
+    /// Number of entries in `inner`, updated under its lock
+    count: watch::Sender<usize>,
 }
 
 impl PendingRequests {
@@ -72,9 +74,15 @@ impl PendingRequests {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(HashMap::new())),
+            count: watch::Sender::new(0),
         }
     }
 
+    /// Watch the number of pending requests
+    pub(crate) fn watch_len(&self) -> watch::Receiver<usize> {
+        self.count.subscribe()
+    }
+
     /// Insert a new pending request
     ///
     /// Stores the oneshot sender for the given request ID. When the response
@@ -100,7 +108,9 @@ impl PendingRequests {
     /// # }
     /// ```
     pub async fn insert(&self, id: String, sender: oneshot::Sender<ControlResponse>) {
-        self.inner.lock().await.insert(id, sender);
+        let mut inner = self.inner.lock().await;
+        inner.insert(id, sender);
+        self.count.send_replace(inner.len());
     }
 
     /// Complete a pending request with a response
@@ -138,10 +148,13 @@ impl PendingRequests {
     /// # }
     /// ```
     pub async fn complete(&self, id: &str, response: ControlResponse) -> bool {
-        if let Some(sender) = self.inner.lock().await.remove(id) {
-            sender.send(response).is_ok()
-        } else {
-            false
+        let mut inner = self.inner.lock().await;
+        let sender = inner.remove(id);
+        self.count.send_replace(inner.len());
+        drop(inner);
+        match sender {
+            Some(sender) => sender.send(response).is_ok(),
+            None => false,
         }
     }
 
@@ -172,7 +185,20 @@ impl PendingRequests {
     /// # }
     /// ```
     pub async fn cancel(&self, id: &str) {
-        self.inner.lock().await.remove(id);
+        let mut inner = self.inner.lock().await;
+        inner.remove(id);
+        self.count.send_replace(inner.len());
+    }
+
+    /// Cancel every pending request
+    ///
+    /// Drops all stored senders, so each waiting caller observes a closed
+    /// response channel instead of waiting for its timeout. Used when the
+    /// transport the requests were written to has gone away.
+    pub async fn cancel_all(&self) {
+        let mut inner = self.inner.lock().await;
+        inner.clear();
+        self.count.send_replace(0);
     }
 
     /// Get the number of pending requests
@@ -233,6 +259,21 @@ mod tests {
         assert!(!sent);
     }
 
+    #[tokio::test]
+    async fn test_cancel_all_closes_waiters() {
+        let pending = PendingRequests::new();
+        let (tx1, rx1) = oneshot::channel();
+        let (tx2, rx2) = oneshot::channel();
+        pending.insert("req_1".to_string(), tx1).await;
+        pending.insert("req_2".to_string(), tx2).await;
+
+        pending.cancel_all().await;
+
+        assert!(pending.is_empty().await);
+        assert!(rx1.await.is_err());
+        assert!(rx2.await.is_err());
+    }
+
     #[tokio::test]
     async fn test_cancel() {
         let pending = PendingRequests::new();
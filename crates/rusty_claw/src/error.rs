@@ -12,9 +12,14 @@
 //! - `ClawError::JsonDecode`: JSONL parsing errors (auto-converts from `serde_json::Error`)
 //! - `ClawError::MessageParse`: Malformed control protocol messages
 //! - `ClawError::ControlTimeout`: Control protocol request timeouts
+//! - `ClawError::ControlCancelled`: Control protocol request cancelled by the caller
 //! - `ClawError::ControlError`: Control protocol semantic errors
 //! - `ClawError::Io`: Filesystem and I/O operations (auto-converts from `std::io::Error`)
 //! - `ClawError::ToolExecution`: MCP tool handler failures
+//! - `ClawError::SubscriberLagged`: A session subscriber fell behind and missed messages
+//! - `ClawError::ChannelOverflow`: A bounded message channel filled up under `OverflowPolicy::Error`
+//! - `ClawError::StructuredOutput`: Structured output did not match the requested type
+//! - `ClawError::RateLimited`: A rate limit blocks new turns under the configured `RateLimitPolicy`
 //!
 //! # Example
 //!
@@ -114,6 +119,16 @@ pub enum ClawError {
         subtype: String,
     },
 
+    /// Control protocol request was cancelled before the CLI responded
+    ///
+    /// Returned by [`ControlProtocol::request_with`](crate::control::ControlProtocol::request_with)
+    /// when its cancellation token fires.
+    #[error("Control protocol request {subtype} was cancelled")]
+    ControlCancelled {
+        /// The request subtype that was cancelled (e.g., "interrupt")
+        subtype: String,
+    },
+
     /// Control protocol semantic error
     ///
     /// This error occurs when the control protocol returns an error response,
@@ -133,6 +148,52 @@ pub enum ClawError {
     /// This error occurs when a registered tool handler returns an error during execution.
     #[error("Tool execution failed: {0}")]
     ToolExecution(String),
+
+    /// A session subscriber fell behind and missed messages
+    ///
+    /// Yielded by [`SessionStream`](crate::client::SessionStream) when the
+    /// subscriber did not keep up with the session's broadcast buffer. The
+    /// stream keeps going with the oldest message still buffered.
+    #[error("Session subscriber lagged behind, {skipped} messages were dropped")]
+    SubscriberLagged {
+        /// Number of messages the subscriber missed
+        skipped: u64,
+    },
+
+    /// A bounded message channel was full under [`OverflowPolicy::Error`](crate::options::OverflowPolicy::Error)
+    ///
+    /// Delivered after the messages already buffered; the producer stops
+    /// delivering to that channel afterwards.
+    #[error("Message channel overflowed its capacity of {capacity}")]
+    ChannelOverflow {
+        /// Configured channel capacity
+        capacity: usize,
+    },
+
+    /// Structured output could not be deserialized into the requested type
+    ///
+    /// Returned by [`ResultMessage::structured`](crate::messages::ResultMessage::structured)
+    /// and the APIs built on it. `raw` holds the payload the model produced
+    /// (or the result text / error fields when there was none).
+    #[error("Structured output did not match the expected type: {reason}")]
+    StructuredOutput {
+        /// Why deserialization failed
+        reason: String,
+        /// The raw structured output
+        raw: serde_json::Value,
+    },
+
+    /// A rate limit blocks new turns and the [`RateLimitPolicy`](crate::options::RateLimitPolicy) does not wait it out
+    ///
+    /// Returned by [`ClaudeClient::send_message`](crate::client::ClaudeClient::send_message)
+    /// before anything is sent to the CLI.
+    #[error("Rate limit {limit} reached, resets in {retry_after:?}")]
+    RateLimited {
+        /// Which limit was hit (e.g., "five_hour")
+        limit: String,
+        /// Time until the limit resets
+        retry_after: std::time::Duration,
+    },
 }
 
 #[cfg(test)]
@@ -195,6 +256,17 @@ mod tests {
         assert!(err.to_string().contains("prompt_response"));
     }
 
+    #[test]
+    fn test_control_cancelled_error() {
+        let err = ClawError::ControlCancelled {
+            subtype: "interrupt".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Control protocol request interrupt was cancelled"
+        );
+    }
+
     #[test]
     fn test_control_error() {
         let err = ClawError::ControlError("permission denied".to_string());
@@ -207,6 +279,45 @@ mod tests {
         assert!(err.to_string().contains("handler panicked"));
     }
 
+    #[test]
+    fn test_structured_output_error() {
+        let err = ClawError::StructuredOutput {
+            reason: "missing field `title`".to_string(),
+            raw: serde_json::json!({"name": "x"}),
+        };
+        assert!(err.to_string().contains("missing field `title`"));
+    }
+
+    #[test]
+    fn test_channel_overflow_message() {
+        let err = ClawError::ChannelOverflow { capacity: 64 };
+        assert_eq!(
+            err.to_string(),
+            "Message channel overflowed its capacity of 64"
+        );
+    }
+
+    #[test]
+    fn test_rate_limited_message() {
+        let err = ClawError::RateLimited {
+            limit: "five_hour".to_string(),
+            retry_after: std::time::Duration::from_secs(90),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Rate limit five_hour reached, resets in 90s"
+        );
+    }
+
+    #[test]
+    fn test_subscriber_lagged_message() {
+        let err = ClawError::SubscriberLagged { skipped: 12 };
+        assert_eq!(
+            err.to_string(),
+            "Session subscriber lagged behind, 12 messages were dropped"
+        );
+    }
+
     #[test]
     fn test_io_error_conversion() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
@@ -18,6 +18,8 @@
 //! - `HookInput` - Data passed to hooks
 //! - `HookContext` - Session context available to hooks
 //! - `HookResponse` - Response with permission decisions
+//! - `HookRegistration` - A callback bound to an event and matcher, sent in the
+//!   initialize handshake
 //!
 //! # Examples
 //!
@@ -84,10 +86,13 @@
 //! ```
 
 mod callback;
+mod registration;
 mod response;
 mod types;
 
 pub use callback::HookCallback;
+pub(crate) use registration::HookCallbackAdapter;
+pub use registration::HookRegistration;
 pub use response::{HookOutput, HookResponse, PermissionDecision};
 pub use types::{HookContext, HookEventInput, HookInput};
 
@@ -0,0 +1,374 @@
+//! Typed hook registrations and their control protocol adapter.
+
+use crate::control::handlers::HookHandler;
+use crate::error::ClawError;
+use crate::hooks::{HookCallback, HookContext, HookEventInput, HookInput, HookResponse};
+use crate::options::{HookEvent, HookMatcher, HookTimeoutBehavior};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// A [`HookCallback`] registered for one event and tool matcher.
+///
+/// Registrations are usually created through
+/// [`ClaudeAgentOptionsBuilder::hook`](crate::options::ClaudeAgentOptionsBuilder::hook).
+/// During the initialize handshake each registration is assigned a callback ID
+/// (`hook_0`, `hook_1`, ...) that the CLI sends back when the hook fires.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_claw::prelude::*;
+/// use async_trait::async_trait;
+///
+/// struct BlockBash;
+///
+/// #[async_trait]
+/// impl HookCallback for BlockBash {
+///     async fn call(
+///         &self,
+///         _input: HookInput,
+///         _tool_use_id: Option<&str>,
+///         _context: &HookContext,
+///     ) -> Result<HookResponse, ClawError> {
+///         Ok(HookResponse::deny("Bash is disabled"))
+///     }
+/// }
+///
+/// let registration = HookRegistration::new(
+///     HookEvent::PreToolUse,
+///     HookMatcher::tool("Bash"),
+///     BlockBash,
+/// );
+/// assert_eq!(registration.event, HookEvent::PreToolUse);
+/// ```
+#[derive(Clone)]
+pub struct HookRegistration {
+    /// Event the callback is registered for
+    pub event: HookEvent,
+    /// Tool matcher and timeout for the callback
+    pub matcher: HookMatcher,
+    /// Callback invoked when the hook fires
+    pub callback: Arc<dyn HookCallback>,
+}
+
+impl HookRegistration {
+    /// Create a registration for `callback` on `event`, filtered by `matcher`
+    pub fn new(
+        event: HookEvent,
+        matcher: HookMatcher,
+        callback: impl HookCallback + 'static,
+    ) -> Self {
+        Self {
+            event,
+            matcher,
+            callback: Arc::new(callback),
+        }
+    }
+}
+
+impl std::fmt::Debug for HookRegistration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HookRegistration")
+            .field("event", &self.event)
+            .field("matcher", &self.matcher)
+            .field("callback", &"<HookCallback>")
+            .finish()
+    }
+}
+
+/// Bridges a typed [`HookRegistration`] to the raw JSON [`HookHandler`] interface.
+///
+/// Parses the CLI `hook_input` into [`HookEventInput`] / [`HookInput`], applies
+/// the matcher's tool filter and timeout, and converts the returned
+/// [`HookResponse`] into the CLI hook output shape. A timed-out callback
+/// answers with the matcher's [`HookTimeoutBehavior`].
+pub(crate) struct HookCallbackAdapter {
+    registration: HookRegistration,
+}
+
+impl HookCallbackAdapter {
+    pub(crate) fn new(registration: HookRegistration) -> Self {
+        Self { registration }
+    }
+}
+
+#[async_trait]
+impl HookHandler for HookCallbackAdapter {
+    async fn call(&self, hook_event: HookEvent, hook_input: Value) -> Result<Value, ClawError> {
+        let matcher = &self.registration.matcher;
+        let tool_use_id = hook_input
+            .get("tool_use_id")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let session_id = hook_input
+            .get("session_id")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let event_input = parse_event_input(&hook_event, &hook_input);
+
+        // The CLI pattern is a superset of the glob; re-check so globs behave as documented
+        if let Some(tool_name) = event_input.as_ref().and_then(HookEventInput::tool_name)
+            && !matcher.matches(tool_name)
+        {
+            return Ok(HookResponse::default().to_cli_output(&hook_event));
+        }
+
+        let input = match &event_input {
+            Some(typed) => HookInput::from(typed.clone()),
+            None => serde_json::from_value(hook_input).unwrap_or_default(),
+        };
+        let context = HookContext {
+            session_id,
+            event: event_input,
+            ..Default::default()
+        };
+
+        let call = self
+            .registration
+            .callback
+            .call(input, tool_use_id.as_deref(), &context);
+        let response = match matcher.timeout_ms {
+            Some(ms) => match tokio::time::timeout(Duration::from_millis(ms), call).await {
+                Ok(result) => result?,
+                Err(_) => {
+                    let behavior = matcher
+                        .on_timeout
+                        .unwrap_or_else(|| HookTimeoutBehavior::default_for(&hook_event));
+                    warn!(
+                        "Hook callback for {:?} timed out after {}ms, applying {:?}",
+                        hook_event, ms, behavior
+                    );
+                    match behavior {
+                        HookTimeoutBehavior::Allow => HookResponse::default(),
+                        HookTimeoutBehavior::Deny => {
+                            HookResponse::deny(format!("Hook timed out after {}ms", ms))
+                        }
+                    }
+                }
+            },
+            None => call.await?,
+        };
+
+        Ok(response.to_cli_output(&hook_event))
+    }
+}
+
+/// Parse the CLI hook payload, filling in `hook_event_name` when the CLI omits it.
+fn parse_event_input(hook_event: &HookEvent, hook_input: &Value) -> Option<HookEventInput> {
+    let mut value = hook_input.clone();
+    if let Some(obj) = value.as_object_mut()
+        && !obj.contains_key("hook_event_name")
+    {
+        obj.insert(
+            "hook_event_name".to_string(),
+            serde_json::to_value(hook_event).ok()?,
+        );
+    }
+    serde_json::from_value(value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Records what the adapter passed in and denies `rm` commands
+    #[derive(Default)]
+    #[allow(clippy::type_complexity)]
+    struct RecordingHook {
+        seen: Arc<Mutex<Vec<(HookInput, Option<String>, HookContext)>>>,
+    }
+
+    #[async_trait]
+    impl HookCallback for RecordingHook {
+        async fn call(
+            &self,
+            input: HookInput,
+            tool_use_id: Option<&str>,
+            context: &HookContext,
+        ) -> Result<HookResponse, ClawError> {
+            self.seen.lock().unwrap().push((
+                input.clone(),
+                tool_use_id.map(str::to_string),
+                context.clone(),
+            ));
+            let cmd = input
+                .tool_input
+                .as_ref()
+                .and_then(|v| v.get("command"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if cmd.starts_with("rm") {
+                Ok(HookResponse::deny("rm is blocked"))
+            } else {
+                Ok(HookResponse::default())
+            }
+        }
+    }
+
+    struct SlowHook;
+
+    #[async_trait]
+    impl HookCallback for SlowHook {
+        async fn call(
+            &self,
+            _input: HookInput,
+            _tool_use_id: Option<&str>,
+            _context: &HookContext,
+        ) -> Result<HookResponse, ClawError> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(HookResponse::deny("too late"))
+        }
+    }
+
+    fn adapter(matcher: HookMatcher, callback: impl HookCallback + 'static) -> HookCallbackAdapter {
+        HookCallbackAdapter::new(HookRegistration::new(
+            HookEvent::PreToolUse,
+            matcher,
+            callback,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_adapter_parses_input_and_converts_response() {
+        let hook = RecordingHook::default();
+        let seen = hook.seen.clone();
+        let adapter = adapter(HookMatcher::tool("Bash"), hook);
+
+        let output = adapter
+            .call(
+                HookEvent::PreToolUse,
+                json!({
+                    "hook_event_name": "PreToolUse",
+                    "session_id": "sess-1",
+                    "tool_use_id": "toolu_1",
+                    "tool_name": "Bash",
+                    "tool_input": {"command": "rm -rf /"}
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output["hookSpecificOutput"]["permissionDecision"], "deny");
+        assert_eq!(
+            output["hookSpecificOutput"]["permissionDecisionReason"],
+            "rm is blocked"
+        );
+
+        let seen = seen.lock().unwrap();
+        let (input, tool_use_id, context) = &seen[0];
+        assert_eq!(input.tool_name.as_deref(), Some("Bash"));
+        assert_eq!(tool_use_id.as_deref(), Some("toolu_1"));
+        assert_eq!(context.session_id.as_deref(), Some("sess-1"));
+        assert!(matches!(
+            context.event,
+            Some(HookEventInput::PreToolUse { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_adapter_fills_missing_event_name() {
+        let hook = RecordingHook::default();
+        let seen = hook.seen.clone();
+        let adapter = adapter(HookMatcher::all(), hook);
+
+        adapter
+            .call(
+                HookEvent::PreToolUse,
+                json!({"tool_name": "Read", "tool_input": {"file_path": "/tmp/a"}}),
+            )
+            .await
+            .unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert!(seen[0].2.event.is_some());
+        assert_eq!(seen[0].0.tool_name.as_deref(), Some("Read"));
+    }
+
+    #[tokio::test]
+    async fn test_adapter_skips_non_matching_tool() {
+        let hook = RecordingHook::default();
+        let seen = hook.seen.clone();
+        let adapter = adapter(HookMatcher::tool("mcp__*"), hook);
+
+        let output = adapter
+            .call(
+                HookEvent::PreToolUse,
+                json!({"tool_name": "Bash", "tool_input": {"command": "rm x"}}),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output, json!({ "continue": true }));
+        assert!(seen.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_adapter_timeout_denies_pre_tool_use_by_default() {
+        let adapter = adapter(HookMatcher::all().with_timeout_ms(10), SlowHook);
+
+        let output = adapter
+            .call(
+                HookEvent::PreToolUse,
+                json!({"tool_name": "Bash", "tool_input": {}}),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output["hookSpecificOutput"]["permissionDecision"], "deny");
+        assert_eq!(
+            output["hookSpecificOutput"]["permissionDecisionReason"],
+            "Hook timed out after 10ms"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_adapter_timeout_behavior_is_configurable() {
+        // Explicit allow lets the tool run
+        let allow = adapter(
+            HookMatcher::all()
+                .with_timeout_ms(10)
+                .with_timeout_behavior(HookTimeoutBehavior::Allow),
+            SlowHook,
+        );
+        let output = allow
+            .call(
+                HookEvent::PreToolUse,
+                json!({"tool_name": "Bash", "tool_input": {}}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output, json!({ "continue": true }));
+
+        // Non-permission events continue by default
+        let post = adapter(HookMatcher::all().with_timeout_ms(10), SlowHook);
+        let output = post
+            .call(
+                HookEvent::PostToolUse,
+                json!({"tool_name": "Bash", "tool_input": {}, "tool_response": {}}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output, json!({ "continue": true }));
+
+        // Explicit deny blocks them
+        let block = adapter(
+            HookMatcher::all()
+                .with_timeout_ms(10)
+                .with_timeout_behavior(HookTimeoutBehavior::Deny),
+            SlowHook,
+        );
+        let output = block
+            .call(
+                HookEvent::PostToolUse,
+                json!({"tool_name": "Bash", "tool_input": {}, "tool_response": {}}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output["decision"], "block");
+    }
+}
@@ -1,7 +1,8 @@
 //! Hook response types for permission decisions and context injection.
 
+use crate::options::HookEvent;
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{Map, Value, json};
 
 /// Permission decision for tool use or other controlled actions.
 ///
@@ -127,8 +128,9 @@ impl HookOutput {
             },
             HookOutput::Stop { reason } => HookResponse {
                 permission_decision: Some(PermissionDecision::Deny),
-                permission_decision_reason: Some(reason),
+                permission_decision_reason: Some(reason.clone()),
                 should_continue: false,
+                stop_reason: Some(reason),
                 ..Default::default()
             },
             HookOutput::SuppressOutput => HookResponse {
@@ -315,6 +317,108 @@ impl HookResponse {
         self.suppress_output = suppress;
         self
     }
+
+    /// Convert into the hook output JSON the CLI expects for `event`
+    ///
+    /// Common fields (`continue`, `suppressOutput`, `stopReason`, `systemMessage`)
+    /// are emitted at the top level. Event-specific fields such as the
+    /// `PreToolUse` permission decision are nested under `hookSpecificOutput`.
+    ///
+    /// A permission decision only affects the current tool call: `continue` is
+    /// `false` when a stop reason is set, or when `should_continue` is `false`
+    /// on a response that carries no permission decision.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_claw::prelude::*;
+    ///
+    /// let output = HookResponse::deny("No rm -rf").to_cli_output(&HookEvent::PreToolUse);
+    /// assert_eq!(output["continue"], true);
+    /// assert_eq!(output["hookSpecificOutput"]["hookEventName"], "PreToolUse");
+    /// assert_eq!(output["hookSpecificOutput"]["permissionDecision"], "deny");
+    /// ```
+    pub fn to_cli_output(&self, event: &HookEvent) -> Value {
+        let mut output = Map::new();
+        let stop = self.stop_reason.is_some()
+            || (!self.should_continue && self.permission_decision.is_none());
+        output.insert("continue".to_string(), json!(!stop));
+        if self.suppress_output {
+            output.insert("suppressOutput".to_string(), json!(true));
+        }
+        if let Some(reason) = &self.stop_reason {
+            output.insert("stopReason".to_string(), json!(reason));
+        }
+        if let Some(message) = &self.system_message {
+            output.insert("systemMessage".to_string(), json!(message));
+        }
+
+        let mut specific = Map::new();
+        match event {
+            HookEvent::PreToolUse => {
+                if let Some(decision) = &self.permission_decision {
+                    specific.insert("permissionDecision".to_string(), json!(decision));
+                }
+                if let Some(reason) = &self.permission_decision_reason {
+                    specific.insert("permissionDecisionReason".to_string(), json!(reason));
+                }
+                if let Some(input) = &self.updated_input {
+                    specific.insert("updatedInput".to_string(), input.clone());
+                }
+                if let Some(context) = &self.additional_context {
+                    specific.insert("additionalContext".to_string(), json!(context));
+                }
+            }
+            HookEvent::PermissionRequest => match self.permission_decision {
+                Some(PermissionDecision::Allow) => {
+                    let mut decision = json!({ "behavior": "allow" });
+                    if let Some(input) = &self.updated_input {
+                        decision["updatedInput"] = input.clone();
+                    }
+                    specific.insert("decision".to_string(), decision);
+                }
+                Some(PermissionDecision::Deny) => {
+                    let mut decision = json!({ "behavior": "deny" });
+                    if let Some(reason) = &self.permission_decision_reason {
+                        decision["message"] = json!(reason);
+                    }
+                    specific.insert("decision".to_string(), decision);
+                }
+                Some(PermissionDecision::Ask) | None => {}
+            },
+            HookEvent::PostToolUse
+            | HookEvent::PostToolUseFailure
+            | HookEvent::UserPromptSubmit
+            | HookEvent::SubagentStart
+            | HookEvent::Stop
+            | HookEvent::SubagentStop => {
+                // These events block via the top-level `decision` field
+                if matches!(self.permission_decision, Some(PermissionDecision::Deny)) {
+                    output.insert("decision".to_string(), json!("block"));
+                    if let Some(reason) = &self.permission_decision_reason {
+                        output.insert("reason".to_string(), json!(reason));
+                    }
+                }
+                if !matches!(event, HookEvent::Stop | HookEvent::SubagentStop)
+                    && let Some(context) = &self.additional_context
+                {
+                    specific.insert("additionalContext".to_string(), json!(context));
+                }
+                if *event == HookEvent::PostToolUse
+                    && let Some(updated) = &self.updated_output
+                {
+                    specific.insert("updatedMCPToolOutput".to_string(), updated.clone());
+                }
+            }
+            HookEvent::PreCompact | HookEvent::Notification => {}
+        }
+
+        if !specific.is_empty() {
+            specific.insert("hookEventName".to_string(), json!(event));
+            output.insert("hookSpecificOutput".to_string(), Value::Object(specific));
+        }
+        Value::Object(output)
+    }
 }
 
 #[cfg(test)]
@@ -537,6 +641,71 @@ mod tests {
         assert_eq!(json["suppress_output"], true);
     }
 
+    #[test]
+    fn test_to_cli_output_pre_tool_use_deny() {
+        let output = HookResponse::deny("Destructive command")
+            .with_updated_input(json!({"command": "ls"}))
+            .to_cli_output(&HookEvent::PreToolUse);
+
+        // Denying a single tool call must not stop the whole session
+        assert_eq!(output["continue"], true);
+        let specific = &output["hookSpecificOutput"];
+        assert_eq!(specific["hookEventName"], "PreToolUse");
+        assert_eq!(specific["permissionDecision"], "deny");
+        assert_eq!(specific["permissionDecisionReason"], "Destructive command");
+        assert_eq!(specific["updatedInput"]["command"], "ls");
+    }
+
+    #[test]
+    fn test_to_cli_output_common_fields() {
+        let output = HookOutput::Stop {
+            reason: "Budget exhausted".to_string(),
+        }
+        .into_response()
+        .with_system_message("Stopping now")
+        .with_suppress_output(true)
+        .to_cli_output(&HookEvent::Notification);
+
+        assert_eq!(output["continue"], false);
+        assert_eq!(output["stopReason"], "Budget exhausted");
+        assert_eq!(output["systemMessage"], "Stopping now");
+        assert_eq!(output["suppressOutput"], true);
+        assert!(output.get("hookSpecificOutput").is_none());
+    }
+
+    #[test]
+    fn test_to_cli_output_default_is_minimal() {
+        let output = HookResponse::default().to_cli_output(&HookEvent::PreToolUse);
+        assert_eq!(output, json!({ "continue": true }));
+    }
+
+    #[test]
+    fn test_to_cli_output_post_tool_use() {
+        let output = HookResponse::deny("Leaked secret")
+            .with_context("Output was redacted")
+            .with_updated_output(json!({"redacted": true}))
+            .to_cli_output(&HookEvent::PostToolUse);
+
+        assert_eq!(output["decision"], "block");
+        assert_eq!(output["reason"], "Leaked secret");
+        let specific = &output["hookSpecificOutput"];
+        assert_eq!(specific["hookEventName"], "PostToolUse");
+        assert_eq!(specific["additionalContext"], "Output was redacted");
+        assert_eq!(specific["updatedMCPToolOutput"]["redacted"], true);
+    }
+
+    #[test]
+    fn test_to_cli_output_permission_request() {
+        let output =
+            HookResponse::deny("Not on allowlist").to_cli_output(&HookEvent::PermissionRequest);
+        let decision = &output["hookSpecificOutput"]["decision"];
+        assert_eq!(decision["behavior"], "deny");
+        assert_eq!(decision["message"], "Not on allowlist");
+
+        let output = HookResponse::ask("Confirm?").to_cli_output(&HookEvent::PermissionRequest);
+        assert!(output.get("hookSpecificOutput").is_none());
+    }
+
     #[test]
     fn test_hook_response_approve_without_message() {
         let out = HookOutput::Approve { message: None };
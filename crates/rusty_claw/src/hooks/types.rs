@@ -360,6 +360,13 @@ pub struct HookContext {
     /// Additional context data
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, Value>>,
+
+    /// Typed input of the event that fired the hook
+    ///
+    /// Populated when the CLI payload parses as a [`HookEventInput`], giving
+    /// callbacks access to event-specific fields not carried by [`HookInput`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<HookEventInput>,
 }
 
 impl HookContext {
@@ -87,7 +87,7 @@
 //!         match message? {
 //!             Message::Assistant(msg) => {
 //!                 for block in msg.message.content {
-//!                     if let ContentBlock::Text { text } = block {
+//!                     if let ContentBlock::Text { text, .. } = block {
 //!                         println!("{}", text);
 //!                     }
 //!                 }
@@ -170,9 +170,14 @@ pub mod permissions;
 /// - `JsonDecode` - JSONL parsing errors (auto-converts from `serde_json::Error`)
 /// - `MessageParse` - Malformed control protocol messages
 /// - `ControlTimeout` - Control protocol request timeouts
+/// - `ControlCancelled` - Control protocol request cancelled by the caller
 /// - `ControlError` - Control protocol semantic errors
 /// - `Io` - Filesystem and I/O operations (auto-converts from `std::io::Error`)
 /// - `ToolExecution` - MCP tool handler failures
+/// - `SubscriberLagged` - A session subscriber fell behind and missed messages
+/// - `ChannelOverflow` - A bounded message channel filled up under `OverflowPolicy::Error`
+/// - `StructuredOutput` - Structured output did not match the requested type
+/// - `RateLimited` - A rate limit blocks new turns under the configured `RateLimitPolicy`
 pub mod error;
 
 /// Message types and structures
@@ -194,6 +199,27 @@ pub mod error;
 /// - `Thinking` - Extended thinking tokens
 pub mod messages;
 
+/// Typed models for the built-in tools
+///
+/// This module provides typed input structs for the Claude Code built-in tools (`Bash`,
+/// `Read`, `Edit`, `Grep`, ...) and their structured results. Use `BuiltinToolCall::parse`
+/// on a tool name and JSON input, and `BuiltinToolResult::parse` on a tool result.
+pub mod builtin_tools;
+
+/// Transcript export
+///
+/// This module provides `Transcript`, which renders recorded messages or a live
+/// `ResponseStream` as Markdown (collapsible tool calls), standalone HTML (inline
+/// styles), or normalized JSONL for archiving.
+pub mod transcript;
+
+/// On-disk session history
+///
+/// This module provides `SessionStore`, which reads the session JSONL files the CLI
+/// writes under its config directory. It lists sessions per project (timestamps, first
+/// prompt, cost), loads a session's history as `Vec<Message>`, and searches session text.
+pub mod session_store;
+
 /// Simple query API for one-shot Claude interactions
 ///
 /// This module provides the `query()` function for simple, one-shot queries to Claude
@@ -214,6 +240,14 @@ pub mod options;
 /// and session interruption.
 pub mod client;
 
+/// Pool of pre-connected clients
+///
+/// This module provides `ClaudeClientPool`, which keeps warm `ClaudeClient` instances
+/// built from a template `ClaudeAgentOptions` so requests skip CLI startup and the
+/// initialize handshake. Supports checkout/checkin, idle eviction, health checks, and
+/// recycling after a configurable number of turns.
+pub mod pool;
+
 // Public API re-exports
 pub use query::query;
 
@@ -223,28 +257,41 @@ pub mod prelude {
     //!
     //! Use `use rusty_claw::prelude::*;` to import commonly used types.
 
-    pub use crate::client::{ClaudeClient, ClaudeSDKClient, ResponseStream, with_client};
-    pub use crate::control::ControlProtocol;
+    pub use crate::builtin_tools::{BuiltinToolCall, BuiltinToolResult};
+    pub use crate::client::{
+        Capabilities, ClaudeClient, ClaudeSDKClient, ResponseStream, SessionState, SessionStream,
+        with_client,
+    };
     pub use crate::control::handlers::{CanUseToolHandler, HookHandler, McpMessageHandler};
-    pub use crate::control::messages::{ControlRequest, ControlResponse, IncomingControlRequest};
+    pub use crate::control::messages::{
+        ControlRequest, ControlResponse, ControlSubtype, IncomingControlRequest,
+        InitializeResponse, McpSetServersResponse, McpStatusResponse, ServerInfo,
+    };
+    pub use crate::control::{CancellationToken, ControlProtocol};
     pub use crate::error::ClawError;
     pub use crate::hooks::{
-        HookCallback, HookContext, HookEventInput, HookInput, HookOutput, HookResponse,
-        PermissionDecision,
+        HookCallback, HookContext, HookEventInput, HookInput, HookOutput, HookRegistration,
+        HookResponse, PermissionDecision,
     };
     pub use crate::mcp_server::{
         SdkMcpServerImpl, SdkMcpServerRegistry, SdkMcpTool, ToolContent, ToolHandler, ToolResult,
         TypedToolHandler, create_sdk_mcp_server,
     };
     pub use crate::messages::{
-        ApiMessage, AssistantMessage, AssistantMessageError, ContentBlock, McpServerInfo, Message,
-        ResultMessage, StreamEvent, SystemMessage, ToolInfo, UsageInfo, UserMessage,
+        ApiMessage, AssistantMessage, AssistantMessageError, ContentBlock, McpServerInfo,
+        McpServerStatus, MediaSource, Message, ModelUsage, PartialMessageAccumulator,
+        PermissionDenial, RateLimitEvent, ResultErrorDetails, ResultMessage, StreamEvent,
+        SystemMessage, ToolInfo, UsageInfo, UserContentBlock, UserInput, UserMessage,
     };
     pub use crate::options::{
-        ClaudeAgentOptions, HookEvent, HookMatcher, McpHttpServerConfig, McpSSEServerConfig,
-        McpServerConfig, McpStdioServerConfig, PermissionMode, SdkBeta, SystemPrompt,
+        ClaudeAgentOptions, HookEvent, HookMatcher, HookTimeoutBehavior, McpHttpServerConfig,
+        McpSSEServerConfig, McpServerConfig, McpStdioServerConfig, PermissionMode, RateLimitPolicy,
+        SdkBeta, SystemPrompt,
+    };
+    pub use crate::permissions::{
+        DefaultPermissionHandler, PermissionRuleValue, PermissionUpdate, ToolPermissionContext,
     };
-    pub use crate::permissions::DefaultPermissionHandler;
-    pub use crate::query::{query, query_with_messages};
+    pub use crate::pool::{ClaudeClientPool, PoolConfig, PooledClient};
+    pub use crate::query::{query, query_structured, query_with_messages};
     pub use crate::transport::{CliDiscovery, SubprocessCLITransport, Transport};
 }
@@ -18,6 +18,17 @@
 //! - `ContentBlock::ToolUse` - Tool invocation requests
 //! - `ContentBlock::ToolResult` - Tool execution results
 //! - `ContentBlock::Thinking` - Extended thinking tokens
+//! - `ContentBlock::RedactedThinking` - Encrypted thinking tokens
+//! - `ContentBlock::Image` / `ContentBlock::Document` - Media content
+//! - `ContentBlock::ServerToolUse` / `ContentBlock::WebSearchToolResult` - Server-side tools
+//! - `ContentBlock::Unknown` - Raw JSON for block types added after this release
+//!
+//! # User Content
+//!
+//! Outgoing user turns are typed `UserInput` envelopes carrying text or
+//! `UserContentBlock` items (text, images, documents, tool results), sent via
+//! [`ClaudeClient::send_content`](crate::client::ClaudeClient::send_content) or
+//! [`ClaudeClient::send_input`](crate::client::ClaudeClient::send_input).
 //!
 //! # Test Fixtures
 //!
@@ -63,16 +74,23 @@
 //! }
 //! ```
 
-use serde::{Deserialize, Serialize};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
 
 use crate::control::messages::{ControlRequest, ControlResponse};
+use crate::error::ClawError;
+use crate::options::PermissionMode;
 
 /// Top-level message type discriminated by `type` field
 ///
 /// All messages from Claude Code CLI are wrapped in this enum.
 /// The `type` field is used for JSON deserialization.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
+#[serde(remote = "Self", tag = "type", rename_all = "snake_case")]
 pub enum Message {
     /// System lifecycle events (init, compact boundary)
     System(SystemMessage),
@@ -98,33 +116,318 @@ pub enum Message {
         #[serde(flatten)]
         response: ControlResponse,
     },
+    /// Partial streaming event, emitted when `include_partial_messages` is enabled
+    StreamEvent(StreamEvent),
     /// Rate limit information from the CLI
-    RateLimitEvent(serde_json::Value),
+    RateLimitEvent(RateLimitEvent),
     /// MCP message from the CLI (routed to SDK MCP server handler)
     #[serde(rename = "mcp_message")]
     McpMessage(serde_json::Value),
+    /// Message with a `type` this SDK does not recognize yet
+    ///
+    /// Newer CLI releases may add message types; they are surfaced here
+    /// (with a warning) instead of failing the stream. Malformed messages of
+    /// a known type are deserialization errors, not `Unknown`.
+    #[serde(skip)]
+    Unknown {
+        /// The unrecognized `type` value
+        r#type: String,
+        /// Remaining message fields
+        raw: serde_json::Value,
+    },
+}
+
+impl Serialize for Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Message::Unknown { r#type, raw } => serialize_fallback(serializer, "type", r#type, raw),
+            _ => Self::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Self::from_json(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Message {
+    /// Parse one CLI output line into a [`Message`]
+    ///
+    /// Unrecognized message types and system subtypes parse as
+    /// [`Message::Unknown`] / [`SystemMessage::Other`] and are logged at
+    /// `warn` level; only malformed known messages are errors, and their
+    /// reason includes the serde error of the field that failed.
+    pub(crate) fn parse(value: serde_json::Value) -> Result<Message, ClawError> {
+        let message = match Message::from_json(&value) {
+            Ok(message) => message,
+            Err(e) => {
+                let reason = match (
+                    value.get("type").and_then(serde_json::Value::as_str),
+                    value.get("subtype").and_then(serde_json::Value::as_str),
+                ) {
+                    (Some(r#type @ ("system" | "result")), Some(subtype)) => {
+                        format!("malformed {} {} message: {}", r#type, subtype, e)
+                    }
+                    (Some(r#type), _) => format!("malformed {} message: {}", r#type, e),
+                    (None, _) => e.to_string(),
+                };
+                return Err(ClawError::MessageParse {
+                    reason,
+                    raw: value.to_string(),
+                });
+            }
+        };
+        match &message {
+            Message::Unknown { r#type, .. } => {
+                warn!(
+                    "Unrecognized CLI message type {:?}, passing through",
+                    r#type
+                );
+            }
+            Message::System(SystemMessage::Other { subtype, .. }) => {
+                warn!(
+                    "Unrecognized CLI system message subtype {:?}, passing through",
+                    subtype
+                );
+            }
+            Message::Result(ResultMessage::Other { subtype, .. }) => {
+                warn!(
+                    "Unrecognized CLI result subtype {:?}, passing through",
+                    subtype
+                );
+            }
+            _ => {}
+        }
+        Ok(message)
+    }
+
+    fn from_json(value: &serde_json::Value) -> Result<Message, serde_json::Error> {
+        match unrecognized_tag(value, "type", KNOWN_MESSAGE_TYPES) {
+            Some(r#type) => Ok(Message::Unknown {
+                r#type: r#type.to_string(),
+                raw: without_field(value, "type"),
+            }),
+            // The derived impl has no catch-all, so its error names the bad field
+            None => Self::deserialize(value),
+        }
+    }
 }
 
+/// The `tag` value of `value` if it is a string not listed in `known`
+///
+/// Those go to an enum's catch-all variant; everything else (including a
+/// missing tag) goes through the derived, catch-all-free impl.
+fn unrecognized_tag<'a>(
+    value: &'a serde_json::Value,
+    tag: &str,
+    known: &[&str],
+) -> Option<&'a str> {
+    value
+        .get(tag)
+        .and_then(serde_json::Value::as_str)
+        .filter(|t| !known.contains(t))
+}
+
+/// `value` without its `field` key, as stored in a catch-all variant's `raw`
+fn without_field(value: &serde_json::Value, field: &str) -> serde_json::Value {
+    let mut raw = value.clone();
+    if let Some(map) = raw.as_object_mut() {
+        map.remove(field);
+    }
+    raw
+}
+
+/// Serialize a catch-all variant back to its original object
+fn serialize_fallback<S: Serializer>(
+    serializer: S,
+    tag: &str,
+    tag_value: &str,
+    raw: &serde_json::Value,
+) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeMap;
+
+    let fields = raw.as_object();
+    let mut map = serializer.serialize_map(Some(1 + fields.map_or(0, |f| f.len())))?;
+    map.serialize_entry(tag, tag_value)?;
+    for (key, value) in fields.into_iter().flatten() {
+        map.serialize_entry(key, value)?;
+    }
+    map.end()
+}
+
+/// Wire `type` values with a dedicated [`Message`] variant
+const KNOWN_MESSAGE_TYPES: &[&str] = &[
+    "system",
+    "assistant",
+    "user",
+    "result",
+    "control_request",
+    "control_response",
+    "stream_event",
+    "rate_limit_event",
+    "mcp_message",
+];
+
+/// Wire `subtype` values with a dedicated [`SystemMessage`] variant
+const KNOWN_SYSTEM_SUBTYPES: &[&str] = &["init", "compact_boundary", "reconnected"];
+
+/// Wire `subtype` values with a dedicated [`ResultMessage`] variant
+const KNOWN_RESULT_SUBTYPES: &[&str] = &[
+    "success",
+    "error",
+    "error_max_turns",
+    "error_max_budget_usd",
+    "error_during_execution",
+    "error_max_structured_output_retries",
+    "input_required",
+];
+
 /// System message variants discriminated by `subtype` field
 ///
 /// System messages represent lifecycle events in the agent session.
+// Init is sent once per session; boxing it would only complicate pattern matching
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "subtype", rename_all = "snake_case")]
+#[serde(remote = "Self", tag = "subtype", rename_all = "snake_case")]
 pub enum SystemMessage {
     /// Session initialization with available tools and MCP servers
+    ///
+    /// Fields other than `session_id`, `tools`, and `mcp_servers` are optional
+    /// so older CLI versions that omit them still parse.
     Init {
         /// Unique session identifier
         session_id: String,
         /// Available tool definitions
         tools: Vec<ToolInfo>,
-        /// Connected MCP server information
+        /// MCP servers and their connection status
         mcp_servers: Vec<McpServerInfo>,
+        /// Active model
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        /// Working directory of the session
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cwd: Option<PathBuf>,
+        /// Active permission mode (`None` if absent; unrecognized modes are
+        /// kept as [`PermissionMode::Other`])
+        #[serde(
+            default,
+            rename = "permissionMode",
+            with = "cli_permission_mode",
+            skip_serializing_if = "Option::is_none"
+        )]
+        permission_mode: Option<PermissionMode>,
+        /// Where the API key came from (e.g., "user", "ANTHROPIC_API_KEY", "none")
+        #[serde(
+            default,
+            rename = "apiKeySource",
+            skip_serializing_if = "Option::is_none"
+        )]
+        api_key_source: Option<String>,
+        /// Available slash commands
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        slash_commands: Vec<String>,
+        /// Available subagent names
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        agents: Vec<String>,
+        /// Active output style
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output_style: Option<String>,
+        /// Version of the Claude Code CLI
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        claude_code_version: Option<String>,
         /// Additional fields from the CLI
         #[serde(flatten)]
         extra: serde_json::Value,
     },
     /// Marker for conversation compaction boundary
-    CompactBoundary,
+    CompactBoundary {
+        /// Why and when compaction happened
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        compact_metadata: Option<CompactMetadata>,
+        /// Additional fields from the CLI (e.g., `session_id`, `uuid`)
+        #[serde(flatten)]
+        extra: serde_json::Value,
+    },
+    /// Emitted by the SDK (not the CLI) after crash recovery resumed the session
+    /// on a fresh CLI process (see [`RecoveryPolicy`](crate::options::RecoveryPolicy))
+    ///
+    /// A turn in flight when the CLI died does not complete; its stream yields
+    /// this message and then ends, so the caller can resend the prompt.
+    Reconnected {
+        /// Session ID that was resumed
+        session_id: String,
+        /// Recovery attempt that succeeded (1-based)
+        attempt: u32,
+    },
+    /// System message with a `subtype` this SDK does not recognize yet
+    #[serde(skip)]
+    Other {
+        /// The unrecognized `subtype` value
+        subtype: String,
+        /// Remaining message fields
+        raw: serde_json::Value,
+    },
+}
+
+impl Serialize for SystemMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            SystemMessage::Other { subtype, raw } => {
+                serialize_fallback(serializer, "subtype", subtype, raw)
+            }
+            _ => Self::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SystemMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        match unrecognized_tag(&value, "subtype", KNOWN_SYSTEM_SUBTYPES) {
+            Some(subtype) => Ok(SystemMessage::Other {
+                subtype: subtype.to_string(),
+                raw: without_field(&value, "subtype"),
+            }),
+            None => Self::deserialize(&value).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Metadata attached to a [`SystemMessage::CompactBoundary`]
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::messages::{CompactMetadata, CompactTrigger};
+///
+/// let meta: CompactMetadata =
+///     serde_json::from_str(r#"{"trigger":"auto","pre_tokens":154000}"#).unwrap();
+/// assert_eq!(meta.trigger, CompactTrigger::Auto);
+/// assert_eq!(meta.pre_tokens, Some(154000));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactMetadata {
+    /// What triggered the compaction
+    pub trigger: CompactTrigger,
+    /// Context size in tokens before compaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_tokens: Option<u64>,
+}
+
+/// What triggered a conversation compaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactTrigger {
+    /// Requested by the user (e.g., `/compact`)
+    Manual,
+    /// Context window limit reached
+    Auto,
+    /// Trigger not recognized by this SDK version
+    #[serde(other)]
+    Unknown,
 }
 
 /// Error types that can be reported in an [`AssistantMessage`]
@@ -186,7 +489,7 @@ pub struct AssistantMessage {
     #[serde(default)]
     pub parent_tool_use_id: Option<String>,
     /// Optional duration of the API request in milliseconds
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub duration_ms: Option<u64>,
     /// API-level error if Claude returned an error in this message
     ///
@@ -194,6 +497,9 @@ pub struct AssistantMessage {
     /// mid-session rather than as connection/transport failures.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub error: Option<AssistantMessageError>,
+    /// Additional fields from the CLI (e.g., `uuid`, `session_id`)
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// User input message
@@ -209,13 +515,18 @@ pub struct UserMessage {
     /// Parent tool use ID, present when this message contains a tool result
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub parent_tool_use_id: Option<String>,
+    /// Structured result of a built-in tool call, present alongside tool results
+    ///
+    /// Parse with [`BuiltinToolResult::parse`](crate::builtin_tools::BuiltinToolResult::parse).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use_result: Option<serde_json::Value>,
 }
 
 /// Result message variants discriminated by `subtype` field
 ///
 /// Final outcomes of agent execution: success, error, or input needed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "subtype", rename_all = "snake_case")]
+#[serde(remote = "Self", tag = "subtype", rename_all = "snake_case")]
 pub enum ResultMessage {
     /// Successful execution with final result
     Success {
@@ -252,29 +563,284 @@ pub enum ResultMessage {
         /// fully destructuring the `ResultMessage` enum.
         #[serde(default)]
         is_error: Option<bool>,
+        /// Token usage broken down by model
+        #[serde(default, rename = "modelUsage")]
+        model_usage: HashMap<String, ModelUsage>,
+        /// Tool uses that were denied during the turn
+        #[serde(default)]
+        permission_denials: Vec<PermissionDenial>,
+        /// Why the model stopped generating (e.g., "end_turn")
+        #[serde(default)]
+        stop_reason: Option<String>,
     },
     /// Error during execution
     Error {
         /// Error message text
         error: String,
-        /// Additional error fields
+        /// Metadata reported alongside the error; fields the SDK does not
+        /// model (e.g., `error_code`) land in `details.extra`
         #[serde(flatten)]
-        extra: serde_json::Value,
+        details: ResultErrorDetails,
     },
+    /// The turn stopped after reaching `max_turns`
+    ErrorMaxTurns(ResultErrorDetails),
+    /// The turn stopped after reaching `max_budget_usd`
+    ErrorMaxBudgetUsd(ResultErrorDetails),
+    /// The turn failed with an error while executing
+    ErrorDuringExecution(ResultErrorDetails),
+    /// The model failed to produce valid structured output within the retry limit
+    ErrorMaxStructuredOutputRetries(ResultErrorDetails),
     /// Agent requires additional user input
     InputRequired,
+    /// Result with a `subtype` this SDK does not recognize yet
+    #[serde(skip)]
+    Other {
+        /// The unrecognized `subtype` value
+        subtype: String,
+        /// Remaining message fields
+        raw: serde_json::Value,
+    },
+}
+
+impl Serialize for ResultMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ResultMessage::Other { subtype, raw } => {
+                serialize_fallback(serializer, "subtype", subtype, raw)
+            }
+            _ => Self::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ResultMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        match unrecognized_tag(&value, "subtype", KNOWN_RESULT_SUBTYPES) {
+            Some(subtype) => Ok(ResultMessage::Other {
+                subtype: subtype.to_string(),
+                raw: without_field(&value, "subtype"),
+            }),
+            None => Self::deserialize(&value).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Metadata carried by the error variants of [`ResultMessage`]
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::messages::ResultMessage;
+///
+/// let result: ResultMessage = serde_json::from_value(serde_json::json!({
+///     "subtype": "error_max_turns",
+///     "num_turns": 10,
+///     "total_cost_usd": 0.42,
+///     "usage": {"input_tokens": 1200, "output_tokens": 300}
+/// }))
+/// .unwrap();
+/// let details = result.error_details().unwrap();
+/// assert_eq!(details.num_turns, Some(10));
+/// assert_eq!(result.subtype(), "error_max_turns");
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResultErrorDetails {
+    /// Execution duration in milliseconds
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Time spent in API calls in milliseconds
+    #[serde(default)]
+    pub duration_api_ms: Option<u64>,
+    /// Number of conversation turns
+    #[serde(default)]
+    pub num_turns: Option<u32>,
+    /// Session identifier
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Total cost in USD
+    #[serde(default)]
+    pub total_cost_usd: Option<f64>,
+    /// Aggregate token usage
+    #[serde(default)]
+    pub usage: Option<UsageInfo>,
+    /// Token usage broken down by model
+    #[serde(default, rename = "modelUsage")]
+    pub model_usage: HashMap<String, ModelUsage>,
+    /// Tool uses that were denied during the turn
+    #[serde(default)]
+    pub permission_denials: Vec<PermissionDenial>,
+    /// Error messages reported by the CLI
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// Why the model stopped generating, if it did
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+    /// Additional fields from the CLI
+    #[serde(flatten)]
+    pub extra: serde_json::Value,
+}
+
+/// Per-model token usage and cost reported in a [`ResultMessage`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsage {
+    /// Input tokens consumed
+    #[serde(default)]
+    pub input_tokens: u32,
+    /// Output tokens generated
+    #[serde(default)]
+    pub output_tokens: u32,
+    /// Input tokens read from the prompt cache
+    #[serde(default)]
+    pub cache_read_input_tokens: u32,
+    /// Input tokens written to the prompt cache
+    #[serde(default)]
+    pub cache_creation_input_tokens: u32,
+    /// Web search requests made
+    #[serde(default)]
+    pub web_search_requests: u32,
+    /// Cost in USD attributed to this model
+    #[serde(default, rename = "costUSD")]
+    pub cost_usd: f64,
+    /// Context window size of the model
+    #[serde(default)]
+    pub context_window: Option<u32>,
+}
+
+/// A tool use that was denied by permissions during a turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionDenial {
+    /// Name of the denied tool
+    pub tool_name: String,
+    /// ID of the denied tool use
+    pub tool_use_id: String,
+    /// Input the tool was called with
+    #[serde(default)]
+    pub tool_input: serde_json::Value,
+}
+
+impl ResultMessage {
+    /// The wire `subtype` of this result (e.g., `"success"`, `"error_max_turns"`)
+    pub fn subtype(&self) -> &str {
+        match self {
+            ResultMessage::Success { .. } => "success",
+            ResultMessage::Error { .. } => "error",
+            ResultMessage::ErrorMaxTurns(_) => "error_max_turns",
+            ResultMessage::ErrorMaxBudgetUsd(_) => "error_max_budget_usd",
+            ResultMessage::ErrorDuringExecution(_) => "error_during_execution",
+            ResultMessage::ErrorMaxStructuredOutputRetries(_) => {
+                "error_max_structured_output_retries"
+            }
+            ResultMessage::InputRequired => "input_required",
+            ResultMessage::Other { subtype, .. } => subtype,
+        }
+    }
+
+    /// Details of an error result, or `None` for other subtypes
+    pub fn error_details(&self) -> Option<&ResultErrorDetails> {
+        match self {
+            ResultMessage::Error { details, .. }
+            | ResultMessage::ErrorMaxTurns(details)
+            | ResultMessage::ErrorMaxBudgetUsd(details)
+            | ResultMessage::ErrorDuringExecution(details)
+            | ResultMessage::ErrorMaxStructuredOutputRetries(details) => Some(details),
+            _ => None,
+        }
+    }
+
+    /// Aggregate token usage, when reported
+    ///
+    /// Always `None` for [`ResultMessage::Other`]; read its `raw` fields directly.
+    pub fn usage(&self) -> Option<&UsageInfo> {
+        match self {
+            ResultMessage::Success { usage, .. } => usage.as_ref(),
+            other => other.error_details().and_then(|d| d.usage.as_ref()),
+        }
+    }
+
+    /// Total cost in USD, when reported
+    ///
+    /// Always `None` for [`ResultMessage::Other`]; read its `raw` fields directly.
+    pub fn total_cost_usd(&self) -> Option<f64> {
+        match self {
+            ResultMessage::Success { total_cost_usd, .. } => *total_cost_usd,
+            other => other.error_details().and_then(|d| d.total_cost_usd),
+        }
+    }
+
+    /// Deserialize `structured_output` into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::StructuredOutput`] with the raw payload when the
+    /// output does not match `T`, is missing, or the turn did not succeed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusty_claw::messages::ResultMessage;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Answer {
+    ///     value: u32,
+    /// }
+    ///
+    /// let result: ResultMessage = serde_json::from_value(serde_json::json!({
+    ///     "subtype": "success",
+    ///     "result": "",
+    ///     "structured_output": {"value": 4}
+    /// }))
+    /// .unwrap();
+    /// assert_eq!(result.structured::<Answer>().unwrap().value, 4);
+    /// ```
+    pub fn structured<T: serde::de::DeserializeOwned>(&self) -> Result<T, ClawError> {
+        match self {
+            ResultMessage::Success {
+                structured_output: Some(output),
+                ..
+            } => serde_json::from_value(output.clone()).map_err(|e| ClawError::StructuredOutput {
+                reason: e.to_string(),
+                raw: output.clone(),
+            }),
+            ResultMessage::Success { result, .. } => Err(ClawError::StructuredOutput {
+                reason: "result has no structured_output".to_string(),
+                raw: serde_json::Value::String(result.clone()),
+            }),
+            ResultMessage::Error { error, details } => Err(ClawError::StructuredOutput {
+                reason: format!("turn failed: {}", error),
+                raw: serde_json::to_value(details).unwrap_or_default(),
+            }),
+            ResultMessage::InputRequired => Err(ClawError::StructuredOutput {
+                reason: "turn ended waiting for user input".to_string(),
+                raw: serde_json::Value::Null,
+            }),
+            ResultMessage::Other { subtype, raw } => Err(ClawError::StructuredOutput {
+                reason: format!("unrecognized result subtype {}", subtype),
+                raw: raw.clone(),
+            }),
+            other => Err(ClawError::StructuredOutput {
+                reason: format!("turn failed: {}", other.subtype()),
+                raw: serde_json::to_value(other.error_details()).unwrap_or_default(),
+            }),
+        }
+    }
 }
 
 /// Content block variants discriminated by `type` field
 ///
 /// Represents different types of content in assistant messages.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
+#[serde(remote = "Self", tag = "type", rename_all = "snake_case")]
 pub enum ContentBlock {
     /// Plain text content
     Text {
         /// The text content
         text: String,
+        /// Additional fields from the API (e.g., `citations`)
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     },
     /// Tool invocation request
     ToolUse {
@@ -306,56 +872,792 @@ pub enum ContentBlock {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         signature: Option<String>,
     },
+    /// Extended thinking content encrypted by the safety system
+    ///
+    /// The payload is opaque but must be passed back unchanged in
+    /// multi-turn conversations.
+    RedactedThinking {
+        /// Encrypted thinking data
+        data: String,
+    },
+    /// Image content
+    Image {
+        /// Image data source
+        source: MediaSource,
+    },
+    /// Document content (PDF or plain text)
+    Document {
+        /// Document data source
+        source: MediaSource,
+        /// Optional document title
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Optional context about the document
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        context: Option<String>,
+    },
+    /// Invocation of a server-side tool (e.g., `web_search`)
+    ServerToolUse {
+        /// Unique identifier for this tool use
+        id: String,
+        /// Name of the server tool
+        name: String,
+        /// Tool input parameters as JSON
+        input: serde_json::Value,
+    },
+    /// Result of a server-side web search
+    WebSearchToolResult {
+        /// ID of the server tool use this result corresponds to
+        tool_use_id: String,
+        /// Search results, or an error object, as JSON
+        content: serde_json::Value,
+    },
+    /// Block with a `type` this SDK does not recognize yet
+    ///
+    /// Holds the raw JSON (including `type`) so newer CLI releases do not
+    /// break parsing. Serializes back to the original object. Malformed
+    /// blocks of a known type are deserialization errors, not `Unknown`.
+    #[serde(skip)]
+    Unknown(serde_json::Value),
+}
+
+/// Wire `type` values with a dedicated [`ContentBlock`] variant
+const KNOWN_BLOCK_TYPES: &[&str] = &[
+    "text",
+    "tool_use",
+    "tool_result",
+    "thinking",
+    "redacted_thinking",
+    "image",
+    "document",
+    "server_tool_use",
+    "web_search_tool_result",
+];
+
+impl Serialize for ContentBlock {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ContentBlock::Unknown(raw) => raw.serialize(serializer),
+            _ => Self::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ContentBlock {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let known = value
+            .get("type")
+            .and_then(serde_json::Value::as_str)
+            .is_some_and(|t| KNOWN_BLOCK_TYPES.contains(&t));
+        if known {
+            Self::deserialize(&value).map_err(serde::de::Error::custom)
+        } else {
+            Ok(ContentBlock::Unknown(value))
+        }
+    }
+}
+
+impl ContentBlock {
+    /// The wire `type` of this block (e.g., `"text"`, `"tool_use"`)
+    ///
+    /// For [`ContentBlock::Unknown`] this is the raw `type` field, if present.
+    pub fn block_type(&self) -> Option<&str> {
+        match self {
+            ContentBlock::Text { .. } => Some("text"),
+            ContentBlock::ToolUse { .. } => Some("tool_use"),
+            ContentBlock::ToolResult { .. } => Some("tool_result"),
+            ContentBlock::Thinking { .. } => Some("thinking"),
+            ContentBlock::RedactedThinking { .. } => Some("redacted_thinking"),
+            ContentBlock::Image { .. } => Some("image"),
+            ContentBlock::Document { .. } => Some("document"),
+            ContentBlock::ServerToolUse { .. } => Some("server_tool_use"),
+            ContentBlock::WebSearchToolResult { .. } => Some("web_search_tool_result"),
+            ContentBlock::Unknown(raw) => raw.get("type").and_then(serde_json::Value::as_str),
+        }
+    }
 }
 
-/// Streaming event from CLI
+/// Partial message update from the CLI (`"type": "stream_event"`)
 ///
-/// Used for real-time updates during agent execution.
+/// Emitted when [`include_partial_messages`](crate::options::ClaudeAgentOptions::include_partial_messages)
+/// is enabled. Wraps one raw Anthropic Messages API streaming event. Feed these
+/// into a [`PartialMessageAccumulator`] to rebuild the in-progress message.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamEvent {
-    /// Event type identifier
-    pub event_type: String,
-    /// Event data payload
-    pub data: serde_json::Value,
+    /// Unique event identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// Session the event belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// The Anthropic streaming event
+    pub event: ApiStreamEvent,
+    /// Parent tool use ID, present when the event comes from a subagent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_tool_use_id: Option<String>,
 }
 
-/// Message in Anthropic Messages API format
+/// Anthropic Messages API streaming event, discriminated by `type`
 ///
-/// Standard structure for assistant and user messages.
+/// A message streams as `message_start`, then for each content block a
+/// `content_block_start`, any number of `content_block_delta`s, and a
+/// `content_block_stop`, followed by `message_delta` and `message_stop`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiMessage {
-    /// Message role ("assistant" or "user")
-    pub role: String,
-    /// Message content blocks
-    pub content: Vec<ContentBlock>,
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApiStreamEvent {
+    /// A new assistant message started
+    MessageStart {
+        /// The message shell (content is empty at this point)
+        message: StreamMessageStart,
+    },
+    /// A content block started at `index`
+    ContentBlockStart {
+        /// Position of the block in the message content
+        index: usize,
+        /// Initial block (empty text, tool name and ID, ...)
+        content_block: ContentBlock,
+    },
+    /// Incremental update to the content block at `index`
+    ContentBlockDelta {
+        /// Position of the block in the message content
+        index: usize,
+        /// The increment
+        delta: ContentDelta,
+    },
+    /// The content block at `index` is complete
+    ContentBlockStop {
+        /// Position of the block in the message content
+        index: usize,
+    },
+    /// Top-level message changes such as the stop reason
+    MessageDelta {
+        /// Changed message fields
+        delta: MessageDeltaInfo,
+        /// Cumulative usage for the message
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        usage: Option<serde_json::Value>,
+    },
+    /// The message is complete
+    MessageStop,
+    /// Keep-alive
+    Ping,
+    /// Streaming error from the API
+    Error {
+        /// Error details (`type` and `message`)
+        error: serde_json::Value,
+    },
+    /// Event type not known to this SDK version
+    #[serde(other)]
+    Unknown,
 }
 
-/// Token usage information from the API
-///
-/// Tracks input and output token consumption.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UsageInfo {
-    /// Number of input tokens consumed
-    pub input_tokens: u32,
-    /// Number of output tokens generated
-    pub output_tokens: u32,
+/// Message fields known at `message_start`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StreamMessageStart {
+    /// API message identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Model generating the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Initial usage (input tokens)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<serde_json::Value>,
 }
 
-/// Information about an available tool
-///
-/// Provided in system init messages to describe callable tools.
-/// The CLI may send tools as plain strings (just the name) or as objects
-/// with name/description/input_schema fields.
-#[derive(Debug, Clone, Serialize)]
-pub struct ToolInfo {
-    /// Tool name identifier
-    pub name: String,
-    /// Optional tool description
-    #[serde(default)]
-    pub description: Option<String>,
-    /// Optional JSON schema for tool input
-    #[serde(default)]
-    pub input_schema: Option<serde_json::Value>,
+/// Message fields changed by a `message_delta` event
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MessageDeltaInfo {
+    /// Why generation stopped (e.g., `"end_turn"`, `"tool_use"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+    /// Stop sequence that ended generation, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_sequence: Option<String>,
+}
+
+/// Increment carried by a `content_block_delta` event, discriminated by `type`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentDelta {
+    /// More text for a text block
+    TextDelta {
+        /// Text fragment
+        text: String,
+    },
+    /// More JSON for a tool use block's input
+    InputJsonDelta {
+        /// JSON fragment; only the concatenation of all fragments is valid JSON
+        partial_json: String,
+    },
+    /// More reasoning for a thinking block
+    ThinkingDelta {
+        /// Thinking fragment
+        thinking: String,
+    },
+    /// Signature for a thinking block, sent just before it stops
+    SignatureDelta {
+        /// Thinking block signature
+        signature: String,
+    },
+    /// A citation attached to a text block
+    CitationsDelta {
+        /// Citation payload
+        citation: serde_json::Value,
+    },
+    /// Delta type not known to this SDK version
+    #[serde(other)]
+    Unknown,
+}
+
+/// A content block of an in-progress message
+#[derive(Debug, Clone)]
+pub enum PartialBlock {
+    /// Text received so far
+    Text {
+        /// Accumulated text
+        text: String,
+    },
+    /// Reasoning received so far
+    Thinking {
+        /// Accumulated thinking
+        thinking: String,
+        /// Signature, once received
+        signature: Option<String>,
+    },
+    /// Tool invocation whose input is still streaming
+    ToolUse {
+        /// Tool use identifier
+        id: String,
+        /// Tool name
+        name: String,
+        /// Input JSON received so far (usually incomplete until the block stops)
+        partial_json: String,
+    },
+    /// Any other block, kept as sent in `content_block_start`
+    Other(ContentBlock),
+}
+
+impl PartialBlock {
+    fn start(block: &ContentBlock) -> Self {
+        match block {
+            ContentBlock::Text { text, .. } => PartialBlock::Text { text: text.clone() },
+            ContentBlock::Thinking {
+                thinking,
+                signature,
+            } => PartialBlock::Thinking {
+                thinking: thinking.clone(),
+                signature: signature.clone(),
+            },
+            ContentBlock::ToolUse { id, name, .. } => PartialBlock::ToolUse {
+                id: id.clone(),
+                name: name.clone(),
+                partial_json: String::new(),
+            },
+            other => PartialBlock::Other(other.clone()),
+        }
+    }
+
+    fn apply(&mut self, delta: &ContentDelta) {
+        match (self, delta) {
+            (PartialBlock::Text { text }, ContentDelta::TextDelta { text: more }) => {
+                text.push_str(more)
+            }
+            (
+                PartialBlock::Thinking { thinking, .. },
+                ContentDelta::ThinkingDelta { thinking: more },
+            ) => thinking.push_str(more),
+            (
+                PartialBlock::Thinking { signature, .. },
+                ContentDelta::SignatureDelta { signature: sig },
+            ) => *signature = Some(sig.clone()),
+            (
+                PartialBlock::ToolUse { partial_json, .. },
+                ContentDelta::InputJsonDelta { partial_json: more },
+            ) => partial_json.push_str(more),
+            _ => {}
+        }
+    }
+
+    /// Tool input parsed from the JSON received so far
+    ///
+    /// Returns `None` for non-tool blocks and while the JSON is still incomplete.
+    pub fn tool_input(&self) -> Option<serde_json::Value> {
+        match self {
+            PartialBlock::ToolUse { partial_json, .. } if partial_json.is_empty() => {
+                Some(serde_json::Value::Object(Default::default()))
+            }
+            PartialBlock::ToolUse { partial_json, .. } => serde_json::from_str(partial_json).ok(),
+            _ => None,
+        }
+    }
+
+    /// Snapshot as a [`ContentBlock`]; incomplete tool input becomes `null`
+    pub fn to_content_block(&self) -> ContentBlock {
+        match self {
+            PartialBlock::Text { text } => ContentBlock::Text {
+                text: text.clone(),
+                extra: Default::default(),
+            },
+            PartialBlock::Thinking {
+                thinking,
+                signature,
+            } => ContentBlock::Thinking {
+                thinking: thinking.clone(),
+                signature: signature.clone(),
+            },
+            PartialBlock::ToolUse { id, name, .. } => ContentBlock::ToolUse {
+                id: id.clone(),
+                name: name.clone(),
+                input: self.tool_input().unwrap_or(serde_json::Value::Null),
+            },
+            PartialBlock::Other(block) => block.clone(),
+        }
+    }
+}
+
+/// Rebuilds the in-progress assistant message from [`StreamEvent`]s
+///
+/// Push every stream event of a turn; read the accumulated text, thinking, and
+/// tool input at any point to render token-by-token. A `message_start` resets
+/// the state, so one accumulator can follow all messages of one agent in a turn.
+///
+/// Subagents stream their own messages, interleaved with the main agent's and
+/// tagged with [`StreamEvent::parent_tool_use_id`]. An accumulator follows a
+/// single agent: [`new()`](Self::new) the main agent and
+/// [`for_subagent()`](Self::for_subagent) the subagent launched by one tool
+/// call. [`push()`](Self::push) skips events of other agents; events passed to
+/// [`apply()`](Self::apply) are not filtered.
+///
+/// # Example
+///
+/// ```no_run
+/// use rusty_claw::prelude::*;
+/// use rusty_claw::messages::PartialMessageAccumulator;
+/// use tokio_stream::StreamExt;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let options = ClaudeAgentOptions::builder()
+///     .include_partial_messages(true)
+///     .build();
+/// let mut client = ClaudeClient::new(options)?;
+/// client.connect().await?;
+///
+/// let mut partial = PartialMessageAccumulator::new();
+/// let mut stream = client.send_message("Write a haiku").await?;
+/// while let Some(message) = stream.next().await {
+///     match message? {
+///         Message::StreamEvent(event) => {
+///             partial.push(&event);
+///             print!("\r{}", partial.text());
+///         }
+///         Message::Result(_) => break,
+///         _ => {}
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PartialMessageAccumulator {
+    parent_tool_use_id: Option<String>,
+    message_id: Option<String>,
+    model: Option<String>,
+    blocks: Vec<PartialBlock>,
+    stop_reason: Option<String>,
+    complete: bool,
+}
+
+impl PartialMessageAccumulator {
+    /// Create an empty accumulator for the main agent's messages
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty accumulator for the subagent launched by tool call `parent_tool_use_id`
+    pub fn for_subagent(parent_tool_use_id: impl Into<String>) -> Self {
+        Self {
+            parent_tool_use_id: Some(parent_tool_use_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Apply one stream event, unless it belongs to another agent
+    pub fn push(&mut self, event: &StreamEvent) {
+        if event.parent_tool_use_id == self.parent_tool_use_id {
+            self.apply(&event.event);
+        }
+    }
+
+    /// Apply one raw API streaming event
+    pub fn apply(&mut self, event: &ApiStreamEvent) {
+        match event {
+            ApiStreamEvent::MessageStart { message } => {
+                *self = Self {
+                    parent_tool_use_id: self.parent_tool_use_id.take(),
+                    message_id: message.id.clone(),
+                    model: message.model.clone(),
+                    ..Self::default()
+                };
+            }
+            ApiStreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                let block = PartialBlock::start(content_block);
+                // Blocks arrive in order; an index past the next slot is bogus
+                let len = self.blocks.len();
+                match self.blocks.get_mut(*index) {
+                    Some(slot) => *slot = block,
+                    None if *index == len => self.blocks.push(block),
+                    None => tracing::debug!(
+                        "Ignoring content_block_start at index {} with {} blocks",
+                        index,
+                        len
+                    ),
+                }
+            }
+            ApiStreamEvent::ContentBlockDelta { index, delta } => {
+                if let Some(block) = self.blocks.get_mut(*index) {
+                    block.apply(delta);
+                }
+            }
+            ApiStreamEvent::MessageDelta { delta, .. } => {
+                if delta.stop_reason.is_some() {
+                    self.stop_reason = delta.stop_reason.clone();
+                }
+            }
+            ApiStreamEvent::MessageStop => self.complete = true,
+            ApiStreamEvent::ContentBlockStop { .. }
+            | ApiStreamEvent::Ping
+            | ApiStreamEvent::Error { .. }
+            | ApiStreamEvent::Unknown => {}
+        }
+    }
+
+    /// Tool call whose subagent this accumulator follows (`None` for the main agent)
+    pub fn parent_tool_use_id(&self) -> Option<&str> {
+        self.parent_tool_use_id.as_deref()
+    }
+
+    /// Blocks received so far, indexed as in the API message
+    pub fn blocks(&self) -> &[PartialBlock] {
+        &self.blocks
+    }
+
+    /// Concatenated text of all text blocks so far
+    pub fn text(&self) -> String {
+        self.blocks
+            .iter()
+            .filter_map(|block| match block {
+                PartialBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Concatenated reasoning of all thinking blocks so far
+    pub fn thinking(&self) -> String {
+        self.blocks
+            .iter()
+            .filter_map(|block| match block {
+                PartialBlock::Thinking { thinking, .. } => Some(thinking.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Snapshot of the message content as [`ContentBlock`]s
+    pub fn content(&self) -> Vec<ContentBlock> {
+        self.blocks
+            .iter()
+            .map(PartialBlock::to_content_block)
+            .collect()
+    }
+
+    /// API message identifier from `message_start`
+    pub fn message_id(&self) -> Option<&str> {
+        self.message_id.as_deref()
+    }
+
+    /// Model from `message_start`
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// Stop reason from `message_delta`, once known
+    pub fn stop_reason(&self) -> Option<&str> {
+        self.stop_reason.as_deref()
+    }
+
+    /// Whether `message_stop` was received
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+}
+
+/// Message in Anthropic Messages API format
+///
+/// Standard structure for assistant and user messages. Assistant messages also
+/// carry the API message metadata; fields this SDK does not model are kept in
+/// `extra`, so a parsed message serializes back without losing data.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiMessage {
+    /// Message role ("assistant" or "user")
+    pub role: String,
+    /// Message content blocks
+    pub content: Vec<ContentBlock>,
+    /// API message ID (e.g., "msg_01...")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Model that generated the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Why the model stopped generating (e.g., "end_turn", "tool_use")
+    ///
+    /// `Some(None)` records an explicit `null` so it serializes back as sent.
+    #[serde(default, with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<Option<String>>,
+    /// Stop sequence that ended generation; `Some(None)` for an explicit `null`
+    #[serde(default, with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub stop_sequence: Option<Option<String>>,
+    /// Token usage of this API call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageInfo>,
+    /// Additional fields from the CLI (e.g., `type`, `container`)
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Token usage information from the API
+///
+/// Tracks input, output, and prompt cache token consumption. Usage from
+/// several results can be summed with `+=`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageInfo {
+    /// Number of input tokens consumed
+    pub input_tokens: u32,
+    /// Number of output tokens generated
+    pub output_tokens: u32,
+    /// Input tokens written to the prompt cache, if reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_tokens: Option<u32>,
+    /// Input tokens read from the prompt cache, if reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<u32>,
+    /// Server-side tool requests (e.g., web search)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_tool_use: Option<ServerToolUsage>,
+    /// Additional usage fields from the API (e.g., `service_tier`); not summed by `+=`
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl std::ops::AddAssign<&UsageInfo> for UsageInfo {
+    fn add_assign(&mut self, other: &UsageInfo) {
+        self.input_tokens = self.input_tokens.saturating_add(other.input_tokens);
+        self.output_tokens = self.output_tokens.saturating_add(other.output_tokens);
+        add_reported(
+            &mut self.cache_creation_input_tokens,
+            other.cache_creation_input_tokens,
+        );
+        add_reported(
+            &mut self.cache_read_input_tokens,
+            other.cache_read_input_tokens,
+        );
+        if let Some(other_tools) = &other.server_tool_use {
+            *self.server_tool_use.get_or_insert_with(Default::default) += other_tools;
+        }
+    }
+}
+
+/// Add an optional counter, keeping it unset only if neither side reported it
+fn add_reported(total: &mut Option<u32>, other: Option<u32>) {
+    if let Some(other) = other {
+        *total = Some(total.unwrap_or(0).saturating_add(other));
+    }
+}
+
+/// Server-side tool request counts in [`UsageInfo`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerToolUsage {
+    /// Number of web search requests
+    #[serde(default)]
+    pub web_search_requests: u32,
+    /// Number of web fetch requests
+    #[serde(default)]
+    pub web_fetch_requests: u32,
+}
+
+impl std::ops::AddAssign<&ServerToolUsage> for ServerToolUsage {
+    fn add_assign(&mut self, other: &ServerToolUsage) {
+        self.web_search_requests = self
+            .web_search_requests
+            .saturating_add(other.web_search_requests);
+        self.web_fetch_requests = self
+            .web_fetch_requests
+            .saturating_add(other.web_fetch_requests);
+    }
+}
+
+/// Rate limit status update from the CLI (`"type": "rate_limit_event"`)
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::messages::{Message, RateLimitStatus, RateLimitType};
+///
+/// let msg: Message = serde_json::from_str(
+///     r#"{"type":"rate_limit_event","rate_limit_info":{"status":"rejected","rateLimitType":"five_hour","resetsAt":1760000000}}"#,
+/// )
+/// .unwrap();
+/// let Message::RateLimitEvent(event) = msg else { unreachable!() };
+/// assert_eq!(event.rate_limit_info.status, RateLimitStatus::Rejected);
+/// assert_eq!(event.rate_limit_info.rate_limit_type, Some(RateLimitType::FiveHour));
+/// assert!(event.rate_limit_info.is_blocking());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitEvent {
+    /// Current state of the rate limit
+    #[serde(default)]
+    pub rate_limit_info: RateLimitInfo,
+    /// Unique event identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// Session the event belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Additional fields from the CLI
+    #[serde(flatten)]
+    pub extra: serde_json::Value,
+}
+
+/// Rate limit details carried by a [`RateLimitEvent`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitInfo {
+    /// Whether requests are currently allowed
+    #[serde(default)]
+    pub status: RateLimitStatus,
+    /// Which limit this update is about
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_type: Option<RateLimitType>,
+    /// Fraction of the limit used (0.0 to 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utilization: Option<f64>,
+    /// Unix timestamp (seconds) at which the limit resets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resets_at: Option<u64>,
+    /// Whether usage beyond the limit (overage) is allowed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overage_status: Option<RateLimitStatus>,
+    /// Unix timestamp (seconds) at which the overage limit resets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overage_resets_at: Option<u64>,
+    /// Whether requests are currently billed as overage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_using_overage: Option<bool>,
+}
+
+impl RateLimitInfo {
+    /// Whether new requests will be rejected until the limit resets
+    ///
+    /// True when the limit is rejected and overage cannot absorb the request.
+    pub fn is_blocking(&self) -> bool {
+        self.status == RateLimitStatus::Rejected
+            && !matches!(
+                self.overage_status,
+                Some(RateLimitStatus::Allowed | RateLimitStatus::AllowedWarning)
+            )
+    }
+
+    /// Reset time as a [`SystemTime`](std::time::SystemTime)
+    pub fn resets_at_time(&self) -> Option<std::time::SystemTime> {
+        self.resets_at
+            .map(|secs| std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+    }
+
+    /// Time until a blocking limit resets, or `None` if requests can proceed
+    ///
+    /// Also `None` when the limit is blocking but the reset time is unknown
+    /// or already past.
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        if !self.is_blocking() {
+            return None;
+        }
+        self.resets_at_time()?
+            .duration_since(std::time::SystemTime::now())
+            .ok()
+            .filter(|d| !d.is_zero())
+    }
+}
+
+/// Status of a rate limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitStatus {
+    /// Requests are allowed
+    #[default]
+    Allowed,
+    /// Requests are allowed but the limit is close
+    AllowedWarning,
+    /// Requests are rejected until the limit resets
+    Rejected,
+    /// Status not recognized by this SDK version
+    #[serde(other)]
+    Unknown,
+}
+
+/// Kind of rate limit reported in a [`RateLimitInfo`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitType {
+    /// Five-hour session limit
+    FiveHour,
+    /// Weekly limit across all models
+    SevenDay,
+    /// Weekly limit for Opus models
+    SevenDayOpus,
+    /// Weekly limit for Sonnet models
+    SevenDaySonnet,
+    /// Overage (extra usage) limit
+    Overage,
+    /// Limit not recognized by this SDK version
+    #[serde(other)]
+    Unknown,
+}
+
+impl RateLimitType {
+    /// Wire name of the limit (e.g., `"five_hour"`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitType::FiveHour => "five_hour",
+            RateLimitType::SevenDay => "seven_day",
+            RateLimitType::SevenDayOpus => "seven_day_opus",
+            RateLimitType::SevenDaySonnet => "seven_day_sonnet",
+            RateLimitType::Overage => "overage",
+            RateLimitType::Unknown => "unknown",
+        }
+    }
+}
+
+/// Information about an available tool
+///
+/// Provided in system init messages to describe callable tools.
+/// The CLI may send tools as plain strings (just the name) or as objects
+/// with name/description/input_schema fields.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolInfo {
+    /// Tool name identifier
+    pub name: String,
+    /// Optional tool description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Optional JSON schema for tool input
+    #[serde(default)]
+    pub input_schema: Option<serde_json::Value>,
 }
 
 impl<'de> serde::Deserialize<'de> for ToolInfo {
@@ -399,67 +1701,774 @@ impl<'de> serde::Deserialize<'de> for ToolInfo {
 
 /// Information about an MCP server
 ///
-/// Provided in system init messages to describe connected MCP servers.
+/// Provided in system init messages to describe configured MCP servers.
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::messages::{McpServerInfo, McpServerStatus};
+///
+/// let server: McpServerInfo =
+///     serde_json::from_str(r#"{"name":"github","status":"needs-auth"}"#).unwrap();
+/// assert_eq!(server.status, Some(McpServerStatus::NeedsAuth));
+/// assert!(!server.is_connected());
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerInfo {
     /// MCP server name identifier
     pub name: String,
+    /// Connection status reported by the CLI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<McpServerStatus>,
     /// Additional server information fields
     #[serde(flatten)]
     pub extra: serde_json::Value,
 }
 
+impl McpServerInfo {
+    /// Whether the CLI reports the server as connected
+    pub fn is_connected(&self) -> bool {
+        self.status == Some(McpServerStatus::Connected)
+    }
+}
+
+/// Connection status of an MCP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum McpServerStatus {
+    /// Connected and serving tools
+    Connected,
+    /// Connection or startup failed
+    Failed,
+    /// Waiting for the user to authenticate
+    NeedsAuth,
+    /// Still connecting
+    Pending,
+    /// Disabled in the configuration
+    Disabled,
+    /// Status not recognized by this SDK version
+    #[serde(other)]
+    Unknown,
+}
+
+/// Serde adapter that keeps an explicit `null` apart from an absent field
+///
+/// Use with `#[serde(default)]`: absent is `None`, `null` is `Some(None)`.
+mod nullable {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<T: Serialize, S: Serializer>(
+        value: &Option<Option<T>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(Some(value)) => value.serialize(serializer),
+            _ => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Option<T>>, D::Error> {
+        Option::<T>::deserialize(deserializer).map(Some)
+    }
+}
+
+/// Serde adapter for the CLI's camelCase permission mode (e.g., "acceptEdits")
+mod cli_permission_mode {
+    use crate::options::PermissionMode;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        mode: &Option<PermissionMode>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match mode {
+            Some(mode) => serializer.serialize_str(mode.to_cli_arg()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<PermissionMode>, D::Error> {
+        let value = Option::<String>::deserialize(deserializer)?;
+        Ok(value.as_deref().map(PermissionMode::from_cli_arg_lossless))
+    }
+}
+
+/// Source of image or document data in a [`UserContentBlock`] or [`ContentBlock`]
+///
+/// Matches the Anthropic Messages API `source` object.
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::messages::MediaSource;
+///
+/// let source = MediaSource::Url { url: "https://example.com/a.png".to_string() };
+/// let json = serde_json::to_value(&source).unwrap();
+/// assert_eq!(json["type"], "url");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MediaSource {
+    /// Inline base64-encoded data
+    Base64 {
+        /// MIME type (e.g., "image/png", "application/pdf")
+        media_type: String,
+        /// Base64-encoded bytes
+        data: String,
+    },
+    /// Data fetched from a URL
+    Url {
+        /// Location of the image or document
+        url: String,
+    },
+    /// Inline plain text (documents only)
+    Text {
+        /// MIME type (always "text/plain")
+        media_type: String,
+        /// The document text
+        data: String,
+    },
+}
+
+/// Content block in an outgoing user message
+///
+/// Used with [`ClaudeClient::send_content`](crate::client::ClaudeClient::send_content)
+/// to send multimodal turns mixing text, images, and documents.
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::messages::UserContentBlock;
+///
+/// let blocks = vec![
+///     UserContentBlock::text("What is wrong with this layout?"),
+///     UserContentBlock::image_base64("image/png", "iVBORw0KGgo="),
+/// ];
+/// let json = serde_json::to_value(&blocks).unwrap();
+/// assert_eq!(json[1]["type"], "image");
+/// assert_eq!(json[1]["source"]["media_type"], "image/png");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UserContentBlock {
+    /// Plain text content
+    Text {
+        /// The text content
+        text: String,
+    },
+    /// Image content
+    Image {
+        /// Image data source
+        source: MediaSource,
+    },
+    /// Document content (PDF or plain text)
+    Document {
+        /// Document data source
+        source: MediaSource,
+        /// Optional document title
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Optional context about the document
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        context: Option<String>,
+    },
+    /// Result of a tool call, answering a `ContentBlock::ToolUse`
+    ToolResult {
+        /// ID of the tool use this result answers
+        tool_use_id: String,
+        /// Result data (a string or an array of content blocks)
+        content: serde_json::Value,
+        /// Whether the tool call failed
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
+    },
+}
+
+impl UserContentBlock {
+    /// Create a text block
+    pub fn text(text: impl Into<String>) -> Self {
+        UserContentBlock::Text { text: text.into() }
+    }
+
+    /// Create an image block from base64-encoded data
+    pub fn image_base64(media_type: impl Into<String>, data: impl Into<String>) -> Self {
+        UserContentBlock::Image {
+            source: MediaSource::Base64 {
+                media_type: media_type.into(),
+                data: data.into(),
+            },
+        }
+    }
+
+    /// Create an image block from raw bytes, base64-encoding them
+    pub fn image_bytes(media_type: impl Into<String>, bytes: &[u8]) -> Self {
+        Self::image_base64(media_type, BASE64.encode(bytes))
+    }
+
+    /// Create an image block referencing a URL
+    pub fn image_url(url: impl Into<String>) -> Self {
+        UserContentBlock::Image {
+            source: MediaSource::Url { url: url.into() },
+        }
+    }
+
+    /// Create an image block from a file on disk
+    ///
+    /// The media type is inferred from the extension (`png`, `jpg`/`jpeg`,
+    /// `gif`, `webp`). The file is read synchronously.
+    ///
+    /// # Errors
+    ///
+    /// - `ClawError::Io` - The file cannot be read or has an unsupported extension
+    pub fn image_file(path: impl AsRef<Path>) -> Result<Self, ClawError> {
+        let path = path.as_ref();
+        let media_type = match extension(path).as_deref() {
+            Some("png") => "image/png",
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            _ => return Err(unsupported_file(path, "image")),
+        };
+        let bytes = std::fs::read(path)?;
+        Ok(Self::image_bytes(media_type, &bytes))
+    }
+
+    /// Create a PDF document block from base64-encoded data
+    pub fn pdf_base64(data: impl Into<String>) -> Self {
+        UserContentBlock::Document {
+            source: MediaSource::Base64 {
+                media_type: "application/pdf".to_string(),
+                data: data.into(),
+            },
+            title: None,
+            context: None,
+        }
+    }
+
+    /// Create a plain text document block
+    pub fn document_text(text: impl Into<String>) -> Self {
+        UserContentBlock::Document {
+            source: MediaSource::Text {
+                media_type: "text/plain".to_string(),
+                data: text.into(),
+            },
+            title: None,
+            context: None,
+        }
+    }
+
+    /// Create a document block referencing a URL
+    pub fn document_url(url: impl Into<String>) -> Self {
+        UserContentBlock::Document {
+            source: MediaSource::Url { url: url.into() },
+            title: None,
+            context: None,
+        }
+    }
+
+    /// Create a document block from a file on disk
+    ///
+    /// `.pdf` files are sent as base64 PDF documents; `.txt` and `.md` files
+    /// are sent as plain text documents. The file name is used as the title.
+    /// The file is read synchronously.
+    ///
+    /// # Errors
+    ///
+    /// - `ClawError::Io` - The file cannot be read, is not valid UTF-8 text,
+    ///   or has an unsupported extension
+    pub fn document_file(path: impl AsRef<Path>) -> Result<Self, ClawError> {
+        let path = path.as_ref();
+        let block = match extension(path).as_deref() {
+            Some("pdf") => Self::pdf_base64(BASE64.encode(std::fs::read(path)?)),
+            Some("txt") | Some("md") => Self::document_text(std::fs::read_to_string(path)?),
+            _ => return Err(unsupported_file(path, "document")),
+        };
+        let title = path.file_name().map(|n| n.to_string_lossy().into_owned());
+        Ok(match title {
+            Some(title) => block.with_title(title),
+            None => block,
+        })
+    }
+
+    /// Set the title of a document block (no-op for other blocks)
+    pub fn with_title(mut self, new_title: impl Into<String>) -> Self {
+        if let UserContentBlock::Document { title, .. } = &mut self {
+            *title = Some(new_title.into());
+        }
+        self
+    }
+
+    /// Set the context of a document block (no-op for other blocks)
+    pub fn with_context(mut self, new_context: impl Into<String>) -> Self {
+        if let UserContentBlock::Document { context, .. } = &mut self {
+            *context = Some(new_context.into());
+        }
+        self
+    }
+
+    /// Create a tool result block
+    pub fn tool_result(
+        tool_use_id: impl Into<String>,
+        content: impl Into<serde_json::Value>,
+    ) -> Self {
+        UserContentBlock::ToolResult {
+            tool_use_id: tool_use_id.into(),
+            content: content.into(),
+            is_error: false,
+        }
+    }
+
+    /// Create a tool result block reporting a failed tool call
+    pub fn tool_error(tool_use_id: impl Into<String>, message: impl Into<String>) -> Self {
+        UserContentBlock::ToolResult {
+            tool_use_id: tool_use_id.into(),
+            content: serde_json::Value::String(message.into()),
+            is_error: true,
+        }
+    }
+}
+
+/// Outgoing user message written to the CLI's stdin in stream-json mode
+///
+/// Every user turn the SDK sends is built from this type:
+/// [`ClaudeClient::send_message`](crate::client::ClaudeClient::send_message),
+/// [`ClaudeClient::send_content`](crate::client::ClaudeClient::send_content),
+/// [`ClaudeClient::send_input`](crate::client::ClaudeClient::send_input), and
+/// [`query_with_messages`](crate::query::query_with_messages).
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::messages::{UserContentBlock, UserInput};
+///
+/// let input = UserInput::text("What is 2 + 2?").with_session_id("sess_1");
+/// let json = serde_json::to_value(&input).unwrap();
+/// assert_eq!(json["type"], "user");
+/// assert_eq!(json["session_id"], "sess_1");
+/// assert_eq!(json["message"]["content"], "What is 2 + 2?");
+///
+/// let answer = UserInput::blocks(vec![UserContentBlock::tool_result("toolu_1", "42")])
+///     .with_parent_tool_use_id("toolu_0");
+/// let json = serde_json::to_value(&answer).unwrap();
+/// assert_eq!(json["message"]["content"][0]["type"], "tool_result");
+/// assert_eq!(json["parent_tool_use_id"], "toolu_0");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "user")]
+pub struct UserInput {
+    /// Session the message belongs to (empty lets the CLI use the current session)
+    #[serde(default)]
+    pub session_id: String,
+    /// The message body
+    pub message: UserInputMessage,
+    /// Tool use this message answers, for messages inside a subagent
+    #[serde(default)]
+    pub parent_tool_use_id: Option<String>,
+    /// Unique message identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+}
+
+impl UserInput {
+    /// Create a plain text user message
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(UserInputContent::Text(text.into()))
+    }
+
+    /// Create a user message from content blocks
+    pub fn blocks(blocks: Vec<UserContentBlock>) -> Self {
+        Self::new(UserInputContent::Blocks(blocks))
+    }
+
+    fn new(content: UserInputContent) -> Self {
+        Self {
+            session_id: String::new(),
+            message: UserInputMessage {
+                role: "user".to_string(),
+                content,
+            },
+            parent_tool_use_id: None,
+            uuid: None,
+        }
+    }
+
+    /// Set the session ID
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = session_id.into();
+        self
+    }
+
+    /// Set the parent tool use ID
+    pub fn with_parent_tool_use_id(mut self, id: impl Into<String>) -> Self {
+        self.parent_tool_use_id = Some(id.into());
+        self
+    }
+
+    /// Set the message UUID
+    pub fn with_uuid(mut self, uuid: impl Into<String>) -> Self {
+        self.uuid = Some(uuid.into());
+        self
+    }
+}
+
+impl From<String> for UserInput {
+    fn from(text: String) -> Self {
+        UserInput::text(text)
+    }
+}
+
+impl From<&str> for UserInput {
+    fn from(text: &str) -> Self {
+        UserInput::text(text)
+    }
+}
+
+impl From<Vec<UserContentBlock>> for UserInput {
+    fn from(blocks: Vec<UserContentBlock>) -> Self {
+        UserInput::blocks(blocks)
+    }
+}
+
+/// Body of a [`UserInput`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserInputMessage {
+    /// Message role (always "user")
+    pub role: String,
+    /// Message content
+    pub content: UserInputContent,
+}
+
+/// Content of a [`UserInput`]: plain text or a list of blocks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UserInputContent {
+    /// Plain text, sent as a JSON string
+    Text(String),
+    /// Content blocks, sent as a JSON array
+    Blocks(Vec<UserContentBlock>),
+}
+
+/// Lowercased file extension, if any
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+}
+
+fn unsupported_file(path: &Path, kind: &str) -> ClawError {
+    ClawError::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("Unsupported {} file type: {}", kind, path.display()),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
     #[test]
-    fn test_message_system_init() {
+    fn test_message_system_init() {
+        let json = json!({
+            "type": "system",
+            "subtype": "init",
+            "session_id": "sess_123",
+            "tools": [{"name": "bash", "description": "Run shell commands"}],
+            "mcp_servers": [{"name": "filesystem"}],
+            "extra_field": "value"
+        });
+
+        let msg: Message = serde_json::from_value(json.clone()).unwrap();
+        match &msg {
+            Message::System(SystemMessage::Init {
+                session_id,
+                tools,
+                mcp_servers,
+                ..
+            }) => {
+                assert_eq!(session_id, "sess_123");
+                assert_eq!(tools.len(), 1);
+                assert_eq!(tools[0].name, "bash");
+                assert_eq!(mcp_servers.len(), 1);
+                assert_eq!(mcp_servers[0].name, "filesystem");
+            }
+            _ => panic!("Expected System::Init message"),
+        }
+
+        // Round-trip test
+        let serialized = serde_json::to_value(&msg).unwrap();
+        assert_eq!(serialized["type"], "system");
+        assert_eq!(serialized["subtype"], "init");
+    }
+
+    #[test]
+    fn test_message_system_init_typed_fields() {
+        let json = json!({
+            "type": "system",
+            "subtype": "init",
+            "session_id": "sess_123",
+            "uuid": "evt_1",
+            "cwd": "/work/repo",
+            "tools": ["Read", "Bash"],
+            "mcp_servers": [{"name": "github", "status": "connected"}],
+            "model": "claude-sonnet-4",
+            "permissionMode": "acceptEdits",
+            "apiKeySource": "ANTHROPIC_API_KEY",
+            "slash_commands": ["compact", "review"],
+            "agents": ["code-reviewer"],
+            "output_style": "default",
+            "claude_code_version": "2.1.3"
+        });
+
+        let msg: Message = serde_json::from_value(json.clone()).unwrap();
+        match &msg {
+            Message::System(SystemMessage::Init {
+                mcp_servers,
+                model,
+                cwd,
+                permission_mode,
+                api_key_source,
+                slash_commands,
+                agents,
+                output_style,
+                claude_code_version,
+                extra,
+                ..
+            }) => {
+                assert!(mcp_servers[0].is_connected());
+                assert_eq!(model.as_deref(), Some("claude-sonnet-4"));
+                assert_eq!(cwd.as_deref(), Some(Path::new("/work/repo")));
+                assert_eq!(*permission_mode, Some(PermissionMode::AcceptEdits));
+                assert_eq!(api_key_source.as_deref(), Some("ANTHROPIC_API_KEY"));
+                assert_eq!(slash_commands, &["compact", "review"]);
+                assert_eq!(agents, &["code-reviewer"]);
+                assert_eq!(output_style.as_deref(), Some("default"));
+                assert_eq!(claude_code_version.as_deref(), Some("2.1.3"));
+                assert_eq!(extra["uuid"], "evt_1");
+                assert!(extra.get("model").is_none());
+            }
+            _ => panic!("Expected System::Init message"),
+        }
+
+        let serialized = serde_json::to_value(&msg).unwrap();
+        assert_eq!(serialized["permissionMode"], "acceptEdits");
+        assert_eq!(serialized["apiKeySource"], "ANTHROPIC_API_KEY");
+        assert_eq!(serialized["mcp_servers"][0]["status"], "connected");
+    }
+
+    #[test]
+    fn test_message_system_init_unknown_permission_mode() {
+        let msg: Message = serde_json::from_value(json!({
+            "type": "system",
+            "subtype": "init",
+            "session_id": "sess_123",
+            "tools": [],
+            "mcp_servers": [],
+            "permissionMode": "dontAsk"
+        }))
+        .unwrap();
+        match &msg {
+            Message::System(SystemMessage::Init {
+                permission_mode, ..
+            }) => assert_eq!(
+                permission_mode,
+                &Some(PermissionMode::Other("dontAsk".to_string()))
+            ),
+            _ => panic!("Expected System::Init message"),
+        }
+        assert_eq!(
+            serde_json::to_value(&msg).unwrap()["permissionMode"],
+            "dontAsk"
+        );
+    }
+
+    #[test]
+    fn test_message_system_compact_boundary() {
+        let json = json!({
+            "type": "system",
+            "subtype": "compact_boundary"
+        });
+
+        let msg: Message = serde_json::from_value(json).unwrap();
+        match msg {
+            Message::System(SystemMessage::CompactBoundary {
+                compact_metadata, ..
+            }) => assert!(compact_metadata.is_none()),
+            _ => panic!("Expected System::CompactBoundary message"),
+        }
+    }
+
+    #[test]
+    fn test_message_system_compact_boundary_metadata() {
+        let json = json!({
+            "type": "system",
+            "subtype": "compact_boundary",
+            "session_id": "sess_123",
+            "compact_metadata": {"trigger": "manual", "pre_tokens": 98765}
+        });
+
+        let msg: Message = serde_json::from_value(json.clone()).unwrap();
+        match &msg {
+            Message::System(SystemMessage::CompactBoundary {
+                compact_metadata,
+                extra,
+            }) => {
+                let meta = compact_metadata.as_ref().unwrap();
+                assert_eq!(meta.trigger, CompactTrigger::Manual);
+                assert_eq!(meta.pre_tokens, Some(98765));
+                assert_eq!(extra["session_id"], "sess_123");
+            }
+            _ => panic!("Expected System::CompactBoundary message"),
+        }
+        assert_eq!(serde_json::to_value(&msg).unwrap(), json);
+    }
+
+    #[test]
+    fn test_compact_trigger_unknown() {
+        let meta: CompactMetadata =
+            serde_json::from_value(json!({"trigger": "scheduled"})).unwrap();
+        assert_eq!(meta.trigger, CompactTrigger::Unknown);
+        assert_eq!(meta.pre_tokens, None);
+    }
+
+    #[test]
+    fn test_message_system_other_subtype() {
+        let json = json!({
+            "type": "system",
+            "subtype": "hook_progress",
+            "session_id": "sess_123",
+            "progress": 0.5
+        });
+
+        let msg: Message = serde_json::from_value(json.clone()).unwrap();
+        match &msg {
+            Message::System(SystemMessage::Other { subtype, raw }) => {
+                assert_eq!(subtype, "hook_progress");
+                assert_eq!(raw["progress"], 0.5);
+                assert!(raw.get("subtype").is_none());
+            }
+            _ => panic!("Expected System::Other message"),
+        }
+        assert_eq!(serde_json::to_value(&msg).unwrap(), json);
+    }
+
+    #[test]
+    fn test_result_other_subtype() {
+        let json = json!({
+            "type": "result",
+            "subtype": "error_tool_budget",
+            "is_error": true,
+            "total_cost_usd": 0.25,
+            "session_id": "sess_123"
+        });
+
+        let msg = Message::parse(json.clone()).unwrap();
+        let Message::Result(result) = &msg else {
+            panic!("Expected Result message");
+        };
+        match result {
+            ResultMessage::Other { subtype, raw } => {
+                assert_eq!(subtype, "error_tool_budget");
+                assert_eq!(raw["session_id"], "sess_123");
+                assert!(raw.get("subtype").is_none());
+            }
+            other => panic!("Expected Result::Other, got {other:?}"),
+        }
+        assert_eq!(result.subtype(), "error_tool_budget");
+        assert_eq!(result.total_cost_usd(), None);
+        assert!(result.structured::<serde_json::Value>().is_err());
+        assert_eq!(serde_json::to_value(&msg).unwrap(), json);
+
+        // A known subtype with missing fields is still a parse error
+        let malformed = json!({"type": "result", "subtype": "success"});
+        assert!(matches!(
+            Message::parse(malformed),
+            Err(ClawError::MessageParse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_message_unknown_type() {
+        let json = json!({
+            "type": "tool_progress",
+            "tool_use_id": "toolu_1",
+            "elapsed_ms": 1200
+        });
+
+        let msg = Message::parse(json.clone()).unwrap();
+        match &msg {
+            Message::Unknown { r#type, raw } => {
+                assert_eq!(r#type, "tool_progress");
+                assert_eq!(raw["tool_use_id"], "toolu_1");
+            }
+            _ => panic!("Expected Unknown message"),
+        }
+        assert_eq!(serde_json::to_value(&msg).unwrap(), json);
+    }
+
+    #[test]
+    fn test_message_parse_malformed_known_type_is_error() {
+        let json = json!({"type": "system", "subtype": "init", "tools": "not a list"});
+        match Message::parse(json) {
+            Err(ClawError::MessageParse { reason, raw }) => {
+                assert!(raw.contains("not a list"));
+                assert!(reason.starts_with("malformed system init message: "));
+                assert!(reason.contains("expected a sequence"), "{reason}");
+            }
+            other => panic!("Expected MessageParse error, got {:?}", other),
+        }
+
         let json = json!({
-            "type": "system",
-            "subtype": "init",
-            "session_id": "sess_123",
-            "tools": [{"name": "bash", "description": "Run shell commands"}],
-            "mcp_servers": [{"name": "filesystem"}],
-            "extra_field": "value"
+            "type": "assistant",
+            "message": {"role": "assistant", "content": "not a list"}
         });
+        match Message::parse(json) {
+            Err(ClawError::MessageParse { reason, .. }) => {
+                assert!(reason.starts_with("malformed assistant message: "));
+                assert!(reason.contains("expected a sequence"), "{reason}");
+            }
+            other => panic!("Expected MessageParse error, got {:?}", other),
+        }
 
-        let msg: Message = serde_json::from_value(json.clone()).unwrap();
-        match &msg {
-            Message::System(SystemMessage::Init {
-                session_id,
-                tools,
-                mcp_servers,
-                ..
-            }) => {
-                assert_eq!(session_id, "sess_123");
-                assert_eq!(tools.len(), 1);
-                assert_eq!(tools[0].name, "bash");
-                assert_eq!(mcp_servers.len(), 1);
-                assert_eq!(mcp_servers[0].name, "filesystem");
+        let json = json!({"type": "result", "subtype": "error_max_turns", "num_turns": "ten"});
+        match Message::parse(json) {
+            Err(ClawError::MessageParse { reason, .. }) => {
+                assert!(reason.starts_with("malformed result error_max_turns message: "));
+                assert!(reason.contains("invalid type"), "{reason}");
             }
-            _ => panic!("Expected System::Init message"),
+            other => panic!("Expected MessageParse error, got {:?}", other),
         }
 
-        // Round-trip test
-        let serialized = serde_json::to_value(&msg).unwrap();
-        assert_eq!(serialized["type"], "system");
-        assert_eq!(serialized["subtype"], "init");
+        // Plain serde reports the same errors instead of falling back
+        let json = json!({"type": "result", "subtype": "success", "result": 42});
+        let err = serde_json::from_value::<Message>(json).unwrap_err();
+        assert!(err.to_string().contains("invalid type"), "{err}");
     }
 
     #[test]
-    fn test_message_system_compact_boundary() {
+    fn test_system_reconnected_roundtrip() {
         let json = json!({
             "type": "system",
-            "subtype": "compact_boundary"
+            "subtype": "reconnected",
+            "session_id": "sess-9",
+            "attempt": 2
         });
 
-        let msg: Message = serde_json::from_value(json).unwrap();
-        match msg {
-            Message::System(SystemMessage::CompactBoundary) => {}
-            _ => panic!("Expected System::CompactBoundary message"),
+        let msg: Message = serde_json::from_value(json.clone()).unwrap();
+        match &msg {
+            Message::System(SystemMessage::Reconnected {
+                session_id,
+                attempt,
+            }) => {
+                assert_eq!(session_id, "sess-9");
+                assert_eq!(*attempt, 2);
+            }
+            _ => panic!("Expected System::Reconnected message"),
         }
+        assert_eq!(serde_json::to_value(&msg).unwrap(), json);
     }
 
     #[test]
@@ -513,6 +2522,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_message_user_tool_use_result() {
+        use crate::builtin_tools::BuiltinToolResult;
+
+        let json = json!({
+            "type": "user",
+            "message": {
+                "role": "user",
+                "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "ok"}
+                ]
+            },
+            "parent_tool_use_id": null,
+            "tool_use_result": {"stdout": "ok", "stderr": "", "interrupted": false}
+        });
+
+        let msg: Message = serde_json::from_value(json).unwrap();
+        let Message::User(user_msg) = msg else {
+            panic!("Expected User message");
+        };
+        let raw = user_msg.tool_use_result.expect("tool_use_result");
+        match BuiltinToolResult::parse("Bash", &raw).unwrap() {
+            BuiltinToolResult::Bash(output) => assert_eq!(output.stdout, "ok"),
+            other => panic!("Expected Bash result, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_message_result_success() {
         let json = json!({
@@ -560,19 +2596,145 @@ mod tests {
             "type": "result",
             "subtype": "error",
             "error": "Something went wrong",
-            "code": 500
+            "code": 500,
+            "total_cost_usd": 0.5,
+            "usage": {"input_tokens": 50, "output_tokens": 5}
         });
 
         let msg: Message = serde_json::from_value(json).unwrap();
         match msg {
-            Message::Result(ResultMessage::Error { error, extra }) => {
+            Message::Result(result @ ResultMessage::Error { .. }) => {
+                assert_eq!(result.subtype(), "error");
+                assert_eq!(result.total_cost_usd(), Some(0.5));
+                assert_eq!(result.usage().unwrap().output_tokens, 5);
+                let ResultMessage::Error { error, details } = result else {
+                    unreachable!()
+                };
                 assert_eq!(error, "Something went wrong");
-                assert_eq!(extra["code"], 500);
+                assert_eq!(details.extra["code"], 500);
             }
             _ => panic!("Expected Result::Error message"),
         }
     }
 
+    #[test]
+    fn test_message_result_success_metadata() {
+        let json = json!({
+            "type": "result",
+            "subtype": "success",
+            "result": "done",
+            "stop_reason": "end_turn",
+            "usage": {
+                "input_tokens": 10,
+                "output_tokens": 20,
+                "cache_creation_input_tokens": 300,
+                "cache_read_input_tokens": 4000,
+                "server_tool_use": {"web_search_requests": 2}
+            },
+            "modelUsage": {
+                "claude-sonnet-4": {
+                    "inputTokens": 10,
+                    "outputTokens": 20,
+                    "cacheReadInputTokens": 4000,
+                    "cacheCreationInputTokens": 300,
+                    "webSearchRequests": 2,
+                    "costUSD": 0.031,
+                    "contextWindow": 200000
+                }
+            },
+            "permission_denials": [
+                {"tool_name": "Bash", "tool_use_id": "toolu_9", "tool_input": {"command": "rm -rf /"}}
+            ]
+        });
+
+        let msg: Message = serde_json::from_value(json).unwrap();
+        let Message::Result(result) = &msg else {
+            panic!("Expected Result message");
+        };
+        let usage = result.usage().unwrap();
+        assert_eq!(usage.cache_creation_input_tokens, Some(300));
+        assert_eq!(usage.cache_read_input_tokens, Some(4000));
+        assert_eq!(
+            usage.server_tool_use.as_ref().unwrap().web_search_requests,
+            2
+        );
+        match result {
+            ResultMessage::Success {
+                model_usage,
+                permission_denials,
+                stop_reason,
+                ..
+            } => {
+                let sonnet = &model_usage["claude-sonnet-4"];
+                assert_eq!(sonnet.cache_read_input_tokens, 4000);
+                assert_eq!(sonnet.cost_usd, 0.031);
+                assert_eq!(sonnet.context_window, Some(200000));
+                assert_eq!(permission_denials[0].tool_name, "Bash");
+                assert_eq!(permission_denials[0].tool_input["command"], "rm -rf /");
+                assert_eq!(stop_reason.as_deref(), Some("end_turn"));
+            }
+            _ => panic!("Expected Result::Success message"),
+        }
+    }
+
+    #[test]
+    fn test_message_result_typed_error_subtypes() {
+        for (subtype, expected) in [
+            ("error_max_turns", "ErrorMaxTurns"),
+            ("error_max_budget_usd", "ErrorMaxBudgetUsd"),
+            ("error_during_execution", "ErrorDuringExecution"),
+            (
+                "error_max_structured_output_retries",
+                "ErrorMaxStructuredOutputRetries",
+            ),
+        ] {
+            let json = json!({
+                "type": "result",
+                "subtype": subtype,
+                "is_error": true,
+                "num_turns": 3,
+                "total_cost_usd": 1.5,
+                "usage": {"input_tokens": 5, "output_tokens": 6},
+                "errors": ["limit reached"],
+                "uuid": "res_1"
+            });
+
+            let msg: Message = serde_json::from_value(json).unwrap();
+            let Message::Result(result) = &msg else {
+                panic!("Expected Result message");
+            };
+            assert!(format!("{:?}", result).starts_with(expected));
+            assert_eq!(result.subtype(), subtype);
+            assert_eq!(result.total_cost_usd(), Some(1.5));
+            assert_eq!(result.usage().unwrap().output_tokens, 6);
+
+            let details = result.error_details().unwrap();
+            assert_eq!(details.num_turns, Some(3));
+            assert_eq!(details.errors, vec!["limit reached".to_string()]);
+            assert_eq!(details.extra["uuid"], "res_1");
+
+            let roundtrip = serde_json::to_value(&msg).unwrap();
+            assert_eq!(roundtrip["subtype"], subtype);
+            assert_eq!(roundtrip["errors"][0], "limit reached");
+        }
+    }
+
+    #[test]
+    fn test_structured_on_typed_error_result() {
+        let result: ResultMessage = serde_json::from_value(json!({
+            "subtype": "error_max_structured_output_retries",
+            "errors": ["schema mismatch"]
+        }))
+        .unwrap();
+        match result.structured::<serde_json::Value>() {
+            Err(ClawError::StructuredOutput { reason, raw }) => {
+                assert!(reason.contains("error_max_structured_output_retries"));
+                assert_eq!(raw["errors"][0], "schema mismatch");
+            }
+            other => panic!("Expected StructuredOutput error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_message_result_input_required() {
         let json = json!({
@@ -593,7 +2755,7 @@ mod tests {
         let block: ContentBlock = serde_json::from_value(json).unwrap();
 
         match block {
-            ContentBlock::Text { text } => {
+            ContentBlock::Text { text, .. } => {
                 assert_eq!(text, "Hello world");
             }
             _ => panic!("Expected Text block"),
@@ -677,16 +2839,320 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_content_block_redacted_thinking() {
+        let json = json!({"type": "redacted_thinking", "data": "EmwKAhgBEgy3"});
+        let block: ContentBlock = serde_json::from_value(json.clone()).unwrap();
+        match &block {
+            ContentBlock::RedactedThinking { data } => assert_eq!(data, "EmwKAhgBEgy3"),
+            _ => panic!("Expected RedactedThinking block"),
+        }
+        assert_eq!(serde_json::to_value(&block).unwrap(), json);
+    }
+
+    #[test]
+    fn test_content_block_image_and_document() {
+        let image: ContentBlock = serde_json::from_value(json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo="}
+        }))
+        .unwrap();
+        match image {
+            ContentBlock::Image { source } => assert_eq!(
+                source,
+                MediaSource::Base64 {
+                    media_type: "image/png".to_string(),
+                    data: "iVBORw0KGgo=".to_string(),
+                }
+            ),
+            _ => panic!("Expected Image block"),
+        }
+
+        let document: ContentBlock = serde_json::from_value(json!({
+            "type": "document",
+            "source": {"type": "url", "url": "https://example.com/a.pdf"},
+            "title": "Spec"
+        }))
+        .unwrap();
+        match document {
+            ContentBlock::Document {
+                source,
+                title,
+                context,
+            } => {
+                assert!(matches!(source, MediaSource::Url { .. }));
+                assert_eq!(title.as_deref(), Some("Spec"));
+                assert!(context.is_none());
+            }
+            _ => panic!("Expected Document block"),
+        }
+    }
+
+    #[test]
+    fn test_content_block_server_tool_use_and_web_search_result() {
+        let tool_use: ContentBlock = serde_json::from_value(json!({
+            "type": "server_tool_use",
+            "id": "srvtoolu_1",
+            "name": "web_search",
+            "input": {"query": "rust serde"}
+        }))
+        .unwrap();
+        match tool_use {
+            ContentBlock::ServerToolUse { id, name, input } => {
+                assert_eq!(id, "srvtoolu_1");
+                assert_eq!(name, "web_search");
+                assert_eq!(input["query"], "rust serde");
+            }
+            _ => panic!("Expected ServerToolUse block"),
+        }
+
+        let result: ContentBlock = serde_json::from_value(json!({
+            "type": "web_search_tool_result",
+            "tool_use_id": "srvtoolu_1",
+            "content": [{"type": "web_search_result", "url": "https://serde.rs", "title": "Serde"}]
+        }))
+        .unwrap();
+        match result {
+            ContentBlock::WebSearchToolResult {
+                tool_use_id,
+                content,
+            } => {
+                assert_eq!(tool_use_id, "srvtoolu_1");
+                assert_eq!(content[0]["url"], "https://serde.rs");
+            }
+            _ => panic!("Expected WebSearchToolResult block"),
+        }
+    }
+
+    #[test]
+    fn test_content_block_unknown_roundtrip() {
+        let json = json!({"type": "container_upload", "file_id": "file_1"});
+        let block: ContentBlock = serde_json::from_value(json.clone()).unwrap();
+        match &block {
+            ContentBlock::Unknown(raw) => assert_eq!(raw, &json),
+            _ => panic!("Expected Unknown block"),
+        }
+        assert_eq!(block.block_type(), Some("container_upload"));
+        assert_eq!(serde_json::to_value(&block).unwrap(), json);
+    }
+
+    #[test]
+    fn test_content_block_malformed_known_type_is_error() {
+        let err = serde_json::from_value::<ContentBlock>(json!({"type": "text"})).unwrap_err();
+        assert!(err.to_string().contains("missing field `text`"), "{err}");
+
+        let err = serde_json::from_value::<ContentBlock>(json!({
+            "type": "tool_use",
+            "name": "Read",
+            "input": {}
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("missing field `id`"), "{err}");
+
+        // The error fails the whole message instead of hiding the block
+        let json = json!({
+            "type": "assistant",
+            "message": {"role": "assistant", "content": [{"type": "text"}]}
+        });
+        match Message::parse(json) {
+            Err(ClawError::MessageParse { reason, .. }) => {
+                assert!(reason.starts_with("malformed assistant message: "));
+                assert!(reason.contains("missing field `text`"), "{reason}");
+            }
+            other => panic!("Expected MessageParse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_assistant_message_with_unknown_block_parses() {
+        let json = json!({
+            "type": "assistant",
+            "message": {
+                "role": "assistant",
+                "content": [
+                    {"type": "text", "text": "Searching"},
+                    {"type": "mystery_block", "payload": 1}
+                ]
+            }
+        });
+        let msg: Message = serde_json::from_value(json).unwrap();
+        match msg {
+            Message::Assistant(assistant) => {
+                assert_eq!(assistant.message.content.len(), 2);
+                assert_eq!(assistant.message.content[0].block_type(), Some("text"));
+                assert!(matches!(
+                    assistant.message.content[1],
+                    ContentBlock::Unknown(_)
+                ));
+            }
+            _ => panic!("Expected Assistant message"),
+        }
+    }
+
     #[test]
     fn test_stream_event() {
         let json = json!({
-            "event_type": "message_start",
-            "data": {"message_id": "msg_123"}
+            "type": "stream_event",
+            "uuid": "evt_1",
+            "session_id": "sess_1",
+            "event": {
+                "type": "message_start",
+                "message": {"id": "msg_123", "model": "claude-sonnet-4", "content": []}
+            },
+            "parent_tool_use_id": null
         });
 
-        let event: StreamEvent = serde_json::from_value(json).unwrap();
-        assert_eq!(event.event_type, "message_start");
-        assert_eq!(event.data["message_id"], "msg_123");
+        match serde_json::from_value::<Message>(json).unwrap() {
+            Message::StreamEvent(event) => {
+                assert_eq!(event.session_id.as_deref(), Some("sess_1"));
+                match event.event {
+                    ApiStreamEvent::MessageStart { message } => {
+                        assert_eq!(message.id.as_deref(), Some("msg_123"));
+                    }
+                    other => panic!("Expected MessageStart, got {:?}", other),
+                }
+            }
+            other => panic!("Expected StreamEvent, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_stream_event_unknown_types() {
+        let event: ApiStreamEvent =
+            serde_json::from_value(json!({"type": "future_event", "x": 1})).unwrap();
+        assert!(matches!(event, ApiStreamEvent::Unknown));
+
+        let event: ApiStreamEvent = serde_json::from_value(json!({
+            "type": "content_block_delta", "index": 0, "delta": {"type": "future_delta"}
+        }))
+        .unwrap();
+        assert!(matches!(
+            event,
+            ApiStreamEvent::ContentBlockDelta {
+                delta: ContentDelta::Unknown,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_partial_message_accumulator() {
+        let events = [
+            json!({"type": "message_start", "message": {"id": "msg_1", "model": "claude-sonnet-4"}}),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "thinking", "thinking": ""}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "Let me "}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "check."}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "signature_delta", "signature": "sig"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "content_block_start", "index": 1, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "Reading "}}),
+            json!({"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "the file."}}),
+            json!({"type": "content_block_stop", "index": 1}),
+            json!({"type": "content_block_start", "index": 2, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "Read", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 2, "delta": {"type": "input_json_delta", "partial_json": "{\"file_path\": "}}),
+        ];
+
+        let mut acc = PartialMessageAccumulator::new();
+        for event in &events {
+            acc.apply(&serde_json::from_value(event.clone()).unwrap());
+        }
+
+        assert_eq!(acc.message_id(), Some("msg_1"));
+        assert_eq!(acc.thinking(), "Let me check.");
+        assert_eq!(acc.text(), "Reading the file.");
+        assert_eq!(acc.blocks()[2].tool_input(), None);
+        assert!(!acc.is_complete());
+
+        for event in [
+            json!({"type": "content_block_delta", "index": 2, "delta": {"type": "input_json_delta", "partial_json": "\"/tmp/a\"}"}}),
+            json!({"type": "content_block_stop", "index": 2}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"}, "usage": {"output_tokens": 12}}),
+            json!({"type": "message_stop"}),
+        ] {
+            acc.apply(&serde_json::from_value(event).unwrap());
+        }
+
+        assert_eq!(
+            acc.blocks()[2].tool_input(),
+            Some(json!({"file_path": "/tmp/a"}))
+        );
+        assert_eq!(acc.stop_reason(), Some("tool_use"));
+        assert!(acc.is_complete());
+        match &acc.content()[0] {
+            ContentBlock::Thinking { signature, .. } => {
+                assert_eq!(signature.as_deref(), Some("sig"))
+            }
+            other => panic!("Expected Thinking, got {:?}", other),
+        }
+
+        // The next message starts from scratch
+        acc.apply(
+            &serde_json::from_value(json!({"type": "message_start", "message": {"id": "msg_2"}}))
+                .unwrap(),
+        );
+        assert!(acc.blocks().is_empty());
+        assert_eq!(acc.message_id(), Some("msg_2"));
+
+        // Out-of-order indices are ignored rather than padded
+        acc.apply(
+            &serde_json::from_value(json!({"type": "content_block_start", "index": 1_000_000, "content_block": {"type": "text", "text": ""}}))
+                .unwrap(),
+        );
+        assert!(acc.blocks().is_empty());
+    }
+
+    #[test]
+    fn test_partial_message_accumulator_interleaved_subagent() {
+        let event = |parent: Option<&str>, event: serde_json::Value| -> StreamEvent {
+            serde_json::from_value(json!({
+                "event": event,
+                "parent_tool_use_id": parent,
+            }))
+            .unwrap()
+        };
+        let events = [
+            event(
+                None,
+                json!({"type": "message_start", "message": {"id": "msg_main"}}),
+            ),
+            event(
+                None,
+                json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+            ),
+            event(
+                None,
+                json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Delegating "}}),
+            ),
+            event(
+                Some("toolu_task"),
+                json!({"type": "message_start", "message": {"id": "msg_sub"}}),
+            ),
+            event(
+                Some("toolu_task"),
+                json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+            ),
+            event(
+                Some("toolu_task"),
+                json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Searching"}}),
+            ),
+            event(
+                None,
+                json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "the search."}}),
+            ),
+        ];
+
+        let mut main = PartialMessageAccumulator::new();
+        let mut sub = PartialMessageAccumulator::for_subagent("toolu_task");
+        for event in &events {
+            main.push(event);
+            sub.push(event);
+        }
+
+        assert_eq!(main.message_id(), Some("msg_main"));
+        assert_eq!(main.text(), "Delegating the search.");
+        assert_eq!(sub.message_id(), Some("msg_sub"));
+        assert_eq!(sub.text(), "Searching");
+        assert_eq!(sub.parent_tool_use_id(), Some("toolu_task"));
     }
 
     #[test]
@@ -701,6 +3167,81 @@ mod tests {
         assert_eq!(usage.output_tokens, 50);
     }
 
+    #[test]
+    fn test_usage_info_add_assign() {
+        let mut total = UsageInfo::default();
+        total += &UsageInfo {
+            input_tokens: 100,
+            output_tokens: 50,
+            cache_read_input_tokens: Some(10),
+            ..Default::default()
+        };
+        total += &UsageInfo {
+            input_tokens: u32::MAX,
+            output_tokens: 5,
+            cache_read_input_tokens: Some(5),
+            server_tool_use: Some(ServerToolUsage {
+                web_search_requests: 2,
+                web_fetch_requests: 0,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(total.input_tokens, u32::MAX);
+        assert_eq!(total.output_tokens, 55);
+        assert_eq!(total.cache_read_input_tokens, Some(15));
+        assert_eq!(total.server_tool_use.unwrap().web_search_requests, 2);
+    }
+
+    #[test]
+    fn test_rate_limit_event_typed() {
+        let json = json!({
+            "type": "rate_limit_event",
+            "uuid": "evt_1",
+            "session_id": "sess_1",
+            "rate_limit_info": {
+                "status": "allowed_warning",
+                "rateLimitType": "seven_day_opus",
+                "utilization": 0.92,
+                "resetsAt": 1760000000,
+                "overageStatus": "rejected",
+                "isUsingOverage": false
+            }
+        });
+
+        let msg: Message = serde_json::from_value(json.clone()).unwrap();
+        let Message::RateLimitEvent(event) = &msg else {
+            panic!("Expected RateLimitEvent");
+        };
+        let info = &event.rate_limit_info;
+        assert_eq!(info.status, RateLimitStatus::AllowedWarning);
+        assert_eq!(info.rate_limit_type, Some(RateLimitType::SevenDayOpus));
+        assert_eq!(info.utilization, Some(0.92));
+        assert_eq!(info.overage_status, Some(RateLimitStatus::Rejected));
+        assert_eq!(
+            info.resets_at_time(),
+            Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1760000000))
+        );
+        assert!(!info.is_blocking());
+        assert_eq!(serde_json::to_value(&msg).unwrap(), json);
+    }
+
+    #[test]
+    fn test_rate_limit_info_blocking() {
+        let mut info: RateLimitInfo = serde_json::from_value(json!({
+            "status": "rejected",
+            "rateLimitType": "monthly_team"
+        }))
+        .unwrap();
+        assert_eq!(info.rate_limit_type, Some(RateLimitType::Unknown));
+        assert!(info.is_blocking());
+        // Unknown reset time: blocking, but no known wait
+        assert_eq!(info.retry_after(), None);
+
+        // Overage absorbs the rejected limit
+        info.overage_status = Some(RateLimitStatus::Allowed);
+        assert!(!info.is_blocking());
+    }
+
     #[test]
     fn test_tool_info_minimal() {
         let json = json!({"name": "bash"});
@@ -760,6 +3301,26 @@ mod tests {
         assert_eq!(server.name, "filesystem");
         assert_eq!(server.extra["version"], "1.0.0");
         assert_eq!(server.extra["extra"], "data");
+        assert_eq!(server.status, None);
+        assert!(!server.is_connected());
+    }
+
+    #[test]
+    fn test_mcp_server_info_status() {
+        for (wire, status) in [
+            ("connected", McpServerStatus::Connected),
+            ("failed", McpServerStatus::Failed),
+            ("needs-auth", McpServerStatus::NeedsAuth),
+            ("pending", McpServerStatus::Pending),
+            ("disabled", McpServerStatus::Disabled),
+            ("rebooting", McpServerStatus::Unknown),
+        ] {
+            let server: McpServerInfo =
+                serde_json::from_value(json!({"name": "files", "status": wire})).unwrap();
+            assert_eq!(server.status, Some(status));
+            assert!(server.extra.get("status").is_none());
+            assert_eq!(server.is_connected(), status == McpServerStatus::Connected);
+        }
     }
 
     #[test]
@@ -783,6 +3344,95 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_assistant_message_metadata_round_trip() {
+        let json = json!({
+            "type": "assistant",
+            "message": {
+                "id": "msg_01XYZ",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-sonnet-4-5",
+                "content": [{"type": "text", "text": "Done."}],
+                "stop_reason": "end_turn",
+                "stop_sequence": "###",
+                "usage": {
+                    "input_tokens": 12,
+                    "output_tokens": 3,
+                    "cache_creation_input_tokens": 0,
+                    "cache_read_input_tokens": 100,
+                    "service_tier": "standard"
+                },
+                "context_management": {"applied_edits": []}
+            },
+            "parent_tool_use_id": "toolu_1",
+            "session_id": "sess_1",
+            "uuid": "4f1c-uuid"
+        });
+
+        let msg: Message = serde_json::from_value(json.clone()).unwrap();
+        let Message::Assistant(assistant) = &msg else {
+            panic!("Expected Assistant message");
+        };
+        let api = &assistant.message;
+        assert_eq!(api.id.as_deref(), Some("msg_01XYZ"));
+        assert_eq!(api.model.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(api.stop_reason, Some(Some("end_turn".to_string())));
+        assert_eq!(api.stop_sequence, Some(Some("###".to_string())));
+        let usage = api.usage.as_ref().unwrap();
+        assert_eq!(usage.cache_read_input_tokens, Some(100));
+        assert_eq!(usage.extra["service_tier"], "standard");
+        assert_eq!(api.extra["type"], "message");
+        assert_eq!(assistant.extra["uuid"], "4f1c-uuid");
+
+        assert_eq!(serde_json::to_value(&msg).unwrap(), json);
+    }
+
+    #[test]
+    fn test_assistant_message_keeps_nulls_and_citations() {
+        let json = json!({
+            "type": "assistant",
+            "message": {
+                "role": "assistant",
+                "content": [{
+                    "type": "text",
+                    "text": "The sky is blue.",
+                    "citations": [{
+                        "type": "char_location",
+                        "cited_text": "The sky is blue.",
+                        "document_index": 0,
+                        "start_char_index": 0,
+                        "end_char_index": 16
+                    }]
+                }],
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 8, "output_tokens": 5}
+            },
+            "parent_tool_use_id": null,
+            "session_id": "sess_1"
+        });
+
+        let msg: Message = serde_json::from_value(json.clone()).unwrap();
+        let Message::Assistant(assistant) = &msg else {
+            panic!("Expected Assistant message");
+        };
+        let api = &assistant.message;
+        assert_eq!(api.stop_sequence, Some(None));
+        assert_eq!(api.id, None);
+        match &api.content[0] {
+            ContentBlock::Text { text, extra } => {
+                assert_eq!(text, "The sky is blue.");
+                assert_eq!(extra["citations"][0]["end_char_index"], 16);
+            }
+            other => panic!("Expected Text block, got {other:?}"),
+        }
+        let usage = api.usage.as_ref().unwrap();
+        assert_eq!(usage.cache_read_input_tokens, None);
+
+        assert_eq!(serde_json::to_value(&msg).unwrap(), json);
+    }
+
     #[test]
     fn test_json_round_trip_complex() {
         // Complex message with multiple content blocks
@@ -792,6 +3442,7 @@ mod tests {
                 content: vec![
                     ContentBlock::Text {
                         text: "I'll run that command.".to_string(),
+                        extra: Default::default(),
                     },
                     ContentBlock::ToolUse {
                         id: "tool_xyz".to_string(),
@@ -803,10 +3454,12 @@ mod tests {
                         signature: None,
                     },
                 ],
+                ..Default::default()
             },
             parent_tool_use_id: None,
             duration_ms: Some(150),
             error: None,
+            extra: Default::default(),
         });
 
         // Serialize to JSON
@@ -892,7 +3545,7 @@ mod tests {
                 assert_eq!(assistant_msg.message.role, "assistant");
                 assert_eq!(assistant_msg.message.content.len(), 1);
                 match &assistant_msg.message.content[0] {
-                    ContentBlock::Text { text } => {
+                    ContentBlock::Text { text, .. } => {
                         assert!(text.contains("README.md"));
                     }
                     _ => panic!("Expected Text content block"),
@@ -952,7 +3605,7 @@ mod tests {
                 assert_eq!(assistant_msg.message.content.len(), 2);
                 // First block: Text
                 match &assistant_msg.message.content[0] {
-                    ContentBlock::Text { text } => {
+                    ContentBlock::Text { text, .. } => {
                         assert!(text.contains("check the current directory"));
                     }
                     _ => panic!("Expected Text block"),
@@ -996,7 +3649,7 @@ mod tests {
             Message::Assistant(assistant_msg) => {
                 assert_eq!(assistant_msg.message.content.len(), 1);
                 match &assistant_msg.message.content[0] {
-                    ContentBlock::Text { text } => {
+                    ContentBlock::Text { text, .. } => {
                         assert!(text.contains("Rust project"));
                     }
                     _ => panic!("Expected Text block"),
@@ -1035,10 +3688,10 @@ mod tests {
 
         // Third message: Result::Error with extra fields
         match &messages[2] {
-            Message::Result(ResultMessage::Error { error, extra }) => {
+            Message::Result(ResultMessage::Error { error, details }) => {
                 assert_eq!(error, "Failed to execute command: permission denied");
-                assert_eq!(extra["error_code"], "EACCES");
-                assert_eq!(extra["exit_code"], 126);
+                assert_eq!(details.extra["error_code"], "EACCES");
+                assert_eq!(details.extra["exit_code"], 126);
             }
             _ => panic!("Expected Result::Error"),
         }
@@ -1071,7 +3724,7 @@ mod tests {
                 }
                 // Second block: Text
                 match &assistant_msg.message.content[1] {
-                    ContentBlock::Text { text } => {
+                    ContentBlock::Text { text, .. } => {
                         assert!(text.contains("list the files"));
                     }
                     _ => panic!("Expected Text block"),
@@ -1117,8 +3770,15 @@ mod tests {
                     | Message::Result(_)
                     | Message::ControlRequest { .. }
                     | Message::ControlResponse { .. }
+                    | Message::StreamEvent(_)
                     | Message::RateLimitEvent(_)
                     | Message::McpMessage(_) => {}
+                    Message::Unknown { r#type, .. } => {
+                        panic!(
+                            "Fixture '{}' has unrecognized type {}",
+                            fixture_name, r#type
+                        )
+                    }
                 }
                 // If we got here, the message is valid
                 let _ = i; // Use i to avoid unused warning
@@ -1134,7 +3794,7 @@ mod tests {
         let block: ContentBlock = serde_json::from_value(json).unwrap();
 
         match block {
-            ContentBlock::Text { text } => {
+            ContentBlock::Text { text, .. } => {
                 assert_eq!(text, "");
             }
             _ => panic!("Expected Text block"),
@@ -1237,7 +3897,7 @@ mod tests {
 
         let block: ContentBlock = serde_json::from_value(json).unwrap();
         match block {
-            ContentBlock::Text { text } => {
+            ContentBlock::Text { text, .. } => {
                 assert!(text.contains("世界"));
                 assert!(text.contains("🚀"));
                 assert!(text.contains("✅"));
@@ -1245,4 +3905,129 @@ mod tests {
             _ => panic!("Expected Text block"),
         }
     }
+
+    #[test]
+    fn test_user_content_block_text_serialization() {
+        let block = UserContentBlock::text("Describe this screenshot");
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(
+            json,
+            json!({"type": "text", "text": "Describe this screenshot"})
+        );
+    }
+
+    #[test]
+    fn test_user_content_block_image_bytes_encodes_base64() {
+        let block = UserContentBlock::image_bytes("image/png", b"hello");
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(json["type"], "image");
+        assert_eq!(json["source"]["type"], "base64");
+        assert_eq!(json["source"]["media_type"], "image/png");
+        assert_eq!(json["source"]["data"], "aGVsbG8=");
+    }
+
+    #[test]
+    fn test_user_content_block_document_variants() {
+        let json = serde_json::to_value(UserContentBlock::pdf_base64("JVBERi0=")).unwrap();
+        assert_eq!(json["type"], "document");
+        assert_eq!(json["source"]["media_type"], "application/pdf");
+        assert!(json.get("title").is_none());
+
+        let block = UserContentBlock::document_text("Release notes")
+            .with_title("notes.md")
+            .with_context("Summarize for the changelog");
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(json["source"]["type"], "text");
+        assert_eq!(json["source"]["data"], "Release notes");
+        assert_eq!(json["title"], "notes.md");
+        assert_eq!(json["context"], "Summarize for the changelog");
+
+        let json =
+            serde_json::to_value(UserContentBlock::document_url("https://x.dev/a.pdf")).unwrap();
+        assert_eq!(
+            json["source"],
+            json!({"type": "url", "url": "https://x.dev/a.pdf"})
+        );
+    }
+
+    #[test]
+    fn test_user_content_block_tool_result() {
+        let json = serde_json::to_value(UserContentBlock::tool_result("toolu_1", "ok")).unwrap();
+        assert_eq!(
+            json,
+            json!({"type": "tool_result", "tool_use_id": "toolu_1", "content": "ok"})
+        );
+
+        let json = serde_json::to_value(UserContentBlock::tool_error("toolu_2", "boom")).unwrap();
+        assert_eq!(json["is_error"], true);
+    }
+
+    #[test]
+    fn test_user_input_envelope() {
+        let json = serde_json::to_value(UserInput::text("hi")).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "type": "user",
+                "session_id": "",
+                "message": {"role": "user", "content": "hi"},
+                "parent_tool_use_id": null
+            })
+        );
+
+        let input = UserInput::blocks(vec![
+            UserContentBlock::text("Review this"),
+            UserContentBlock::image_base64("image/png", "aGVsbG8="),
+        ])
+        .with_session_id("sess_1")
+        .with_parent_tool_use_id("toolu_9")
+        .with_uuid("u-1");
+        let json = serde_json::to_value(&input).unwrap();
+        assert_eq!(json["session_id"], "sess_1");
+        assert_eq!(json["parent_tool_use_id"], "toolu_9");
+        assert_eq!(json["uuid"], "u-1");
+        let content = json["message"]["content"].as_array().unwrap();
+        assert_eq!(content[1]["source"]["type"], "base64");
+
+        let parsed: UserInput = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, input);
+    }
+
+    #[test]
+    fn test_user_content_block_roundtrip() {
+        let block = UserContentBlock::image_url("https://example.com/shot.webp");
+        let json = serde_json::to_string(&block).unwrap();
+        let parsed: UserContentBlock = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, block);
+    }
+
+    #[test]
+    fn test_user_content_block_from_files() {
+        let dir = std::env::temp_dir().join(format!("rusty_claw_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let image = dir.join("shot.PNG");
+        std::fs::write(&image, b"hello").unwrap();
+        let block = UserContentBlock::image_file(&image).unwrap();
+        assert_eq!(
+            block,
+            UserContentBlock::image_base64("image/png", "aGVsbG8=")
+        );
+
+        let doc = dir.join("notes.md");
+        std::fs::write(&doc, "# Notes").unwrap();
+        match UserContentBlock::document_file(&doc).unwrap() {
+            UserContentBlock::Document { source, title, .. } => {
+                assert_eq!(title.as_deref(), Some("notes.md"));
+                assert!(matches!(source, MediaSource::Text { data, .. } if data == "# Notes"));
+            }
+            _ => panic!("Expected Document block"),
+        }
+
+        let err = UserContentBlock::image_file(dir.join("archive.zip")).unwrap_err();
+        assert!(matches!(err, ClawError::Io(_)));
+        assert!(UserContentBlock::image_file(dir.join("missing.png")).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
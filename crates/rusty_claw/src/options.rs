@@ -30,10 +30,13 @@
 //! ```
 
 use crate::control::handlers::CanUseToolHandler;
+use crate::control::messages::ControlSubtype;
+use crate::hooks::{HookCallback, HookRegistration};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Known beta feature identifiers for use with [`ClaudeAgentOptions::betas`]
 ///
@@ -80,7 +83,7 @@ pub enum SystemPrompt {
 }
 
 /// Permission mode for tool execution
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PermissionMode {
     /// Default permission mode
@@ -99,6 +102,12 @@ pub enum PermissionMode {
     Deny,
     /// Use custom permission logic via hooks
     Custom,
+    /// Mode not recognized by this SDK version, with the CLI's name for it
+    /// (e.g., `"dontAsk"`)
+    ///
+    /// Passed through to the CLI unchanged.
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl PermissionMode {
@@ -113,8 +122,32 @@ impl PermissionMode {
             PermissionMode::Ask => "ask",
             PermissionMode::Deny => "deny",
             PermissionMode::Custom => "custom",
+            PermissionMode::Other(mode) => mode,
+        }
+    }
+
+    /// Parse the CLI's camelCase form, as reported in the system init message
+    ///
+    /// Returns `None` for modes this SDK version does not know; see
+    /// [`from_cli_arg_lossless()`](Self::from_cli_arg_lossless) to keep them.
+    pub fn from_cli_arg(value: &str) -> Option<Self> {
+        match value {
+            "default" => Some(PermissionMode::Default),
+            "acceptEdits" => Some(PermissionMode::AcceptEdits),
+            "bypassPermissions" => Some(PermissionMode::BypassPermissions),
+            "plan" => Some(PermissionMode::Plan),
+            "allow" => Some(PermissionMode::Allow),
+            "ask" => Some(PermissionMode::Ask),
+            "deny" => Some(PermissionMode::Deny),
+            "custom" => Some(PermissionMode::Custom),
+            _ => None,
         }
     }
+
+    /// Parse the CLI's camelCase form, keeping unknown modes as [`PermissionMode::Other`]
+    pub fn from_cli_arg_lossless(value: &str) -> Self {
+        Self::from_cli_arg(value).unwrap_or_else(|| PermissionMode::Other(value.to_string()))
+    }
 }
 
 // ============================================================================
@@ -301,12 +334,37 @@ pub enum HookEvent {
     PermissionRequest,
 }
 
+/// Decision a hook answers with when its callback exceeds [`HookMatcher::timeout_ms`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookTimeoutBehavior {
+    /// Let the action proceed (`{"continue": true}`)
+    Allow,
+    /// Deny the tool call or permission request, or block the action for
+    /// events that support blocking
+    Deny,
+}
+
+impl HookTimeoutBehavior {
+    /// Behavior for a matcher without [`HookMatcher::on_timeout`]
+    ///
+    /// `Deny` for `PreToolUse` and `PermissionRequest`, so a hung policy hook
+    /// cannot wave a tool through; `Allow` for every other event.
+    pub fn default_for(event: &HookEvent) -> Self {
+        match event {
+            HookEvent::PreToolUse | HookEvent::PermissionRequest => HookTimeoutBehavior::Deny,
+            _ => HookTimeoutBehavior::Allow,
+        }
+    }
+}
+
 /// Hook matcher for pattern-based hook triggering.
 ///
 /// A `HookMatcher` can filter hooks by:
 /// - Tool name pattern (exact match or glob-style wildcard with `*`)
 /// - Event types (which lifecycle events this matcher responds to)
-/// - Timeout (maximum milliseconds before the hook is cancelled)
+/// - Timeout (maximum milliseconds before the hook is cancelled) and what
+///   the hook answers when it times out
 ///
 /// # Examples
 ///
@@ -331,6 +389,12 @@ pub enum HookEvent {
 /// // With timeout
 /// let matcher = HookMatcher::all().with_timeout_ms(5000);
 /// assert_eq!(matcher.timeout_ms, Some(5000));
+///
+/// // Let the tool run if the hook is too slow
+/// let matcher = HookMatcher::tool("Read")
+///     .with_timeout_ms(5000)
+///     .with_timeout_behavior(HookTimeoutBehavior::Allow);
+/// assert_eq!(matcher.on_timeout, Some(HookTimeoutBehavior::Allow));
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HookMatcher {
@@ -354,12 +418,19 @@ pub struct HookMatcher {
     /// Per-hook timeout in milliseconds.
     ///
     /// If the hook handler takes longer than this to respond, it is cancelled
-    /// and the default allow/deny decision is applied. This prevents slow
-    /// external services from blocking the entire session.
+    /// and the [`on_timeout`](Self::on_timeout) decision is applied. This
+    /// prevents slow external services from blocking the entire session.
     ///
     /// When `None`, no timeout is enforced.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout_ms: Option<u64>,
+
+    /// Decision applied when the hook exceeds [`timeout_ms`](Self::timeout_ms).
+    ///
+    /// When `None`, [`HookTimeoutBehavior::default_for`] picks one from the
+    /// event: permission checks fail closed, everything else continues.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub on_timeout: Option<HookTimeoutBehavior>,
 }
 
 impl HookMatcher {
@@ -369,6 +440,7 @@ impl HookMatcher {
             tool_name: None,
             hooks: Vec::new(),
             timeout_ms: None,
+            on_timeout: None,
         }
     }
 
@@ -382,6 +454,7 @@ impl HookMatcher {
             tool_name: Some(name.into()),
             hooks: Vec::new(),
             timeout_ms: None,
+            on_timeout: None,
         }
     }
 
@@ -395,13 +468,19 @@ impl HookMatcher {
 
     /// Set the per-hook timeout in milliseconds.
     ///
-    /// If the hook exceeds this duration, it is cancelled and the default
-    /// decision is applied.
+    /// If the hook exceeds this duration, it is cancelled and the timeout
+    /// decision is applied (see [`with_timeout_behavior`](Self::with_timeout_behavior)).
     pub fn with_timeout_ms(mut self, ms: u64) -> Self {
         self.timeout_ms = Some(ms);
         self
     }
 
+    /// Set the decision applied when the hook times out.
+    pub fn with_timeout_behavior(mut self, behavior: HookTimeoutBehavior) -> Self {
+        self.on_timeout = Some(behavior);
+        self
+    }
+
     /// Check if this matcher matches the given tool name.
     ///
     /// Supports glob-style wildcard patterns with `*`:
@@ -537,6 +616,239 @@ impl SandboxSettings {
     }
 }
 
+/// What to do when a bounded message channel is full
+///
+/// Applies to the transport's stdout channel and to each
+/// [`ResponseStream`](crate::client::ResponseStream). See [`ChannelConfig`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for the consumer to catch up (backpressure)
+    ///
+    /// On the stdout channel the reader stops reading, so the OS pipe fills and
+    /// the CLI blocks on its next write. A full `ResponseStream` pauses the
+    /// client's message router the same way, so the backpressure reaches the
+    /// CLI. While an SDK control request (e.g. `interrupt()`) awaits its
+    /// response, the router reads ahead and holds turn messages in memory
+    /// instead, so the response is not stuck behind an unread stream.
+    #[default]
+    Block,
+    /// Drop partial `stream_event` messages when full; block for everything else
+    DropPartial,
+    /// Stop delivering and yield [`ClawError::ChannelOverflow`](crate::error::ClawError::ChannelOverflow)
+    Error,
+}
+
+/// Capacity and overflow policy for the bounded message channels
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::options::{ClaudeAgentOptions, OverflowPolicy};
+///
+/// let options = ClaudeAgentOptions::builder()
+///     .channel_capacity(128)
+///     .overflow_policy(OverflowPolicy::DropPartial)
+///     .build();
+/// assert_eq!(options.channel.capacity, 128);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelConfig {
+    /// Maximum number of buffered messages per channel (at least 1)
+    pub capacity: usize,
+    /// Behavior when the channel is full
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            overflow: OverflowPolicy::Block,
+        }
+    }
+}
+
+/// Crash recovery policy for interactive [`ClaudeClient`](crate::client::ClaudeClient) sessions
+///
+/// When the CLI subprocess exits without [`close()`](crate::client::ClaudeClient::close)
+/// being called, the client respawns it with `--resume <session_id>`, re-runs the
+/// initialize handshake with the same handlers, and emits
+/// [`SystemMessage::Reconnected`](crate::messages::SystemMessage::Reconnected).
+/// Attempts back off exponentially from `initial_backoff` up to `max_backoff`.
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::options::{ClaudeAgentOptions, RecoveryPolicy};
+/// use std::time::Duration;
+///
+/// let options = ClaudeAgentOptions::builder()
+///     .recovery(RecoveryPolicy::new(5).with_backoff(Duration::from_secs(1), Duration::from_secs(30)))
+///     .build();
+/// assert_eq!(options.recovery.unwrap().max_attempts, 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryPolicy {
+    /// Maximum respawn attempts per crash before giving up
+    pub max_attempts: u32,
+    /// Delay before the first attempt
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_backoff: Duration,
+}
+
+impl RecoveryPolicy {
+    /// Create a policy with `max_attempts` and the default backoff
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /// Set the initial and maximum backoff between attempts
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Delay before the given 1-based attempt: doubles each attempt, capped at `max_backoff`
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+/// How [`ClaudeClient`](crate::client::ClaudeClient) handles a blocking rate limit before a new turn
+///
+/// The client remembers the latest [`RateLimitEvent`](crate::messages::RateLimitEvent).
+/// While it reports a rejected limit with a known reset time,
+/// [`send_message()`](crate::client::ClaudeClient::send_message) either waits
+/// for the reset or fails with [`ClawError::RateLimited`](crate::error::ClawError::RateLimited)
+/// instead of sending a turn that would be rejected.
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::options::{ClaudeAgentOptions, RateLimitPolicy};
+/// use std::time::Duration;
+///
+/// let options = ClaudeAgentOptions::builder()
+///     .rate_limit_policy(RateLimitPolicy::wait(Duration::from_secs(600)))
+///     .build();
+/// assert!(matches!(options.rate_limit_policy, Some(RateLimitPolicy::Wait { .. })));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitPolicy {
+    /// Sleep until the limit resets; fail if the reset is further away than `max_wait`
+    Wait {
+        /// Longest acceptable wait
+        max_wait: Duration,
+    },
+    /// Fail immediately with [`ClawError::RateLimited`](crate::error::ClawError::RateLimited)
+    FailFast,
+}
+
+impl RateLimitPolicy {
+    /// Wait for resets up to `max_wait` away
+    pub fn wait(max_wait: Duration) -> Self {
+        RateLimitPolicy::Wait { max_wait }
+    }
+}
+
+/// `output_format` requesting structured output matching the schema of `T`
+pub(crate) fn output_format_for<T: schemars::JsonSchema>() -> serde_json::Value {
+    serde_json::json!({
+        "type": "json_schema",
+        "schema": schemars::schema_for!(T),
+    })
+}
+
+/// Extract the schema from an `output_format` value
+///
+/// Accepts the `{"type": "json_schema", "schema": ...}` wrapper used by the
+/// official SDKs, or a bare schema.
+fn json_schema_of(format: &serde_json::Value) -> &serde_json::Value {
+    match format.get("schema") {
+        Some(schema) if format.get("type").and_then(|t| t.as_str()) == Some("json_schema") => {
+            schema
+        }
+        _ => format,
+    }
+}
+
+/// Response deadlines for outgoing control requests, per request subtype
+///
+/// Subtypes without an override use `default`. Out of the box `initialize`
+/// gets the full 60 seconds to accommodate MCP server startup, while
+/// `interrupt` gives up after 10 seconds.
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::control::messages::ControlSubtype;
+/// use rusty_claw::options::ClaudeAgentOptions;
+/// use std::time::Duration;
+///
+/// let options = ClaudeAgentOptions::builder()
+///     .control_timeout(ControlSubtype::Interrupt, Duration::from_secs(2))
+///     .default_control_timeout(Duration::from_secs(30))
+///     .build();
+/// assert_eq!(
+///     options.control_timeouts.for_subtype(ControlSubtype::Interrupt),
+///     Duration::from_secs(2)
+/// );
+/// assert_eq!(
+///     options.control_timeouts.for_subtype(ControlSubtype::SetModel),
+///     Duration::from_secs(30)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlTimeouts {
+    /// Deadline for subtypes without an override
+    pub default: Duration,
+    /// Per-subtype deadlines
+    pub overrides: HashMap<ControlSubtype, Duration>,
+}
+
+impl ControlTimeouts {
+    /// Set the deadline for one request subtype
+    pub fn with_timeout(mut self, subtype: ControlSubtype, timeout: Duration) -> Self {
+        self.overrides.insert(subtype, timeout);
+        self
+    }
+
+    /// Deadline that applies to `subtype`
+    pub fn for_subtype(&self, subtype: ControlSubtype) -> Duration {
+        self.overrides
+            .get(&subtype)
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+impl Default for ControlTimeouts {
+    fn default() -> Self {
+        Self {
+            default: Duration::from_secs(60),
+            overrides: HashMap::from([(ControlSubtype::Interrupt, Duration::from_secs(10))]),
+        }
+    }
+}
+
 /// Configuration options for Claude agent sessions
 ///
 /// This struct provides comprehensive configuration for Claude agent behavior,
@@ -583,6 +895,10 @@ pub struct ClaudeAgentOptions {
     // Hooks (placeholder for future tasks)
     /// Hook event handlers
     pub hooks: HashMap<HookEvent, Vec<HookMatcher>>,
+    /// Typed hook callbacks, assigned callback IDs during initialization
+    ///
+    /// Use [`ClaudeAgentOptionsBuilder::hook`] to add entries.
+    pub hook_callbacks: Vec<HookRegistration>,
 
     // Subagents (placeholder for future tasks)
     /// Agent definitions
@@ -602,6 +918,10 @@ pub struct ClaudeAgentOptions {
     pub session_name: Option<String>,
     /// Enable file-based checkpointing
     pub enable_file_checkpointing: bool,
+    /// Respawn and resume the session if the CLI process dies (see [`RecoveryPolicy`])
+    pub recovery: Option<RecoveryPolicy>,
+    /// Wait for or fail on rate limits before sending a turn (see [`RateLimitPolicy`])
+    pub rate_limit_policy: Option<RateLimitPolicy>,
 
     // Environment
     /// Working directory
@@ -619,7 +939,10 @@ pub struct ClaudeAgentOptions {
     pub setting_sources: Option<Vec<String>>,
 
     // Output
-    /// Output format specification
+    /// Output format specification, passed to the CLI as `--json-schema`
+    ///
+    /// Either `{"type": "json_schema", "schema": {...}}` or a bare JSON schema.
+    /// Use [`ClaudeAgentOptionsBuilder::output_schema`] to derive it from a type.
     pub output_format: Option<serde_json::Value>,
     /// Include partial messages in output
     pub include_partial_messages: bool,
@@ -652,6 +975,16 @@ pub struct ClaudeAgentOptions {
     /// Stdout buffer size for the subprocess (None = system default)
     pub max_buffer_size: Option<usize>,
 
+    /// Capacity and overflow policy for message channels (see [`ChannelConfig`])
+    pub channel: ChannelConfig,
+
+    /// Response deadlines for control requests (see [`ControlTimeouts`])
+    pub control_timeouts: ControlTimeouts,
+
+    /// Maximum number of incoming control requests (permission checks, hook
+    /// callbacks, MCP messages) handled at once (None = 16)
+    pub max_concurrent_control_requests: Option<usize>,
+
     /// Escape hatch for arbitrary CLI flags not yet modeled in the SDK.
     ///
     /// Each entry `(key, value)` is emitted as `--key value` or `--key` (if `value` is `None`).
@@ -688,8 +1021,12 @@ pub struct ClaudeAgentOptions {
     ///         &self,
     ///         tool_name: &str,
     ///         _tool_input: &serde_json::Value,
+    ///         _context: &ToolPermissionContext,
     ///     ) -> Result<PermissionDecision, ClawError> {
-    ///         Ok(PermissionDecision::Allow { updated_input: None })
+    ///         Ok(PermissionDecision::Allow {
+    ///             updated_input: None,
+    ///             updated_permissions: vec![],
+    ///         })
     ///     }
     /// }
     ///
@@ -717,12 +1054,15 @@ impl std::fmt::Debug for ClaudeAgentOptions {
             .field("mcp_servers", &"<McpServerConfig map>")
             .field("sdk_mcp_servers", &self.sdk_mcp_servers)
             .field("hooks", &self.hooks)
+            .field("hook_callbacks", &self.hook_callbacks)
             .field("agents", &self.agents)
             .field("resume", &self.resume)
             .field("fork_session", &self.fork_session)
             .field("continue_conversation", &self.continue_conversation)
             .field("session_name", &self.session_name)
             .field("enable_file_checkpointing", &self.enable_file_checkpointing)
+            .field("recovery", &self.recovery)
+            .field("rate_limit_policy", &self.rate_limit_policy)
             .field("cwd", &self.cwd)
             .field("cli_path", &self.cli_path)
             .field("env", &self.env)
@@ -737,6 +1077,12 @@ impl std::fmt::Debug for ClaudeAgentOptions {
             .field("user", &self.user)
             .field("fallback_model", &self.fallback_model)
             .field("max_buffer_size", &self.max_buffer_size)
+            .field("channel", &self.channel)
+            .field("control_timeouts", &self.control_timeouts)
+            .field(
+                "max_concurrent_control_requests",
+                &self.max_concurrent_control_requests,
+            )
             .field("extra_args", &self.extra_args)
             .field(
                 "stderr_callback",
@@ -768,12 +1114,15 @@ impl Clone for ClaudeAgentOptions {
             mcp_servers: self.mcp_servers.clone(),
             sdk_mcp_servers: self.sdk_mcp_servers.clone(),
             hooks: self.hooks.clone(),
+            hook_callbacks: self.hook_callbacks.clone(),
             agents: self.agents.clone(),
             resume: self.resume.clone(),
             fork_session: self.fork_session,
             continue_conversation: self.continue_conversation,
             session_name: self.session_name.clone(),
             enable_file_checkpointing: self.enable_file_checkpointing,
+            recovery: self.recovery.clone(),
+            rate_limit_policy: self.rate_limit_policy,
             cwd: self.cwd.clone(),
             cli_path: self.cli_path.clone(),
             env: self.env.clone(),
@@ -788,6 +1137,9 @@ impl Clone for ClaudeAgentOptions {
             user: self.user.clone(),
             fallback_model: self.fallback_model.clone(),
             max_buffer_size: self.max_buffer_size,
+            channel: self.channel,
+            control_timeouts: self.control_timeouts.clone(),
+            max_concurrent_control_requests: self.max_concurrent_control_requests,
             extra_args: self.extra_args.clone(),
             stderr_callback: self.stderr_callback.clone(),
             permission_handler: self.permission_handler.clone(),
@@ -985,6 +1337,17 @@ impl ClaudeAgentOptions {
             }
         }
 
+        // Partial streaming events
+        if self.include_partial_messages {
+            args.push("--include-partial-messages".to_string());
+        }
+
+        // Structured output schema
+        if let Some(schema) = self.output_format.as_ref().map(json_schema_of) {
+            args.push("--json-schema".to_string());
+            args.push(schema.to_string());
+        }
+
         // Budget cap
         if let Some(max_budget) = self.max_budget_usd {
             args.push("--max-budget-usd".to_string());
@@ -1196,6 +1559,60 @@ impl ClaudeAgentOptionsBuilder {
         self
     }
 
+    /// Register a typed hook callback for an event
+    ///
+    /// The callback is assigned a callback ID and advertised to the CLI in the
+    /// initialize handshake. When the hook fires, the CLI payload is parsed into
+    /// [`HookInput`](crate::hooks::HookInput) and the returned
+    /// [`HookResponse`](crate::hooks::HookResponse) is converted to the CLI's
+    /// hook output format. The matcher's `timeout_ms` bounds the callback.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_claw::prelude::*;
+    /// use async_trait::async_trait;
+    ///
+    /// struct BlockRm;
+    ///
+    /// #[async_trait]
+    /// impl HookCallback for BlockRm {
+    ///     async fn call(
+    ///         &self,
+    ///         input: HookInput,
+    ///         _tool_use_id: Option<&str>,
+    ///         _context: &HookContext,
+    ///     ) -> Result<HookResponse, ClawError> {
+    ///         let cmd = input
+    ///             .tool_input
+    ///             .as_ref()
+    ///             .and_then(|v| v.get("command"))
+    ///             .and_then(|v| v.as_str())
+    ///             .unwrap_or_default();
+    ///         if cmd.contains("rm -rf") {
+    ///             return Ok(HookResponse::deny("Destructive command blocked"));
+    ///         }
+    ///         Ok(HookResponse::default())
+    ///     }
+    /// }
+    ///
+    /// let options = ClaudeAgentOptions::builder()
+    ///     .hook(HookEvent::PreToolUse, HookMatcher::tool("Bash"), BlockRm)
+    ///     .build();
+    /// assert_eq!(options.hook_callbacks.len(), 1);
+    /// ```
+    pub fn hook(
+        mut self,
+        event: HookEvent,
+        matcher: HookMatcher,
+        callback: impl HookCallback + 'static,
+    ) -> Self {
+        self.inner
+            .hook_callbacks
+            .push(HookRegistration::new(event, matcher, callback));
+        self
+    }
+
     /// Set agents
     pub fn agents(mut self, agents: HashMap<String, AgentDefinition>) -> Self {
         self.inner.agents = agents;
@@ -1234,6 +1651,18 @@ impl ClaudeAgentOptionsBuilder {
         self
     }
 
+    /// Enable automatic crash recovery with the given policy
+    pub fn recovery(mut self, policy: RecoveryPolicy) -> Self {
+        self.inner.recovery = Some(policy);
+        self
+    }
+
+    /// Wait for or fail on blocking rate limits before sending a turn
+    pub fn rate_limit_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.inner.rate_limit_policy = Some(policy);
+        self
+    }
+
     /// Set working directory
     pub fn cwd(mut self, path: impl Into<PathBuf>) -> Self {
         self.inner.cwd = Some(path.into());
@@ -1282,6 +1711,37 @@ impl ClaudeAgentOptionsBuilder {
         self
     }
 
+    /// Request structured output matching the JSON schema of `T`
+    ///
+    /// The result arrives in `ResultMessage::Success::structured_output`; use
+    /// [`ResponseStream::structured`](crate::client::ResponseStream::structured)
+    /// or [`query_structured`](crate::query::query_structured) to deserialize it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusty_claw::options::ClaudeAgentOptions;
+    /// use schemars::JsonSchema;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct Summary {
+    ///     title: String,
+    ///     bullet_points: Vec<String>,
+    /// }
+    ///
+    /// let options = ClaudeAgentOptions::builder()
+    ///     .output_schema::<Summary>()
+    ///     .build();
+    /// let format = options.output_format.unwrap();
+    /// assert_eq!(format["type"], "json_schema");
+    /// assert!(format["schema"]["properties"]["bullet_points"].is_object());
+    /// ```
+    pub fn output_schema<T: schemars::JsonSchema>(mut self) -> Self {
+        self.inner.output_format = Some(output_format_for::<T>());
+        self
+    }
+
     /// Enable partial messages in output
     pub fn include_partial_messages(mut self, include: bool) -> Self {
         self.inner.include_partial_messages = include;
@@ -1350,6 +1810,41 @@ impl ClaudeAgentOptionsBuilder {
         self
     }
 
+    /// Set the capacity of each message channel (clamped to at least 1)
+    pub fn channel_capacity(mut self, capacity: usize) -> Self {
+        self.inner.channel.capacity = capacity.max(1);
+        self
+    }
+
+    /// Set what happens when a message channel is full
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.inner.channel.overflow = policy;
+        self
+    }
+
+    /// Set the response deadline for one control request subtype
+    pub fn control_timeout(mut self, subtype: ControlSubtype, timeout: Duration) -> Self {
+        self.inner
+            .control_timeouts
+            .overrides
+            .insert(subtype, timeout);
+        self
+    }
+
+    /// Set the response deadline for control request subtypes without an override
+    pub fn default_control_timeout(mut self, timeout: Duration) -> Self {
+        self.inner.control_timeouts.default = timeout;
+        self
+    }
+
+    /// Set how many incoming control requests are handled at once (clamped to at least 1)
+    ///
+    /// Requests beyond the limit wait for a slot; routing of other messages continues.
+    pub fn max_concurrent_control_requests(mut self, limit: usize) -> Self {
+        self.inner.max_concurrent_control_requests = Some(limit.max(1));
+        self
+    }
+
     /// Set extra CLI args escape hatch
     ///
     /// Each entry `(key, value)` is emitted as `--key value` or `--key` (if `value` is `None`).
@@ -1417,6 +1912,7 @@ mod tests {
         assert!(opts.disallowed_tools.is_empty());
         assert!(opts.mcp_servers.is_empty());
         assert!(opts.hooks.is_empty());
+        assert!(opts.hook_callbacks.is_empty());
         assert!(opts.agents.is_empty());
         assert!(!opts.fork_session);
         assert!(!opts.continue_conversation);
@@ -1425,6 +1921,9 @@ mod tests {
         assert_eq!(opts.max_budget_usd, None);
         assert_eq!(opts.max_thinking_tokens, None);
         assert!(opts.sandbox_settings.is_none());
+        assert!(opts.recovery.is_none());
+        assert_eq!(opts.channel, ChannelConfig::default());
+        assert_eq!(opts.control_timeouts, ControlTimeouts::default());
     }
 
     #[test]
@@ -1614,6 +2113,47 @@ mod tests {
         assert!(args.contains(&"local,project".to_string()));
     }
 
+    #[test]
+    fn test_to_cli_args_include_partial_messages() {
+        let args = ClaudeAgentOptions::builder()
+            .include_partial_messages(true)
+            .build()
+            .to_base_cli_args();
+        assert!(args.contains(&"--include-partial-messages".to_string()));
+
+        let args = ClaudeAgentOptions::default().to_base_cli_args();
+        assert!(!args.contains(&"--include-partial-messages".to_string()));
+    }
+
+    #[test]
+    fn test_to_cli_args_json_schema() {
+        let schema =
+            serde_json::json!({"type": "object", "properties": {"n": {"type": "integer"}}});
+
+        let wrapped = ClaudeAgentOptions::builder()
+            .output_format(serde_json::json!({"type": "json_schema", "schema": schema}))
+            .build()
+            .to_cli_args("test");
+        let pos = wrapped.iter().position(|a| a == "--json-schema").unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&wrapped[pos + 1]).unwrap(),
+            schema
+        );
+
+        let bare = ClaudeAgentOptions::builder()
+            .output_format(schema.clone())
+            .build()
+            .to_cli_args("test");
+        let pos = bare.iter().position(|a| a == "--json-schema").unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&bare[pos + 1]).unwrap(),
+            schema
+        );
+
+        let none = ClaudeAgentOptions::default().to_cli_args("test");
+        assert!(!none.contains(&"--json-schema".to_string()));
+    }
+
     #[test]
     fn test_permission_mode_to_cli_arg() {
         assert_eq!(PermissionMode::Default.to_cli_arg(), "default");
@@ -1625,6 +2165,36 @@ mod tests {
         assert_eq!(PermissionMode::Plan.to_cli_arg(), "plan");
     }
 
+    #[test]
+    fn test_permission_mode_from_cli_arg() {
+        for mode in [
+            PermissionMode::Default,
+            PermissionMode::AcceptEdits,
+            PermissionMode::BypassPermissions,
+            PermissionMode::Plan,
+            PermissionMode::Allow,
+            PermissionMode::Ask,
+            PermissionMode::Deny,
+            PermissionMode::Custom,
+        ] {
+            assert_eq!(PermissionMode::from_cli_arg(mode.to_cli_arg()), Some(mode));
+        }
+        assert_eq!(PermissionMode::from_cli_arg("accept_edits"), None);
+
+        assert_eq!(
+            PermissionMode::from_cli_arg_lossless("dontAsk"),
+            PermissionMode::Other("dontAsk".to_string())
+        );
+        assert_eq!(
+            PermissionMode::from_cli_arg_lossless("plan"),
+            PermissionMode::Plan
+        );
+        assert_eq!(
+            PermissionMode::Other("dontAsk".to_string()).to_cli_arg(),
+            "dontAsk"
+        );
+    }
+
     #[test]
     fn test_default_trait() {
         let opts = ClaudeAgentOptions::default();
@@ -1813,6 +2383,76 @@ mod tests {
     // MCP server config tests (bvo, 9be, xik, yhn)
     // =========================================================================
 
+    #[test]
+    fn test_channel_config_builder() {
+        let opts = ClaudeAgentOptions::builder()
+            .channel_capacity(0)
+            .overflow_policy(OverflowPolicy::Error)
+            .build();
+        assert_eq!(opts.channel.capacity, 1);
+        assert_eq!(opts.channel.overflow, OverflowPolicy::Error);
+        assert_eq!(opts.clone().channel, opts.channel);
+    }
+
+    #[test]
+    fn test_control_timeouts() {
+        let defaults = ControlTimeouts::default();
+        assert_eq!(
+            defaults.for_subtype(ControlSubtype::Initialize),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            defaults.for_subtype(ControlSubtype::Interrupt),
+            Duration::from_secs(10)
+        );
+
+        let opts = ClaudeAgentOptions::builder()
+            .control_timeout(ControlSubtype::SetModel, Duration::from_secs(5))
+            .default_control_timeout(Duration::from_secs(20))
+            .build();
+        let timeouts = opts.clone().control_timeouts;
+        assert_eq!(
+            timeouts.for_subtype(ControlSubtype::SetModel),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            timeouts.for_subtype(ControlSubtype::Interrupt),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            timeouts.for_subtype(ControlSubtype::McpStatus),
+            Duration::from_secs(20)
+        );
+    }
+
+    #[test]
+    fn test_max_concurrent_control_requests() {
+        assert_eq!(
+            ClaudeAgentOptions::default().max_concurrent_control_requests,
+            None
+        );
+        let opts = ClaudeAgentOptions::builder()
+            .max_concurrent_control_requests(0)
+            .build();
+        assert_eq!(opts.max_concurrent_control_requests, Some(1));
+    }
+
+    #[test]
+    fn test_recovery_policy_backoff() {
+        let policy = RecoveryPolicy::new(4)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(350));
+        assert_eq!(policy.max_attempts, 4);
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_for(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff_for(40), Duration::from_millis(350));
+
+        let opts = ClaudeAgentOptions::builder()
+            .recovery(RecoveryPolicy::default())
+            .build();
+        assert_eq!(opts.recovery, Some(RecoveryPolicy::default()));
+    }
+
     #[test]
     fn test_to_mcp_config_json_empty() {
         let opts = ClaudeAgentOptions::default();
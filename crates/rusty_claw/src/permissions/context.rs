@@ -0,0 +1,301 @@
+//! Context and permission rule updates for `can_use_tool` requests.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::control::CancellationToken;
+
+/// Extra information the CLI sends with a `can_use_tool` request
+///
+/// Passed to [`CanUseToolHandler::can_use_tool()`](crate::control::handlers::CanUseToolHandler::can_use_tool)
+/// alongside the tool name and input.
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::permissions::{PermissionUpdate, ToolPermissionContext};
+/// use serde_json::json;
+///
+/// let context: ToolPermissionContext = serde_json::from_value(json!({
+///     "permission_suggestions": [{
+///         "type": "addDirectories",
+///         "directories": ["/home/dev/other-repo"],
+///         "destination": "session"
+///     }],
+///     "blocked_path": "/home/dev/other-repo/README.md",
+///     "tool_use_id": "toolu_01"
+/// }))
+/// .unwrap();
+///
+/// assert_eq!(context.blocked_path.as_deref(), Some("/home/dev/other-repo/README.md"));
+/// assert!(matches!(context.suggestions[0], PermissionUpdate::AddDirectories { .. }));
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolPermissionContext {
+    /// Rule updates the CLI suggests offering the user (e.g., "always allow
+    /// this command in this session")
+    ///
+    /// Return the chosen ones in
+    /// [`PermissionDecision::Allow::updated_permissions`](crate::permissions::PermissionDecision::Allow)
+    /// to apply them. Suggestions of unknown types are skipped.
+    #[serde(
+        rename = "permission_suggestions",
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_suggestions"
+    )]
+    pub suggestions: Vec<PermissionUpdate>,
+
+    /// Path outside the allowed directories that triggered the request, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_path: Option<String>,
+
+    /// ID of the `tool_use` block being checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+
+    /// ID of the subagent making the tool call (`None` for the main agent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+
+    /// Cancelled when the CLI abandons the request or the client closes
+    ///
+    /// The handler's future is dropped at that point; use this token to stop
+    /// work it started elsewhere, e.g. a permission prompt shown to a user.
+    #[serde(skip)]
+    pub cancellation: CancellationToken,
+}
+
+// The cancellation token has no identity worth comparing
+impl PartialEq for ToolPermissionContext {
+    fn eq(&self, other: &Self) -> bool {
+        self.suggestions == other.suggestions
+            && self.blocked_path == other.blocked_path
+            && self.tool_use_id == other.tool_use_id
+            && self.agent_id == other.agent_id
+    }
+}
+
+/// Parse each suggestion on its own so one unknown type does not reject the request
+fn deserialize_suggestions<'de, D>(deserializer: D) -> Result<Vec<PermissionUpdate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Option::<Vec<Value>>::deserialize(deserializer)?.unwrap_or_default();
+    Ok(values
+        .into_iter()
+        .filter_map(|value| match serde_json::from_value(value) {
+            Ok(update) => Some(update),
+            Err(e) => {
+                warn!("Skipping unrecognized permission suggestion: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Change to the CLI's permission rules, applied when a tool use is allowed
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::permissions::{PermissionRuleValue, PermissionUpdate};
+///
+/// // "Always allow `npm test` in this session"
+/// let update = PermissionUpdate::allow_in_session(
+///     PermissionRuleValue::new("Bash").with_content("npm test"),
+/// );
+/// let json = serde_json::to_value(&update).unwrap();
+/// assert_eq!(json["type"], "addRules");
+/// assert_eq!(json["rules"][0]["toolName"], "Bash");
+/// assert_eq!(json["destination"], "session");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PermissionUpdate {
+    /// Add rules with the given behavior
+    AddRules {
+        /// Rules to add
+        rules: Vec<PermissionRuleValue>,
+        /// Whether the rules allow, deny, or ask
+        behavior: PermissionBehavior,
+        /// Where the rules are stored
+        destination: PermissionUpdateDestination,
+    },
+    /// Replace all rules with the given behavior
+    ReplaceRules {
+        /// New rules
+        rules: Vec<PermissionRuleValue>,
+        /// Whether the rules allow, deny, or ask
+        behavior: PermissionBehavior,
+        /// Where the rules are stored
+        destination: PermissionUpdateDestination,
+    },
+    /// Remove rules with the given behavior
+    RemoveRules {
+        /// Rules to remove
+        rules: Vec<PermissionRuleValue>,
+        /// Whether the rules allow, deny, or ask
+        behavior: PermissionBehavior,
+        /// Where the rules are stored
+        destination: PermissionUpdateDestination,
+    },
+    /// Switch the permission mode
+    SetMode {
+        /// CLI permission mode (e.g., `"acceptEdits"`, see
+        /// [`PermissionMode::to_cli_arg()`](crate::options::PermissionMode::to_cli_arg))
+        mode: String,
+        /// Where the mode is stored
+        destination: PermissionUpdateDestination,
+    },
+    /// Allow access to additional directories
+    AddDirectories {
+        /// Directories to add
+        directories: Vec<String>,
+        /// Where the directories are stored
+        destination: PermissionUpdateDestination,
+    },
+    /// Revoke access to directories
+    RemoveDirectories {
+        /// Directories to remove
+        directories: Vec<String>,
+        /// Where the directories are stored
+        destination: PermissionUpdateDestination,
+    },
+}
+
+impl PermissionUpdate {
+    /// Allow tool uses matching `rule` for the rest of the session
+    pub fn allow_in_session(rule: PermissionRuleValue) -> Self {
+        PermissionUpdate::AddRules {
+            rules: vec![rule],
+            behavior: PermissionBehavior::Allow,
+            destination: PermissionUpdateDestination::Session,
+        }
+    }
+
+    /// Allow access to `directory` for the rest of the session
+    pub fn add_directory_in_session(directory: impl Into<String>) -> Self {
+        PermissionUpdate::AddDirectories {
+            directories: vec![directory.into()],
+            destination: PermissionUpdateDestination::Session,
+        }
+    }
+}
+
+/// Permission rule matching a tool, optionally narrowed by content
+///
+/// Corresponds to rules like `Bash(npm test)` in settings files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRuleValue {
+    /// Tool the rule applies to (e.g., `"Bash"`, `"mcp__github__create_issue"`)
+    pub tool_name: String,
+    /// Rule content, e.g. a command prefix for `Bash` or a path glob for `Edit`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_content: Option<String>,
+}
+
+impl PermissionRuleValue {
+    /// Rule matching every use of `tool_name`
+    pub fn new(tool_name: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            rule_content: None,
+        }
+    }
+
+    /// Narrow the rule to matching content
+    pub fn with_content(mut self, content: impl Into<String>) -> Self {
+        self.rule_content = Some(content.into());
+        self
+    }
+}
+
+/// Effect of a permission rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionBehavior {
+    /// Allow matching tool uses without asking
+    Allow,
+    /// Deny matching tool uses
+    Deny,
+    /// Ask before matching tool uses
+    Ask,
+}
+
+/// Where a permission update is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionUpdateDestination {
+    /// `~/.claude/settings.json`
+    UserSettings,
+    /// `.claude/settings.json` in the project
+    ProjectSettings,
+    /// `.claude/settings.local.json` in the project
+    LocalSettings,
+    /// Current session only
+    Session,
+    /// Command-line arguments of the current process
+    CliArg,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_permission_update_wire_format() {
+        let update = PermissionUpdate::SetMode {
+            mode: "acceptEdits".to_string(),
+            destination: PermissionUpdateDestination::LocalSettings,
+        };
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            json!({"type": "setMode", "mode": "acceptEdits", "destination": "localSettings"})
+        );
+
+        let update = PermissionUpdate::add_directory_in_session("/tmp/work");
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            json!({"type": "addDirectories", "directories": ["/tmp/work"], "destination": "session"})
+        );
+    }
+
+    #[test]
+    fn test_context_skips_unknown_suggestions() {
+        let context: ToolPermissionContext = serde_json::from_value(json!({
+            "permission_suggestions": [
+                {"type": "futureUpdate", "whatever": 1},
+                {
+                    "type": "addRules",
+                    "rules": [{"toolName": "Bash", "ruleContent": "npm test"}],
+                    "behavior": "allow",
+                    "destination": "session"
+                }
+            ],
+            "agent_id": "agent_1"
+        }))
+        .unwrap();
+        assert_eq!(
+            context.suggestions,
+            vec![PermissionUpdate::allow_in_session(
+                PermissionRuleValue::new("Bash").with_content("npm test")
+            )]
+        );
+        assert_eq!(context.agent_id.as_deref(), Some("agent_1"));
+        assert_eq!(context.tool_use_id, None);
+    }
+
+    #[test]
+    fn test_context_defaults_when_absent() {
+        let context: ToolPermissionContext = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(context, ToolPermissionContext::default());
+
+        let context: ToolPermissionContext =
+            serde_json::from_value(json!({"permission_suggestions": null})).unwrap();
+        assert!(context.suggestions.is_empty());
+    }
+}
@@ -3,7 +3,7 @@
 use crate::control::handlers::CanUseToolHandler;
 use crate::error::ClawError;
 use crate::options::PermissionMode;
-use crate::permissions::PermissionDecision;
+use crate::permissions::{PermissionDecision, ToolPermissionContext};
 use async_trait::async_trait;
 use serde_json::Value;
 
@@ -60,6 +60,7 @@ impl DefaultPermissionHandler {
             PermissionMode::Deny => false,
             PermissionMode::Ask => false, // Default to deny, CLI should prompt
             PermissionMode::Custom => false, // Require hook, deny if no hook
+            PermissionMode::Other(_) => false, // Unknown mode, fail closed
             // Legacy modes default to allow for backward compatibility
             PermissionMode::Default
             | PermissionMode::AcceptEdits
@@ -75,26 +76,30 @@ impl CanUseToolHandler for DefaultPermissionHandler {
         &self,
         tool_name: &str,
         _tool_input: &Value,
+        _context: &ToolPermissionContext,
     ) -> Result<PermissionDecision, ClawError> {
         // 1. Explicit deny list has highest priority
         if self.is_denied(tool_name) {
-            return Ok(PermissionDecision::Deny { interrupt: false });
+            return Ok(PermissionDecision::deny(format!(
+                "Tool '{}' is disallowed by the permission policy",
+                tool_name
+            )));
         }
 
         // 2. Explicit allow list (only applies when non-empty)
         if !self.allowed_tools.is_empty() && self.is_allowed(tool_name) {
-            return Ok(PermissionDecision::Allow {
-                updated_input: None,
-            });
+            return Ok(PermissionDecision::allow());
         }
 
         // 3. Fall back to default policy (covers: tool not in allowlist, or allowlist empty)
         if self.default_policy() {
-            Ok(PermissionDecision::Allow {
-                updated_input: None,
-            })
+            Ok(PermissionDecision::allow())
         } else {
-            Ok(PermissionDecision::Deny { interrupt: false })
+            Ok(PermissionDecision::deny(format!(
+                "Tool '{}' is not allowed in {} mode",
+                tool_name,
+                self.mode.to_cli_arg()
+            )))
         }
     }
 }
@@ -171,13 +176,22 @@ mod tests {
             .build();
 
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_allowed(
-            handler.can_use_tool("read", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("read", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_allowed(
-            handler.can_use_tool("write", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("write", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -188,13 +202,22 @@ mod tests {
             .build();
 
         assert!(is_denied(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_denied(
-            handler.can_use_tool("read", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("read", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_denied(
-            handler.can_use_tool("write", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("write", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -206,13 +229,22 @@ mod tests {
             .build();
 
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_allowed(
-            handler.can_use_tool("read", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("read", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_denied(
-            handler.can_use_tool("write", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("write", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -224,13 +256,22 @@ mod tests {
             .build();
 
         assert!(is_denied(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_allowed(
-            handler.can_use_tool("read", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("read", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_denied(
-            handler.can_use_tool("write", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("write", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -243,7 +284,10 @@ mod tests {
             .build();
 
         assert!(is_denied(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -255,7 +299,10 @@ mod tests {
 
         // Ask mode should default to deny, expecting CLI to prompt
         assert!(is_denied(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -267,7 +314,10 @@ mod tests {
 
         // Custom mode should default to deny, expecting hooks
         assert!(is_denied(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -278,7 +328,10 @@ mod tests {
             .build();
 
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
 
         let handler = DefaultPermissionHandler::builder()
@@ -286,7 +339,10 @@ mod tests {
             .build();
 
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -299,7 +355,10 @@ mod tests {
             .build();
 
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
 
         let handler = DefaultPermissionHandler::builder()
@@ -309,7 +368,10 @@ mod tests {
             .build();
 
         assert!(is_denied(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -321,11 +383,17 @@ mod tests {
             .build();
 
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         // Tools not in allowlist should follow default policy
         assert!(is_allowed(
-            handler.can_use_tool("read", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("read", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -335,7 +403,10 @@ mod tests {
 
         // Should use Default mode with empty lists
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -347,10 +418,16 @@ mod tests {
 
         // Legacy modes should allow all tools
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_allowed(
-            handler.can_use_tool("write", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("write", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -362,7 +439,10 @@ mod tests {
 
         // Plan mode should allow all tools (legacy behavior)
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -380,20 +460,32 @@ mod tests {
 
         // bash and read are in allowlist
         assert!(is_allowed(
-            handler.can_use_tool("bash", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("bash", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_allowed(
-            handler.can_use_tool("read", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("read", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
 
         // write is in both allowlist and denylist - deny wins
         assert!(is_denied(
-            handler.can_use_tool("write", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("write", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
 
         // grep not in allowlist, but not denied - follows default policy
         assert!(is_denied(
-            handler.can_use_tool("grep", &Value::Null).await.unwrap()
+            handler
+                .can_use_tool("grep", &Value::Null, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -410,7 +502,10 @@ mod tests {
         });
 
         assert!(is_allowed(
-            handler.can_use_tool("bash", &complex_input).await.unwrap()
+            handler
+                .can_use_tool("bash", &complex_input, &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -429,24 +524,42 @@ mod tests {
 
         // Read operations allowed
         assert!(is_allowed(
-            handler.can_use_tool("read", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("read", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_allowed(
-            handler.can_use_tool("glob", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("glob", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_allowed(
-            handler.can_use_tool("grep", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("grep", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
 
         // Write operations denied
         assert!(is_denied(
-            handler.can_use_tool("write", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("write", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_denied(
-            handler.can_use_tool("edit", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("edit", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_denied(
-            handler.can_use_tool("bash", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("bash", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -464,21 +577,36 @@ mod tests {
 
         // Safe tools allowed
         assert!(is_allowed(
-            handler.can_use_tool("read", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("read", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_allowed(
-            handler.can_use_tool("grep", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("grep", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
 
         // Dangerous tools denied
         assert!(is_denied(
-            handler.can_use_tool("bash", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("bash", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_denied(
-            handler.can_use_tool("write", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("write", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
         assert!(is_denied(
-            handler.can_use_tool("delete", &json!({})).await.unwrap()
+            handler
+                .can_use_tool("delete", &json!({}), &ToolPermissionContext::default())
+                .await
+                .unwrap()
         ));
     }
 
@@ -491,7 +619,9 @@ mod tests {
                 .build(),
         );
 
-        let result = handler.can_use_tool("bash", &json!({})).await;
+        let result = handler
+            .can_use_tool("bash", &json!({}), &ToolPermissionContext::default())
+            .await;
         assert!(result.is_ok());
         assert!(result.unwrap().is_allowed());
     }
@@ -502,7 +632,10 @@ mod tests {
         let handler = DefaultPermissionHandler::builder()
             .mode(PermissionMode::Allow)
             .build();
-        let decision = handler.can_use_tool("bash", &json!({})).await.unwrap();
+        let decision = handler
+            .can_use_tool("bash", &json!({}), &ToolPermissionContext::default())
+            .await
+            .unwrap();
         assert!(decision.updated_input().is_none());
     }
 
@@ -511,9 +644,17 @@ mod tests {
         let handler = DefaultPermissionHandler::builder()
             .mode(PermissionMode::Deny)
             .build();
-        let decision = handler.can_use_tool("bash", &json!({})).await.unwrap();
+        let decision = handler
+            .can_use_tool("bash", &json!({}), &ToolPermissionContext::default())
+            .await
+            .unwrap();
         match decision {
-            PermissionDecision::Deny { interrupt } => assert!(!interrupt),
+            PermissionDecision::Deny {
+                message, interrupt, ..
+            } => {
+                assert!(!interrupt);
+                assert_eq!(message, "Tool 'bash' is not allowed in deny mode");
+            }
             PermissionDecision::Allow { .. } => panic!("Expected Deny"),
         }
     }
@@ -26,10 +26,14 @@
 //!
 //! # Permission Result
 //!
+//! Handlers receive a [`ToolPermissionContext`](crate::permissions::ToolPermissionContext)
+//! with the CLI's rule suggestions, blocked path, tool use ID, and agent ID.
 //! Handlers return a rich [`PermissionDecision`](crate::permissions::PermissionDecision) enum rather than a simple bool:
 //!
 //! - [`PermissionDecision::Allow`](crate::permissions::PermissionDecision::Allow) - Allow the tool, optionally with a modified input
-//! - [`PermissionDecision::Deny`](crate::permissions::PermissionDecision::Deny) - Deny the tool, optionally interrupting the session
+//!   and [`PermissionUpdate`](crate::permissions::PermissionUpdate)s to the CLI's rules
+//! - [`PermissionDecision::Deny`](crate::permissions::PermissionDecision::Deny) - Deny the tool with a message for the model,
+//!   optionally interrupting the session
 //!
 //! # Examples
 //!
@@ -63,7 +67,7 @@
 //!
 //! ```rust
 //! use rusty_claw::control::handlers::CanUseToolHandler;
-//! use rusty_claw::permissions::PermissionDecision;
+//! use rusty_claw::permissions::{PermissionDecision, ToolPermissionContext};
 //! use rusty_claw::error::ClawError;
 //! use async_trait::async_trait;
 //! use serde_json::{json, Value};
@@ -76,21 +80,32 @@
 //!         &self,
 //!         tool_name: &str,
 //!         tool_input: &Value,
+//!         _context: &ToolPermissionContext,
 //!     ) -> Result<PermissionDecision, ClawError> {
 //!         if tool_name == "Bash" {
 //!             // Strip dangerous flags and return sanitized input
 //!             let safe_input = json!({ "command": "echo 'sanitized'" });
-//!             return Ok(PermissionDecision::Allow { updated_input: Some(safe_input) });
+//!             return Ok(PermissionDecision::Allow {
+//!                 updated_input: Some(safe_input),
+//!                 updated_permissions: vec![],
+//!             });
 //!         }
-//!         Ok(PermissionDecision::Allow { updated_input: None })
+//!         Ok(PermissionDecision::allow())
 //!     }
 //! }
 //! ```
 
+mod context;
 mod handler;
 
+pub use context::{
+    PermissionBehavior, PermissionRuleValue, PermissionUpdate, PermissionUpdateDestination,
+    ToolPermissionContext,
+};
 pub use handler::{DefaultPermissionHandler, DefaultPermissionHandlerBuilder};
 
+use crate::options::PermissionMode;
+
 /// Rich result type for permission decisions.
 ///
 /// Replaces the previous `bool` return from `CanUseToolHandler::can_use_tool()`.
@@ -98,27 +113,41 @@ pub use handler::{DefaultPermissionHandler, DefaultPermissionHandlerBuilder};
 ///
 /// - [`PermissionDecision::Allow`]: Allow tool execution, optionally replacing
 ///   the tool input with a sanitized or modified version.
-/// - [`PermissionDecision::Deny`]: Deny tool execution, optionally interrupting
-///   the entire session (not just the current tool use).
+/// - [`PermissionDecision::Deny`]: Deny tool execution with a message for the
+///   model, optionally interrupting the entire session (not just the current tool use).
+///
+/// Both variants can carry [`PermissionUpdate`]s, including a permission mode
+/// change via [`with_mode()`](Self::with_mode).
 ///
 /// # Examples
 ///
 /// ```rust
+/// use rusty_claw::options::PermissionMode;
 /// use rusty_claw::permissions::PermissionDecision;
 /// use serde_json::json;
 ///
 /// // Simple allow
-/// let decision = PermissionDecision::Allow { updated_input: None };
+/// let decision = PermissionDecision::allow();
 ///
 /// // Allow with sanitized input (strips dangerous args)
 /// let safe_input = json!({ "command": "echo safe" });
-/// let decision = PermissionDecision::Allow { updated_input: Some(safe_input) };
+/// let decision = PermissionDecision::Allow {
+///     updated_input: Some(safe_input),
+///     updated_permissions: vec![],
+/// };
+///
+/// // Allow and stop asking about edits for the rest of the session
+/// let decision = PermissionDecision::allow().with_mode(PermissionMode::AcceptEdits);
 ///
-/// // Simple deny
-/// let decision = PermissionDecision::Deny { interrupt: false };
+/// // Deny, telling the model why so it does not retry variations
+/// let decision = PermissionDecision::deny("Network access is disabled in this sandbox");
 ///
 /// // Deny and interrupt the session entirely
-/// let decision = PermissionDecision::Deny { interrupt: true };
+/// let decision = PermissionDecision::Deny {
+///     message: "Refusing to touch production credentials".to_string(),
+///     interrupt: true,
+///     updated_permissions: vec![],
+/// };
 /// ```
 #[derive(Debug, Clone)]
 pub enum PermissionDecision {
@@ -134,6 +163,12 @@ pub enum PermissionDecision {
         /// When `Some`, the CLI uses this value instead of the original input.
         /// When `None`, the original input is used unchanged.
         updated_input: Option<serde_json::Value>,
+
+        /// Permission rule updates to apply, e.g. one of the
+        /// [`ToolPermissionContext::suggestions`] the user chose
+        ///
+        /// Lets the user answer "always allow" instead of being asked again.
+        updated_permissions: Vec<PermissionUpdate>,
     },
 
     /// Deny tool execution.
@@ -142,15 +177,61 @@ pub enum PermissionDecision {
     /// not just the current tool use. This is appropriate when a dangerous
     /// operation is detected that should halt all further processing.
     Deny {
+        /// Explanation shown to the model as the tool result
+        message: String,
+
         /// Whether to interrupt the entire session.
         ///
         /// - `false`: Deny this tool use only; the agent may continue.
         /// - `true`: Interrupt the entire session immediately.
         interrupt: bool,
+
+        /// Permission rule updates to apply, e.g. "always deny" rules
+        updated_permissions: Vec<PermissionUpdate>,
     },
 }
 
 impl PermissionDecision {
+    /// Allow the tool with its original input.
+    pub fn allow() -> Self {
+        PermissionDecision::Allow {
+            updated_input: None,
+            updated_permissions: Vec::new(),
+        }
+    }
+
+    /// Deny the tool use, explaining why to the model.
+    pub fn deny(message: impl Into<String>) -> Self {
+        PermissionDecision::Deny {
+            message: message.into(),
+            interrupt: false,
+            updated_permissions: Vec::new(),
+        }
+    }
+
+    /// Attach a permission rule update.
+    pub fn with_update(mut self, update: PermissionUpdate) -> Self {
+        match &mut self {
+            PermissionDecision::Allow {
+                updated_permissions,
+                ..
+            }
+            | PermissionDecision::Deny {
+                updated_permissions,
+                ..
+            } => updated_permissions.push(update),
+        }
+        self
+    }
+
+    /// Switch the session's permission mode along with this decision.
+    pub fn with_mode(self, mode: PermissionMode) -> Self {
+        self.with_update(PermissionUpdate::SetMode {
+            mode: mode.to_cli_arg().to_string(),
+            destination: PermissionUpdateDestination::Session,
+        })
+    }
+
     /// Returns `true` if this decision allows the tool to execute.
     pub fn is_allowed(&self) -> bool {
         matches!(self, PermissionDecision::Allow { .. })
@@ -164,8 +245,110 @@ impl PermissionDecision {
     /// Returns the updated input if this is an `Allow` decision with one.
     pub fn updated_input(&self) -> Option<&serde_json::Value> {
         match self {
-            PermissionDecision::Allow { updated_input } => updated_input.as_ref(),
+            PermissionDecision::Allow { updated_input, .. } => updated_input.as_ref(),
             PermissionDecision::Deny { .. } => None,
         }
     }
+
+    /// Returns the denial message if this is a `Deny` decision.
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            PermissionDecision::Allow { .. } => None,
+            PermissionDecision::Deny { message, .. } => Some(message),
+        }
+    }
+
+    /// Returns the permission updates attached to this decision.
+    pub fn updated_permissions(&self) -> &[PermissionUpdate] {
+        match self {
+            PermissionDecision::Allow {
+                updated_permissions,
+                ..
+            }
+            | PermissionDecision::Deny {
+                updated_permissions,
+                ..
+            } => updated_permissions,
+        }
+    }
+
+    /// Build the `can_use_tool` response payload the CLI expects
+    ///
+    /// `tool_input` is the original input, echoed back as `updatedInput` when
+    /// the decision does not replace it.
+    pub(crate) fn into_response(self, tool_input: serde_json::Value) -> serde_json::Value {
+        use serde_json::json;
+
+        let (mut data, updated_permissions) = match self {
+            PermissionDecision::Allow {
+                updated_input,
+                updated_permissions,
+            } => (
+                json!({
+                    "behavior": "allow",
+                    "updatedInput": updated_input.unwrap_or(tool_input),
+                }),
+                updated_permissions,
+            ),
+            PermissionDecision::Deny {
+                message,
+                interrupt,
+                updated_permissions,
+            } => (
+                json!({
+                    "behavior": "deny",
+                    "message": message,
+                    "interrupt": interrupt,
+                }),
+                updated_permissions,
+            ),
+        };
+        if !updated_permissions.is_empty() {
+            data["updatedPermissions"] = json!(updated_permissions);
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_allow_response_echoes_original_input() {
+        let data = PermissionDecision::allow().into_response(json!({"command": "ls"}));
+        assert_eq!(
+            data,
+            json!({"behavior": "allow", "updatedInput": {"command": "ls"}})
+        );
+
+        let data = PermissionDecision::Allow {
+            updated_input: Some(json!({"command": "ls -la"})),
+            updated_permissions: vec![],
+        }
+        .into_response(json!({"command": "ls"}));
+        assert_eq!(data["updatedInput"]["command"], "ls -la");
+    }
+
+    #[test]
+    fn test_deny_response_with_message_and_mode() {
+        let decision =
+            PermissionDecision::deny("Use the read-only replica").with_mode(PermissionMode::Plan);
+        assert_eq!(decision.message(), Some("Use the read-only replica"));
+        assert_eq!(decision.updated_permissions().len(), 1);
+
+        let data = decision.into_response(json!({}));
+        assert_eq!(
+            data,
+            json!({
+                "behavior": "deny",
+                "message": "Use the read-only replica",
+                "interrupt": false,
+                "updatedPermissions": [
+                    {"type": "setMode", "mode": "plan", "destination": "session"}
+                ]
+            })
+        );
+    }
 }
@@ -0,0 +1,842 @@
+//! Pool of pre-connected [`ClaudeClient`]s
+//!
+//! Connecting a `ClaudeClient` spawns the CLI, validates its version, and runs
+//! the initialize handshake, which takes seconds. `ClaudeClientPool` pays that
+//! cost up front: it keeps up to `size` clients connected from a template
+//! [`ClaudeAgentOptions`] and hands them out with [`checkout()`](ClaudeClientPool::checkout).
+//!
+//! - **Checkout / checkin** - A [`PooledClient`] dereferences to `ClaudeClient`
+//!   and returns to the pool when checked in or dropped
+//! - **Idle eviction** - Clients idle longer than [`PoolConfig::idle_timeout`]
+//!   are closed by a background task and on checkout, then reconnected until
+//!   [`PoolConfig::min_idle`] clients are warm again
+//! - **Health checks** - Idle clients are probed with
+//!   [`get_server_info()`](ClaudeClient::get_server_info) before checkout
+//! - **Recycling** - Checked-in clients are closed and replaced with a fresh
+//!   connection in the background
+//!
+//! Every checkout starts a fresh session, so one caller's conversation never
+//! reaches the next. With [`PoolConfig::with_session_reuse`] a checked-in
+//! client keeps its session instead, and the next caller continues the same
+//! conversation; use [`max_turns`](PoolConfig::max_turns) to bound how much
+//! history it accumulates.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rusty_claw::prelude::*;
+//! use rusty_claw::pool::{ClaudeClientPool, PoolConfig};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let template = ClaudeAgentOptions::builder()
+//!     .permission_mode(PermissionMode::AcceptEdits)
+//!     .build();
+//! let pool = ClaudeClientPool::connect(template, PoolConfig::new(4)).await?;
+//!
+//! let client = pool.checkout().await?;
+//! let messages = client.send_message("Hello").await?.receive_response().await?;
+//! println!("{} messages", messages.len());
+//! pool.checkin(client);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::client::ClaudeClient;
+use crate::error::ClawError;
+use crate::options::ClaudeAgentOptions;
+
+/// Sizing and lifecycle settings for a [`ClaudeClientPool`]
+///
+/// # Example
+///
+/// ```
+/// use rusty_claw::pool::PoolConfig;
+/// use std::time::Duration;
+///
+/// let config = PoolConfig::new(8)
+///     .with_idle_timeout(Duration::from_secs(60))
+///     .with_min_idle(2)
+///     .with_session_reuse(true)
+///     .with_max_turns(50);
+/// assert_eq!(config.size, 8);
+/// assert_eq!(config.min_idle, Some(2));
+/// assert_eq!(config.max_turns, Some(50));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Number of clients connected up front, and the maximum checked out at
+    /// once; idle plus checked-out clients never exceed it
+    pub size: usize,
+    /// Close clients that sat idle longer than this (`None` = never)
+    pub idle_timeout: Option<Duration>,
+    /// Idle clients to keep connected after eviction (`None` = `size`)
+    pub min_idle: Option<usize>,
+    /// Return checked-in clients with their session instead of replacing them
+    pub reuse_sessions: bool,
+    /// With session reuse, recycle a client once its session completed this
+    /// many turns (`None` = never)
+    pub max_turns: Option<u32>,
+    /// Probe idle clients with `get_server_info()` before checkout and
+    /// recycle disconnected clients on checkin
+    pub health_check: bool,
+}
+
+impl PoolConfig {
+    /// Create a config for `size` clients (at least 1) with default lifecycle settings
+    pub fn new(size: usize) -> Self {
+        Self {
+            size: size.max(1),
+            ..Default::default()
+        }
+    }
+
+    /// Set how long a client may sit idle before it is closed
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Keep at least `count` clients idle, reconnecting evicted ones
+    ///
+    /// Lower this to let an unused pool shrink after the idle timeout.
+    pub fn with_min_idle(mut self, count: usize) -> Self {
+        self.min_idle = Some(count);
+        self
+    }
+
+    /// Let the next caller continue a checked-in client's session
+    ///
+    /// Only enable this when every caller may see the previous callers'
+    /// conversation.
+    pub fn with_session_reuse(mut self, enabled: bool) -> Self {
+        self.reuse_sessions = enabled;
+        self
+    }
+
+    /// Recycle clients after `turns` completed turns (with session reuse)
+    pub fn with_max_turns(mut self, turns: u32) -> Self {
+        self.max_turns = Some(turns);
+        self
+    }
+
+    /// Enable or disable health checks
+    pub fn with_health_check(mut self, enabled: bool) -> Self {
+        self.health_check = enabled;
+        self
+    }
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            size: 4,
+            idle_timeout: Some(Duration::from_secs(300)),
+            min_idle: None,
+            reuse_sessions: false,
+            max_turns: None,
+            health_check: true,
+        }
+    }
+}
+
+type ConnectFuture = Pin<Box<dyn Future<Output = Result<ClaudeClient, ClawError>> + Send>>;
+
+/// Creates a connected client from the template options
+type Connector = Arc<dyn Fn(ClaudeAgentOptions) -> ConnectFuture + Send + Sync>;
+
+fn connect_client(options: ClaudeAgentOptions) -> ConnectFuture {
+    Box::pin(async move {
+        let mut client = ClaudeClient::new(options)?;
+        client.connect().await?;
+        Ok(client)
+    })
+}
+
+struct IdleClient {
+    client: ClaudeClient,
+    since: Instant,
+}
+
+struct PoolInner {
+    template: ClaudeAgentOptions,
+    config: PoolConfig,
+    connector: Connector,
+    idle: std::sync::Mutex<VecDeque<IdleClient>>,
+    /// One permit per client that may be checked out at a time
+    permits: Arc<Semaphore>,
+    /// Clients currently held by a `PooledClient`
+    checked_out: AtomicUsize,
+    /// Background connects started by `replenish()` that are not idle yet
+    replenishing: AtomicUsize,
+    /// Notified when a client is added to the idle queue or a background
+    /// connect finishes
+    ready: Notify,
+    closed: AtomicBool,
+}
+
+impl PoolInner {
+    fn idle(&self) -> std::sync::MutexGuard<'_, VecDeque<IdleClient>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn is_expired(&self, idle: &IdleClient) -> bool {
+        self.config
+            .idle_timeout
+            .is_some_and(|timeout| idle.since.elapsed() >= timeout)
+    }
+
+    async fn is_healthy(&self, client: &ClaudeClient) -> bool {
+        if !self.config.health_check {
+            return true;
+        }
+        if !client.is_connected() {
+            return false;
+        }
+        match client.get_server_info().await {
+            Ok(_) => true,
+            Err(e) => {
+                debug!("Pooled client failed health check: {}", e);
+                false
+            }
+        }
+    }
+
+    fn min_idle(&self) -> usize {
+        self.config
+            .min_idle
+            .map_or(self.config.size, |count| count.min(self.config.size))
+    }
+
+    fn should_recycle(&self, client: &ClaudeClient) -> bool {
+        if !self.config.reuse_sessions {
+            return true;
+        }
+        let worn_out = self
+            .config
+            .max_turns
+            .is_some_and(|max| client.session().turn_count >= max);
+        worn_out || (self.config.health_check && !client.is_connected())
+    }
+
+    /// Take a checked-out client back and release its permit
+    fn restore(self: &Arc<Self>, client: ClaudeClient, permit: OwnedSemaphorePermit) {
+        if self.closed.load(Ordering::SeqCst) || self.should_recycle(&client) {
+            self.recycle(client, permit);
+            return;
+        }
+        self.idle().push_back(IdleClient {
+            client,
+            since: Instant::now(),
+        });
+        self.checked_out.fetch_sub(1, Ordering::SeqCst);
+        self.ready.notify_waiters();
+        drop(permit);
+    }
+
+    /// Close a checked-out client and connect its replacement in the background
+    ///
+    /// The permit is released right away: the next checkout takes the
+    /// replacement once it is idle, or connects its own if none is on the way.
+    fn recycle(self: &Arc<Self>, client: ClaudeClient, permit: OwnedSemaphorePermit) {
+        retire_in_background(client);
+        self.checked_out.fetch_sub(1, Ordering::SeqCst);
+        self.replenish();
+        drop(permit);
+    }
+
+    /// Connect a fresh client and add it to the idle queue
+    ///
+    /// The client is closed instead if the pool was closed or idle plus
+    /// checked-out clients already reached `size`.
+    async fn connect_idle(&self) -> Result<(), ClawError> {
+        let fresh = (self.connector)(self.template.clone()).await?;
+        let surplus = {
+            let mut idle = self.idle();
+            let full = idle.len() + self.checked_out.load(Ordering::SeqCst) >= self.config.size;
+            if self.closed.load(Ordering::SeqCst) || full {
+                Some(fresh)
+            } else {
+                idle.push_back(IdleClient {
+                    client: fresh,
+                    since: Instant::now(),
+                });
+                None
+            }
+        };
+        if let Some(fresh) = surplus {
+            retire(fresh).await;
+        }
+        Ok(())
+    }
+
+    /// Reconnect clients in the background until `min_idle` are idle or
+    /// connecting, without idle plus checked-out clients exceeding `size`
+    fn replenish(self: &Arc<Self>) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("No Tokio runtime, cannot replenish pool clients");
+            return;
+        };
+        while !self.closed.load(Ordering::SeqCst) {
+            {
+                let idle = self.idle();
+                let warm = idle.len() + self.replenishing.load(Ordering::SeqCst);
+                let live = warm + self.checked_out.load(Ordering::SeqCst);
+                if warm >= self.min_idle() || live >= self.config.size {
+                    return;
+                }
+                self.replenishing.fetch_add(1, Ordering::SeqCst);
+            }
+            let inner = self.clone();
+            runtime.spawn(async move {
+                if let Err(e) = inner.connect_idle().await {
+                    warn!("Failed to replenish pool client: {}", e);
+                }
+                inner.replenishing.fetch_sub(1, Ordering::SeqCst);
+                inner.ready.notify_waiters();
+            });
+        }
+    }
+
+    /// Close idle clients past their idle timeout and reconnect up to `min_idle`
+    async fn evict_expired(self: &Arc<Self>) -> usize {
+        let expired: VecDeque<IdleClient> = {
+            let mut idle = self.idle();
+            let (expired, fresh) = idle.drain(..).partition(|c| self.is_expired(c));
+            *idle = fresh;
+            expired
+        };
+        let count = expired.len();
+        for idle in expired {
+            retire(idle.client).await;
+        }
+        if count > 0 {
+            self.replenish();
+        }
+        count
+    }
+}
+
+async fn retire(mut client: ClaudeClient) {
+    if let Err(e) = client.close().await {
+        debug!("Error closing pooled client: {}", e);
+    }
+}
+
+fn retire_in_background(client: ClaudeClient) {
+    match tokio::runtime::Handle::try_current() {
+        Ok(runtime) => {
+            runtime.spawn(retire(client));
+        }
+        // Dropping the client still kills the CLI (kill_on_drop)
+        Err(_) => warn!("No Tokio runtime, dropping pooled client without closing it"),
+    }
+}
+
+/// A pool of pre-connected [`ClaudeClient`]s built from a template
+///
+/// Cloning the pool is cheap; clones share the same clients. See the
+/// [module documentation](self) for the lifecycle.
+#[derive(Clone)]
+pub struct ClaudeClientPool {
+    inner: Arc<PoolInner>,
+}
+
+impl ClaudeClientPool {
+    /// Connect `config.size` clients from `template` and build the pool
+    ///
+    /// Clients connect concurrently. If any connection fails, the clients that
+    /// did connect are closed and the error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Any error from [`ClaudeClient::connect`], e.g. `ClawError::CliNotFound`
+    /// or `ClawError::InvalidCliVersion`.
+    pub async fn connect(
+        template: ClaudeAgentOptions,
+        config: PoolConfig,
+    ) -> Result<Self, ClawError> {
+        Self::with_connector(template, config, Arc::new(connect_client))
+            .warm()
+            .await
+    }
+
+    fn with_connector(
+        template: ClaudeAgentOptions,
+        config: PoolConfig,
+        connector: Connector,
+    ) -> Self {
+        let permits = Arc::new(Semaphore::new(config.size));
+        Self {
+            inner: Arc::new(PoolInner {
+                template,
+                config,
+                connector,
+                idle: std::sync::Mutex::new(VecDeque::new()),
+                permits,
+                checked_out: AtomicUsize::new(0),
+                replenishing: AtomicUsize::new(0),
+                ready: Notify::new(),
+                closed: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Connect every client up front and start the idle reaper
+    async fn warm(self) -> Result<Self, ClawError> {
+        let mut connecting = JoinSet::new();
+        for _ in 0..self.inner.config.size {
+            connecting.spawn((self.inner.connector)(self.inner.template.clone()));
+        }
+
+        let mut clients = Vec::with_capacity(self.inner.config.size);
+        let mut failure = None;
+        while let Some(joined) = connecting.join_next().await {
+            match joined {
+                Ok(Ok(client)) => clients.push(client),
+                Ok(Err(e)) => failure = failure.or(Some(e)),
+                Err(e) => {
+                    failure = failure.or(Some(ClawError::Connection(format!(
+                        "Pool connect task failed: {}",
+                        e
+                    ))))
+                }
+            }
+        }
+        if let Some(e) = failure {
+            for client in clients {
+                retire(client).await;
+            }
+            return Err(e);
+        }
+
+        let now = Instant::now();
+        self.inner.idle().extend(
+            clients
+                .into_iter()
+                .map(|client| IdleClient { client, since: now }),
+        );
+
+        if let Some(timeout) = self.inner.config.idle_timeout {
+            spawn_reaper(Arc::downgrade(&self.inner), timeout);
+        }
+        Ok(self)
+    }
+
+    /// Check out a client, waiting while `size` clients are already checked out
+    ///
+    /// Idle clients are tried first, discarding any that expired or fail the
+    /// health check; discarded clients are reconnected in the background.
+    /// When none are left, waits for a replacement that is already connecting,
+    /// or connects a new client if there is none (or idle clients just failed).
+    ///
+    /// # Errors
+    ///
+    /// - `ClawError::Connection` - The pool was closed
+    /// - Any error from [`ClaudeClient::connect`] when a new client is needed
+    pub async fn checkout(&self) -> Result<PooledClient, ClawError> {
+        let closed = || ClawError::Connection("Client pool is closed".to_string());
+        let permit = self
+            .inner
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| closed())?;
+
+        let mut discarded = false;
+        loop {
+            if self.inner.closed.load(Ordering::SeqCst) {
+                return Err(closed());
+            }
+            // Created before looking, so a replacement that lands in between still wakes us
+            let ready = self.inner.ready.notified();
+            let next = self.inner.idle().pop_front();
+            let Some(idle) = next else {
+                if discarded || self.inner.replenishing.load(Ordering::SeqCst) == 0 {
+                    break;
+                }
+                ready.await;
+                continue;
+            };
+            if self.inner.is_expired(&idle) || !self.inner.is_healthy(&idle.client).await {
+                retire(idle.client).await;
+                discarded = true;
+                continue;
+            }
+            let client = PooledClient::new(idle.client, permit, self.inner.clone());
+            if discarded {
+                self.inner.replenish();
+            }
+            return Ok(client);
+        }
+
+        let client = (self.inner.connector)(self.inner.template.clone())
+            .await
+            .map(|client| PooledClient::new(client, permit, self.inner.clone()));
+        // Replenish once this checkout holds its client, so it is not counted as idle room
+        if discarded {
+            self.inner.replenish();
+        }
+        client
+    }
+
+    /// Return a client to the pool
+    ///
+    /// Equivalent to dropping the [`PooledClient`]. The client is closed and
+    /// replaced in the background, so the next caller gets a fresh session.
+    /// With session reuse it goes back to the idle queue instead, unless it
+    /// reached `max_turns` or lost its CLI; drain the last `ResponseStream`
+    /// first, since a turn still in flight would leak into the next caller's
+    /// stream.
+    pub fn checkin(&self, client: PooledClient) {
+        drop(client);
+    }
+
+    /// Close idle clients past the idle timeout, returning how many were closed
+    ///
+    /// Runs automatically in the background when an idle timeout is configured.
+    pub async fn evict_idle(&self) -> usize {
+        self.inner.evict_expired().await
+    }
+
+    /// Number of connected clients waiting to be checked out
+    pub fn idle_count(&self) -> usize {
+        self.inner.idle().len()
+    }
+
+    /// Number of clients that can be checked out without waiting
+    pub fn available(&self) -> usize {
+        self.inner.permits.available_permits()
+    }
+
+    /// Close every idle client and reject further checkouts
+    ///
+    /// Clients still checked out are closed when they are returned.
+    pub async fn close(&self) {
+        self.inner.closed.store(true, Ordering::SeqCst);
+        self.inner.permits.close();
+        self.inner.ready.notify_waiters();
+        let idle: Vec<IdleClient> = self.inner.idle().drain(..).collect();
+        for idle in idle {
+            retire(idle.client).await;
+        }
+    }
+}
+
+impl std::fmt::Debug for ClaudeClientPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClaudeClientPool")
+            .field("config", &self.inner.config)
+            .field("idle", &self.idle_count())
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+/// Periodically evict idle clients until the pool is dropped or closed
+fn spawn_reaper(pool: Weak<PoolInner>, idle_timeout: Duration) {
+    let period = (idle_timeout / 2).max(Duration::from_millis(100));
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(Instant::now() + period, period);
+        loop {
+            ticker.tick().await;
+            let Some(inner) = pool.upgrade() else { break };
+            if inner.closed.load(Ordering::SeqCst) {
+                break;
+            }
+            let evicted = inner.evict_expired().await;
+            if evicted > 0 {
+                debug!(evicted, "Evicted idle pool clients");
+            }
+        }
+    });
+}
+
+/// A [`ClaudeClient`] checked out of a [`ClaudeClientPool`]
+///
+/// Dereferences to `ClaudeClient`. Returns to the pool when dropped or passed
+/// to [`ClaudeClientPool::checkin`].
+pub struct PooledClient {
+    client: Option<ClaudeClient>,
+    permit: Option<OwnedSemaphorePermit>,
+    pool: Arc<PoolInner>,
+}
+
+impl PooledClient {
+    fn new(client: ClaudeClient, permit: OwnedSemaphorePermit, pool: Arc<PoolInner>) -> Self {
+        pool.checked_out.fetch_add(1, Ordering::SeqCst);
+        Self {
+            client: Some(client),
+            permit: Some(permit),
+            pool,
+        }
+    }
+
+    /// Close this client instead of returning it, and connect a replacement
+    /// in the background
+    ///
+    /// With session reuse, use this when the session is in a state the next
+    /// caller should not inherit, e.g. after an error mid-turn.
+    pub fn discard(mut self) {
+        if let (Some(client), Some(permit)) = (self.client.take(), self.permit.take()) {
+            self.pool.recycle(client, permit);
+        }
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = ClaudeClient;
+
+    fn deref(&self) -> &ClaudeClient {
+        self.client
+            .as_ref()
+            .expect("pooled client already returned")
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut ClaudeClient {
+        self.client
+            .as_mut()
+            .expect("pooled client already returned")
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        if let (Some(client), Some(permit)) = (self.client.take(), self.permit.take()) {
+            self.pool.restore(client, permit);
+        }
+    }
+}
+
+impl std::fmt::Debug for PooledClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PooledClient")
+            .field("session_id", &self.session().session_id)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::Message;
+
+    /// Pool whose connector hands out unconnected clients and counts connects
+    fn counting_pool(config: PoolConfig) -> (ClaudeClientPool, Arc<AtomicUsize>) {
+        let connects = Arc::new(AtomicUsize::new(0));
+        let counter = connects.clone();
+        let connector: Connector = Arc::new(move |options| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move { ClaudeClient::new(options) })
+        });
+        let pool =
+            ClaudeClientPool::with_connector(ClaudeAgentOptions::default(), config, connector);
+        (pool, connects)
+    }
+
+    /// Counting pool without health checks, since its clients never connect
+    fn test_pool(config: PoolConfig) -> (ClaudeClientPool, Arc<AtomicUsize>) {
+        counting_pool(config.with_health_check(false))
+    }
+
+    /// Let background replacements run until `count` clients are idle
+    async fn wait_for_idle(pool: &ClaudeClientPool, count: usize) {
+        while pool.idle_count() < count {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    fn result_message() -> Message {
+        serde_json::from_value(serde_json::json!({
+            "type": "result", "subtype": "success", "result": "ok",
+            "duration_ms": 1, "num_turns": 1, "session_id": "s1", "total_cost_usd": 0.0
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pool_config_defaults() {
+        let config = PoolConfig::default();
+        assert_eq!(config.size, 4);
+        assert_eq!(config.idle_timeout, Some(Duration::from_secs(300)));
+        assert_eq!(config.min_idle, None);
+        assert!(!config.reuse_sessions);
+        assert!(config.health_check);
+        assert_eq!(PoolConfig::new(0).size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_checkin_replaces_client_with_fresh_session() {
+        let (pool, connects) = test_pool(PoolConfig::new(1));
+        let pool = pool.warm().await.unwrap();
+
+        let client = pool.checkout().await.unwrap();
+        client.record_session(&result_message());
+        pool.checkin(client);
+
+        // Checkout waits for the replacement instead of connecting its own
+        let fresh = pool.checkout().await.unwrap();
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+        assert_eq!(fresh.session().turn_count, 0);
+        assert!(fresh.session().session_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_checkin_releases_permit_before_replacement_connects() {
+        let (pool, connects) = test_pool(PoolConfig::new(1));
+        let pool = pool.warm().await.unwrap();
+
+        let client = pool.checkout().await.unwrap();
+        pool.checkin(client);
+        assert_eq!(pool.available(), 1);
+        assert_eq!(pool.idle_count(), 0);
+
+        wait_for_idle(&pool, 1).await;
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_checkout_reuses_warm_clients() {
+        let (pool, connects) = test_pool(PoolConfig::new(2).with_session_reuse(true));
+        let pool = pool.warm().await.unwrap();
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.idle_count(), 2);
+
+        let client = pool.checkout().await.unwrap();
+        assert_eq!(pool.idle_count(), 1);
+        assert_eq!(pool.available(), 1);
+        pool.checkin(client);
+
+        assert_eq!(pool.idle_count(), 2);
+        assert_eq!(pool.available(), 2);
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_checkout_waits_for_capacity() {
+        let (pool, _) = test_pool(PoolConfig::new(1));
+        let pool = pool.warm().await.unwrap();
+
+        let first = pool.checkout().await.unwrap();
+        let waiting = tokio::time::timeout(Duration::from_millis(20), pool.checkout()).await;
+        assert!(waiting.is_err());
+
+        drop(first);
+        assert!(pool.checkout().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_recycles_after_max_turns() {
+        let (pool, connects) = test_pool(
+            PoolConfig::new(1)
+                .with_session_reuse(true)
+                .with_max_turns(1),
+        );
+        let pool = pool.warm().await.unwrap();
+
+        let client = pool.checkout().await.unwrap();
+        client.record_session(&result_message());
+        pool.checkin(client);
+
+        // Checkout waits for the replacement instead of connecting its own
+        let fresh = pool.checkout().await.unwrap();
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+        assert_eq!(fresh.session().turn_count, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_clients_are_evicted() {
+        let (pool, connects) = test_pool(
+            PoolConfig::new(2)
+                .with_idle_timeout(Duration::from_secs(10))
+                .with_min_idle(0),
+        );
+        let pool = pool.warm().await.unwrap();
+
+        tokio::time::advance(Duration::from_secs(11)).await;
+        assert_eq!(pool.evict_idle().await, 2);
+        assert_eq!(pool.idle_count(), 0);
+
+        // Nothing warm is left, so checkout connects a new client
+        let _client = pool.checkout().await.unwrap();
+        assert_eq!(connects.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_evicted_clients_are_replenished() {
+        let (pool, connects) = test_pool(
+            PoolConfig::new(3)
+                .with_idle_timeout(Duration::from_secs(10))
+                .with_min_idle(2),
+        );
+        let pool = pool.warm().await.unwrap();
+
+        tokio::time::advance(Duration::from_secs(11)).await;
+        assert_eq!(pool.evict_idle().await, 3);
+        wait_for_idle(&pool, 2).await;
+        assert_eq!(connects.load(Ordering::SeqCst), 5);
+
+        let _client = pool.checkout().await.unwrap();
+        assert_eq!(connects.load(Ordering::SeqCst), 5);
+        assert_eq!(pool.idle_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_replaces_dead_clients() {
+        // Unconnected clients fail the health check
+        let (pool, connects) = counting_pool(PoolConfig::new(2));
+        let pool = pool.warm().await.unwrap();
+        assert_eq!(pool.idle_count(), 2);
+
+        // Both idle clients are discarded and checkout connects its own
+        let client = pool.checkout().await.unwrap();
+        assert_eq!(pool.idle_count(), 0);
+        assert_eq!(connects.load(Ordering::SeqCst), 3);
+
+        // The other slot is reconnected in the background
+        wait_for_idle(&pool, 1).await;
+        assert_eq!(connects.load(Ordering::SeqCst), 4);
+        drop(client);
+    }
+
+    #[tokio::test]
+    async fn test_discard_replaces_client() {
+        let (pool, connects) = test_pool(PoolConfig::new(1));
+        let pool = pool.warm().await.unwrap();
+
+        let client = pool.checkout().await.unwrap();
+        client.discard();
+        let _fresh = pool.checkout().await.unwrap();
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_closed_pool_rejects_checkout() {
+        let (pool, _) = test_pool(PoolConfig::new(1));
+        let pool = pool.warm().await.unwrap();
+        pool.close().await;
+
+        assert_eq!(pool.idle_count(), 0);
+        assert!(matches!(
+            pool.checkout().await,
+            Err(ClawError::Connection(_))
+        ));
+    }
+}
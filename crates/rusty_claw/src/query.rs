@@ -29,15 +29,16 @@
 //! }
 //! ```
 
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::pin::Pin;
 use std::task::{Context, Poll};
-use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::{Stream, StreamExt};
 
 use crate::error::ClawError;
-use crate::messages::Message;
-use crate::options::ClaudeAgentOptions;
+use crate::messages::{Message, UserInput};
+use crate::options::{ClaudeAgentOptions, output_format_for};
 use crate::transport::{SubprocessCLITransport, Transport};
 
 /// A stream wrapper that owns the transport to ensure proper lifetime management
@@ -130,6 +131,7 @@ pub async fn query(
     options: Option<ClaudeAgentOptions>,
 ) -> Result<impl Stream<Item = Result<Message, ClawError>>, ClawError> {
     let prompt = prompt.into();
+    let channel = options.as_ref().map(|o| o.channel).unwrap_or_default();
 
     // Extract CLI args from options or use defaults
     let args = if let Some(opts) = options {
@@ -148,6 +150,7 @@ pub async fn query(
 
     // Create transport with auto-discovery (None = discover CLI from PATH/env/common locations)
     let mut transport = SubprocessCLITransport::new(None, args);
+    transport.set_channel_config(channel);
 
     // Connect to CLI (discovers, validates version, spawns process)
     transport.connect().await?;
@@ -159,20 +162,74 @@ pub async fn query(
     let rx = transport.messages();
 
     // Convert receiver to stream and parse Message structs
-    let stream = UnboundedReceiverStream::new(rx).map(|result| {
-        result.and_then(|value| {
-            let raw = value.to_string();
-            serde_json::from_value::<Message>(value).map_err(|e| ClawError::MessageParse {
-                reason: e.to_string(),
-                raw,
-            })
-        })
-    });
+    let stream = ReceiverStream::new(rx).map(|result| result.and_then(Message::parse));
 
     // Wrap in QueryStream to ensure transport outlives the stream
     Ok(QueryStream::new(transport, stream))
 }
 
+/// Execute a one-shot query and deserialize its structured output into `T`
+///
+/// Sets `output_format` to the JSON schema of `T` (replacing any configured
+/// format), runs [`query()`], and deserializes the final result's
+/// `structured_output`.
+///
+/// # Errors
+///
+/// - Any error from [`query()`] or from reading the stream
+/// - `ClawError::StructuredOutput` if the output is missing or does not match
+///   `T`; the error carries the raw payload
+///
+/// # Example
+///
+/// ```no_run
+/// use rusty_claw::query::query_structured;
+/// use schemars::JsonSchema;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, JsonSchema)]
+/// struct Sentiment {
+///     label: String,
+///     confidence: f64,
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let sentiment: Sentiment =
+///     query_structured("Classify: 'I love this library'", None).await?;
+/// println!("{} ({:.2})", sentiment.label, sentiment.confidence);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn query_structured<T>(
+    prompt: impl Into<String>,
+    options: Option<ClaudeAgentOptions>,
+) -> Result<T, ClawError>
+where
+    T: DeserializeOwned + schemars::JsonSchema,
+{
+    let mut options = options.unwrap_or_default();
+    options.output_format = Some(output_format_for::<T>());
+    let stream = query(prompt, Some(options)).await?;
+    collect_structured(stream).await
+}
+
+/// Read messages until the result and deserialize its structured output
+pub(crate) async fn collect_structured<T: DeserializeOwned>(
+    stream: impl Stream<Item = Result<Message, ClawError>>,
+) -> Result<T, ClawError> {
+    let mut stream = std::pin::pin!(stream);
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message? {
+            return result.structured();
+        }
+    }
+    Err(ClawError::StructuredOutput {
+        reason: "stream ended without a result message".to_string(),
+        raw: Value::Null,
+    })
+}
+
 /// Execute a query that accepts a stream of input messages (multi-message input)
 ///
 /// This function enables advanced agentic patterns where the initial input to Claude
@@ -184,7 +241,7 @@ pub async fn query(
 ///
 /// # Arguments
 ///
-/// * `messages` - An async stream of `serde_json::Value` items to send as input messages
+/// * `messages` - An async stream of [`UserInput`] messages to send as the input conversation
 /// * `options` - Optional configuration using [`ClaudeAgentOptions`]
 ///
 /// # Returns
@@ -203,21 +260,15 @@ pub async fn query(
 ///
 /// ```ignore
 /// use rusty_claw::query::query_with_messages;
-/// use rusty_claw::options::ClaudeAgentOptions;
-/// use serde_json::json;
+/// use rusty_claw::messages::{UserContentBlock, UserInput};
 /// use tokio_stream::{StreamExt, iter as stream_iter};
 ///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-///     // Build an initial conversation with a system message + user prompt
+///     // Build an initial conversation from typed user messages
 ///     let messages = vec![
-///         json!({
-///             "type": "user",
-///             "message": {
-///                 "role": "user",
-///                 "content": [{"type": "text", "text": "What is 2 + 2?"}]
-///             }
-///         })
+///         UserInput::text("Here is the file to review."),
+///         UserInput::blocks(vec![UserContentBlock::text("What is 2 + 2?")]),
 ///     ];
 ///
 ///     let mut stream = query_with_messages(
@@ -235,7 +286,7 @@ pub async fn query(
 /// }
 /// ```
 pub async fn query_with_messages(
-    messages: impl Stream<Item = Value> + Unpin,
+    messages: impl Stream<Item = UserInput> + Unpin,
     options: Option<ClaudeAgentOptions>,
 ) -> Result<impl Stream<Item = Result<Message, ClawError>>, ClawError> {
     // Build CLI args WITHOUT -p (prompt comes via stdin in stream-json mode)
@@ -266,6 +317,11 @@ pub async fn query_with_messages(
         transport.set_max_buffer_size(size);
     }
 
+    // Apply message channel capacity and overflow policy if configured
+    if let Some(opts) = &options {
+        transport.set_channel_config(opts.channel);
+    }
+
     // Connect to CLI
     transport.connect().await?;
 
@@ -284,15 +340,7 @@ pub async fn query_with_messages(
     let rx = transport.messages();
 
     // Convert receiver to stream and parse Message structs
-    let stream = UnboundedReceiverStream::new(rx).map(|result| {
-        result.and_then(|value| {
-            let raw = value.to_string();
-            serde_json::from_value::<Message>(value).map_err(|e| ClawError::MessageParse {
-                reason: e.to_string(),
-                raw,
-            })
-        })
-    });
+    let stream = ReceiverStream::new(rx).map(|result| result.and_then(Message::parse));
 
     // Wrap in QueryStream to ensure transport outlives the stream
     Ok(QueryStream::new(transport, stream))
@@ -416,8 +464,7 @@ mod tests {
         // Verify that QueryStream implements Send (required for tokio tasks)
         fn assert_send<T: Send>() {}
         // Use a concrete type for the stream generic parameter
-        use tokio_stream::wrappers::UnboundedReceiverStream;
-        type ConcreteStream = UnboundedReceiverStream<Result<Message, ClawError>>;
+        type ConcreteStream = ReceiverStream<Result<Message, ClawError>>;
         assert_send::<QueryStream<ConcreteStream>>();
     }
 
@@ -425,11 +472,64 @@ mod tests {
     fn test_query_stream_is_unpin() {
         // Verify that QueryStream implements Unpin (required for easy pinning)
         fn assert_unpin<T: Unpin>() {}
-        use tokio_stream::wrappers::UnboundedReceiverStream;
-        type ConcreteStream = UnboundedReceiverStream<Result<Message, ClawError>>;
+        type ConcreteStream = ReceiverStream<Result<Message, ClawError>>;
         assert_unpin::<QueryStream<ConcreteStream>>();
     }
 
+    #[derive(Debug, serde::Deserialize)]
+    struct Answer {
+        value: u32,
+    }
+
+    fn result_with(output: Value) -> Result<Message, ClawError> {
+        Ok(serde_json::from_value(serde_json::json!({
+            "type": "result", "subtype": "success", "result": "", "structured_output": output
+        }))
+        .unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_collect_structured_success() {
+        let stream = tokio_stream::iter(vec![result_with(serde_json::json!({"value": 42}))]);
+        let answer: Answer = collect_structured(stream).await.unwrap();
+        assert_eq!(answer.value, 42);
+    }
+
+    #[tokio::test]
+    async fn test_collect_structured_mismatch_keeps_raw() {
+        let raw = serde_json::json!({"value": "forty-two"});
+        let stream = tokio_stream::iter(vec![result_with(raw.clone())]);
+        match collect_structured::<Answer>(stream).await {
+            Err(ClawError::StructuredOutput { raw: got, .. }) => assert_eq!(got, raw),
+            other => panic!("Expected StructuredOutput error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_structured_without_result() {
+        let stream = tokio_stream::iter(Vec::<Result<Message, ClawError>>::new());
+        assert!(matches!(
+            collect_structured::<Answer>(stream).await,
+            Err(ClawError::StructuredOutput { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_collect_structured_error_result() {
+        let error = serde_json::from_value(serde_json::json!({
+            "type": "result", "subtype": "error", "error": "max turns", "num_turns": 3
+        }))
+        .unwrap();
+        let stream = tokio_stream::iter(vec![Ok(error)]);
+        match collect_structured::<Answer>(stream).await {
+            Err(ClawError::StructuredOutput { reason, raw }) => {
+                assert!(reason.contains("max turns"));
+                assert_eq!(raw["num_turns"], 3);
+            }
+            other => panic!("Expected StructuredOutput error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_query_accepts_string() {
         // Compile-time test: verify query accepts String
@@ -0,0 +1,720 @@
+//! Read and search the CLI's on-disk session history
+//!
+//! The Claude Code CLI records every session as a JSONL file under
+//! `<config dir>/projects/<project>/<session id>.jsonl`, where `<project>` is the
+//! session's working directory with every non-alphanumeric character replaced by `-`
+//! (e.g., `/home/dev/app` becomes `-home-dev-app`).
+//!
+//! [`SessionStore`] lists those sessions with their timestamps, first prompt and cost,
+//! loads a session's history as `Vec<Message>`, and searches session text. The
+//! session IDs it returns can be passed to
+//! [`ClaudeAgentOptions::builder().resume()`](crate::options::ClaudeAgentOptionsBuilder::resume).
+//!
+//! Lines the store does not understand (metadata entries, corrupt lines) are skipped,
+//! so files written by newer CLI versions still load.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rusty_claw::prelude::*;
+//! use rusty_claw::session_store::SessionStore;
+//!
+//! # fn example() -> Result<(), ClawError> {
+//! let store = SessionStore::from_env().expect("HOME is not set");
+//!
+//! for session in store.list_sessions("/home/dev/app")? {
+//!     println!(
+//!         "{}  {}  {}",
+//!         session.session_id,
+//!         session.updated_at.as_deref().unwrap_or("-"),
+//!         session.first_prompt.as_deref().unwrap_or("(no prompt)"),
+//!     );
+//! }
+//!
+//! let hits = store.search("login test")?;
+//! if let Some(hit) = hits.first() {
+//!     let options = ClaudeAgentOptions::builder()
+//!         .resume(hit.session.session_id.clone())
+//!         .build();
+//! #   let _ = options;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::error::ClawError;
+use crate::messages::Message;
+
+/// Maximum length of a search snippet, in characters
+const SNIPPET_LEN: usize = 160;
+
+/// A project directory in the session store
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectInfo {
+    /// Directory name (the encoded working directory, e.g., `-home-dev-app`)
+    pub name: String,
+    /// Full path of the project directory
+    pub path: PathBuf,
+    /// Working directory recorded in the project's sessions, if any
+    pub cwd: Option<PathBuf>,
+}
+
+/// Overview of one recorded session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSummary {
+    /// Session ID, usable with `ClaudeAgentOptions::resume`
+    pub session_id: String,
+    /// Path of the session's JSONL file
+    pub path: PathBuf,
+    /// Working directory of the session
+    pub cwd: Option<PathBuf>,
+    /// Git branch at the start of the session
+    pub git_branch: Option<String>,
+    /// Timestamp of the first entry (RFC 3339)
+    pub started_at: Option<String>,
+    /// Timestamp of the last entry (RFC 3339)
+    pub updated_at: Option<String>,
+    /// First prompt typed by the user
+    pub first_prompt: Option<String>,
+    /// Summary title generated by the CLI
+    pub summary: Option<String>,
+    /// Number of user and assistant messages
+    pub message_count: usize,
+    /// Total cost in USD, when the CLI recorded per-message costs
+    pub total_cost_usd: Option<f64>,
+}
+
+/// A session matching a [`SessionStore::search`] query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionMatch {
+    /// The matching session
+    pub session: SessionSummary,
+    /// Lines of message text containing the query
+    pub snippets: Vec<String>,
+}
+
+/// Reader for the CLI's session history
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    config_dir: PathBuf,
+}
+
+impl SessionStore {
+    /// Create a store reading from the given CLI config directory (e.g., `~/.claude`)
+    pub fn new(config_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            config_dir: config_dir.into(),
+        }
+    }
+
+    /// Create a store for the default config directory
+    ///
+    /// Uses `CLAUDE_CONFIG_DIR` if set, otherwise `$HOME/.claude`. Returns `None`
+    /// if neither variable is set.
+    pub fn from_env() -> Option<Self> {
+        if let Ok(dir) = std::env::var("CLAUDE_CONFIG_DIR") {
+            return Some(Self::new(dir));
+        }
+        let home = std::env::var("HOME").ok()?;
+        Some(Self::new(PathBuf::from(home).join(".claude")))
+    }
+
+    /// The CLI config directory this store reads from
+    pub fn config_dir(&self) -> &Path {
+        &self.config_dir
+    }
+
+    /// Directory holding the sessions for `cwd`
+    pub fn project_dir(&self, cwd: impl AsRef<Path>) -> PathBuf {
+        self.projects_root().join(encode_project_path(cwd.as_ref()))
+    }
+
+    /// List all project directories, sorted by name
+    ///
+    /// Returns an empty list if the config directory has no projects yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::Io`] if a directory cannot be read.
+    pub fn list_projects(&self) -> Result<Vec<ProjectInfo>, ClawError> {
+        let mut projects = Vec::new();
+        for path in read_dir_sorted(&self.projects_root())? {
+            if !path.is_dir() {
+                continue;
+            }
+            let cwd = session_files(&path)?
+                .first()
+                .and_then(|file| summarize(file).ok())
+                .and_then(|summary| summary.cwd);
+            projects.push(ProjectInfo {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                path,
+                cwd,
+            });
+        }
+        Ok(projects)
+    }
+
+    /// List the sessions recorded for a working directory, most recent first
+    ///
+    /// Session files that cannot be read are skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::Io`] if the project directory cannot be read.
+    pub fn list_sessions(&self, cwd: impl AsRef<Path>) -> Result<Vec<SessionSummary>, ClawError> {
+        let mut sessions: Vec<_> = session_files(&self.project_dir(cwd))?
+            .iter()
+            .filter_map(|file| read_readable(file))
+            .map(|(file, entries)| summarize_entries(file, &entries))
+            .collect();
+        sort_recent_first(&mut sessions);
+        Ok(sessions)
+    }
+
+    /// List the sessions of every project, most recent first
+    ///
+    /// Session files that cannot be read are skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::Io`] if a project directory cannot be read.
+    pub fn list_all_sessions(&self) -> Result<Vec<SessionSummary>, ClawError> {
+        let mut sessions: Vec<_> = self
+            .all_session_files()?
+            .iter()
+            .filter_map(|file| read_readable(file))
+            .map(|(file, entries)| summarize_entries(file, &entries))
+            .collect();
+        sort_recent_first(&mut sessions);
+        Ok(sessions)
+    }
+
+    /// Find a session by ID in any project
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::Io`] if a directory or the session file cannot be read.
+    pub fn find(&self, session_id: &str) -> Result<Option<SessionSummary>, ClawError> {
+        match self.session_path(session_id)? {
+            Some(path) => summarize(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Load a session's conversation as messages
+    ///
+    /// Returns the user and assistant messages in file order; entries that are not
+    /// messages (summaries, snapshots) are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::Io`] with `NotFound` if no project contains the session,
+    /// or if the file cannot be read.
+    pub fn load(&self, session_id: &str) -> Result<Vec<Message>, ClawError> {
+        let path = self.session_path(session_id)?.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("session {} not found", session_id),
+            )
+        })?;
+        Ok(read_entries(&path)?
+            .into_iter()
+            .filter_map(|entry| entry.into_message())
+            .collect())
+    }
+
+    /// Search the text of every session for `query` (case-insensitive)
+    ///
+    /// Matches prompts, assistant text and tool results. Returns matching sessions,
+    /// most recent first, each with the matching lines as snippets. Each file is
+    /// read once; files that cannot be read are skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::Io`] if a project directory cannot be read.
+    pub fn search(&self, query: &str) -> Result<Vec<SessionMatch>, ClawError> {
+        let needle = query.to_lowercase();
+        let mut matches = Vec::new();
+        for file in self.all_session_files()? {
+            let Some((file, entries)) = read_readable(&file) else {
+                continue;
+            };
+            let mut snippets = Vec::new();
+            for entry in &entries {
+                for text in entry.texts() {
+                    for line in text.lines() {
+                        if line.to_lowercase().contains(&needle) {
+                            snippets.push(snippet(line));
+                        }
+                    }
+                }
+            }
+            if !snippets.is_empty() {
+                matches.push(SessionMatch {
+                    session: summarize_entries(file, &entries),
+                    snippets,
+                });
+            }
+        }
+        matches.sort_by(|a, b| b.session.updated_at.cmp(&a.session.updated_at));
+        Ok(matches)
+    }
+
+    fn projects_root(&self) -> PathBuf {
+        self.config_dir.join("projects")
+    }
+
+    /// Session files of every project
+    fn all_session_files(&self) -> Result<Vec<PathBuf>, ClawError> {
+        let mut files = Vec::new();
+        for project in read_dir_sorted(&self.projects_root())? {
+            if project.is_dir() {
+                files.extend(session_files(&project)?);
+            }
+        }
+        Ok(files)
+    }
+
+    fn session_path(&self, session_id: &str) -> Result<Option<PathBuf>, ClawError> {
+        // Session IDs are file stems; anything path-like cannot name a session
+        if session_id.is_empty() || session_id.contains(['/', '\\']) || session_id.contains("..") {
+            return Ok(None);
+        }
+        for project in read_dir_sorted(&self.projects_root())? {
+            let path = project.join(format!("{}.jsonl", session_id));
+            if path.is_file() {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Encode a working directory the way the CLI names project directories
+fn encode_project_path(cwd: &Path) -> String {
+    cwd.to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Entries of `dir`, sorted by path; a missing directory is empty
+fn read_dir_sorted(dir: &Path) -> Result<Vec<PathBuf>, ClawError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut paths = entries
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+    Ok(paths)
+}
+
+fn session_files(project: &Path) -> Result<Vec<PathBuf>, ClawError> {
+    Ok(read_dir_sorted(project)?
+        .into_iter()
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect())
+}
+
+fn sort_recent_first(sessions: &mut [SessionSummary]) {
+    sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+}
+
+fn snippet(line: &str) -> String {
+    let line = line.trim();
+    if line.chars().count() > SNIPPET_LEN {
+        let truncated: String = line.chars().take(SNIPPET_LEN).collect();
+        format!("{}…", truncated)
+    } else {
+        line.to_string()
+    }
+}
+
+/// One line of a session file
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    message: Option<Value>,
+    #[serde(default)]
+    uuid: Option<String>,
+    #[serde(default)]
+    timestamp: Option<String>,
+    #[serde(default)]
+    cwd: Option<PathBuf>,
+    #[serde(default)]
+    git_branch: Option<String>,
+    #[serde(default)]
+    is_meta: bool,
+    #[serde(default, rename = "costUSD")]
+    cost_usd: Option<f64>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    tool_use_result: Option<Value>,
+}
+
+impl SessionEntry {
+    fn is_message(&self) -> bool {
+        (self.kind == "user" || self.kind == "assistant") && self.message.is_some()
+    }
+
+    /// Message content as blocks; the CLI stores plain prompts as a bare string
+    fn content(&self) -> Vec<Value> {
+        match self.message.as_ref().and_then(|m| m.get("content")) {
+            Some(Value::String(text)) => vec![json!({"type": "text", "text": text})],
+            Some(Value::Array(blocks)) => blocks.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Searchable text: text blocks, thinking, and string tool results
+    fn texts(&self) -> Vec<String> {
+        if !self.is_message() || self.is_meta {
+            return Vec::new();
+        }
+        self.content()
+            .iter()
+            .filter_map(|block| match block.get("type").and_then(Value::as_str) {
+                Some("text") => block.get("text"),
+                Some("thinking") => block.get("thinking"),
+                Some("tool_result") => block.get("content"),
+                _ => None,
+            })
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// The typed prompt, if this is a user entry with text and no tool results
+    fn prompt(&self) -> Option<String> {
+        if self.kind != "user" || self.is_meta {
+            return None;
+        }
+        let content = self.content();
+        if content
+            .iter()
+            .any(|block| block.get("type").and_then(Value::as_str) == Some("tool_result"))
+        {
+            return None;
+        }
+        content
+            .iter()
+            .find_map(|block| block.get("text").and_then(Value::as_str))
+            .map(|text| text.trim().to_string())
+    }
+
+    fn into_message(self) -> Option<Message> {
+        if !self.is_message() {
+            return None;
+        }
+        let mut message = self.message.clone()?;
+        message["content"] = Value::Array(self.content());
+        let mut value = json!({
+            "type": self.kind,
+            "message": message,
+        });
+        if let Some(uuid) = &self.uuid {
+            value["uuid"] = json!(uuid);
+        }
+        if let Some(result) = self.tool_use_result {
+            value["tool_use_result"] = result;
+        }
+        match Message::parse(value) {
+            Ok(message) => Some(message),
+            Err(e) => {
+                tracing::warn!("Skipping unreadable session entry: {}", e);
+                None
+            }
+        }
+    }
+}
+
+fn read_entries(path: &Path) -> Result<Vec<SessionEntry>, ClawError> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<SessionEntry>(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => tracing::debug!("Skipping malformed line in {}: {}", path.display(), e),
+        }
+    }
+    Ok(entries)
+}
+
+/// Entries of a session file, or `None` (with a warning) if it cannot be read
+fn read_readable(path: &Path) -> Option<(&Path, Vec<SessionEntry>)> {
+    match read_entries(path) {
+        Ok(entries) => Some((path, entries)),
+        Err(e) => {
+            tracing::warn!("Skipping unreadable session file {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn summarize(path: &Path) -> Result<SessionSummary, ClawError> {
+    Ok(summarize_entries(path, &read_entries(path)?))
+}
+
+fn summarize_entries(path: &Path, entries: &[SessionEntry]) -> SessionSummary {
+    let mut summary = SessionSummary {
+        session_id: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        path: path.to_path_buf(),
+        cwd: None,
+        git_branch: None,
+        started_at: None,
+        updated_at: None,
+        first_prompt: None,
+        summary: None,
+        message_count: 0,
+        total_cost_usd: None,
+    };
+    for entry in entries {
+        if summary.cwd.is_none() {
+            summary.cwd = entry.cwd.clone();
+        }
+        if summary.git_branch.is_none() {
+            summary.git_branch = entry.git_branch.clone();
+        }
+        if let Some(timestamp) = &entry.timestamp {
+            if summary.started_at.is_none() {
+                summary.started_at = Some(timestamp.clone());
+            }
+            summary.updated_at = Some(timestamp.clone());
+        }
+        if summary.first_prompt.is_none() {
+            summary.first_prompt = entry.prompt();
+        }
+        if entry.kind == "summary" && entry.summary.is_some() {
+            summary.summary = entry.summary.clone();
+        }
+        if entry.is_message() {
+            summary.message_count += 1;
+        }
+        if let Some(cost) = entry.cost_usd {
+            *summary.total_cost_usd.get_or_insert(0.0) += cost;
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::ContentBlock;
+
+    const LOGIN_SESSION: &str = "0b1c2d3e-0000-4000-8000-000000000001";
+    const CACHE_SESSION: &str = "0b1c2d3e-0000-4000-8000-000000000002";
+    const DOCS_SESSION: &str = "0b1c2d3e-0000-4000-8000-000000000003";
+
+    fn fixture_store() -> SessionStore {
+        SessionStore::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/sessions"
+        ))
+    }
+
+    #[test]
+    fn test_encode_project_path() {
+        assert_eq!(
+            encode_project_path(Path::new("/home/dev/my.app")),
+            "-home-dev-my-app"
+        );
+    }
+
+    #[test]
+    fn test_list_projects() {
+        let projects = fixture_store().list_projects().unwrap();
+        let names: Vec<_> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["-home-dev-app", "-home-dev-lib"]);
+        assert_eq!(projects[0].cwd.as_deref(), Some(Path::new("/home/dev/app")));
+    }
+
+    #[test]
+    fn test_list_sessions_for_project() {
+        let sessions = fixture_store().list_sessions("/home/dev/app").unwrap();
+        assert_eq!(sessions.len(), 2);
+
+        // Most recent first
+        assert_eq!(sessions[0].session_id, CACHE_SESSION);
+        assert_eq!(
+            sessions[0].first_prompt.as_deref(),
+            Some("Add a cache to the user lookup")
+        );
+        assert_eq!(sessions[0].git_branch.as_deref(), Some("feature/cache"));
+        assert_eq!(sessions[0].total_cost_usd, None);
+
+        let login = &sessions[1];
+        assert_eq!(login.session_id, LOGIN_SESSION);
+        // The meta caveat entry is not the first prompt
+        assert_eq!(
+            login.first_prompt.as_deref(),
+            Some("Fix the flaky login test")
+        );
+        assert_eq!(
+            login.summary.as_deref(),
+            Some("Fix flaky login test timeout")
+        );
+        assert_eq!(
+            login.started_at.as_deref(),
+            Some("2025-10-01T09:00:00.000Z")
+        );
+        assert_eq!(
+            login.updated_at.as_deref(),
+            Some("2025-10-01T09:00:15.000Z")
+        );
+        assert_eq!(login.message_count, 5);
+        assert!((login.total_cost_usd.unwrap() - 0.005).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_list_sessions_unknown_project_is_empty() {
+        let store = fixture_store();
+        assert!(store.list_sessions("/nowhere").unwrap().is_empty());
+        assert!(
+            SessionStore::new("/definitely/missing")
+                .list_all_sessions()
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_list_all_sessions() {
+        let ids: Vec<_> = fixture_store()
+            .list_all_sessions()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        assert_eq!(ids, vec![CACHE_SESSION, LOGIN_SESSION, DOCS_SESSION]);
+    }
+
+    #[test]
+    fn test_load_session_messages() {
+        let messages = fixture_store().load(LOGIN_SESSION).unwrap();
+        assert_eq!(messages.len(), 5);
+
+        match &messages[1] {
+            Message::User(user) => match &user.message.content[0] {
+                ContentBlock::Text { text, .. } => assert_eq!(text, "Fix the flaky login test"),
+                other => panic!("Expected text block, got {:?}", other),
+            },
+            other => panic!("Expected User message, got {:?}", other),
+        }
+        match &messages[2] {
+            Message::Assistant(assistant) => {
+                assert!(matches!(
+                    &assistant.message.content[1],
+                    ContentBlock::ToolUse { name, .. } if name == "Bash"
+                ));
+            }
+            other => panic!("Expected Assistant message, got {:?}", other),
+        }
+        match &messages[3] {
+            Message::User(user) => assert!(user.tool_use_result.is_some()),
+            other => panic!("Expected User message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load_skips_malformed_lines() {
+        let messages = fixture_store().load(CACHE_SESSION).unwrap();
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[test]
+    fn test_load_missing_session() {
+        let store = fixture_store();
+        match store.load("missing") {
+            Err(ClawError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+            other => panic!("Expected NotFound, got {:?}", other),
+        }
+        assert!(store.find("../sessions/projects").unwrap().is_none());
+        assert_eq!(
+            store.find(DOCS_SESSION).unwrap().unwrap().cwd.as_deref(),
+            Some(Path::new("/home/dev/lib"))
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let matches = fixture_store().search("LOGIN").unwrap();
+        let ids: Vec<_> = matches
+            .iter()
+            .map(|m| m.session.session_id.as_str())
+            .collect();
+        assert_eq!(ids, vec![LOGIN_SESSION, DOCS_SESSION]);
+        assert!(
+            matches[0]
+                .snippets
+                .contains(&"test login ... FAILED (timeout)".to_string())
+        );
+
+        let matches = fixture_store().search("lru cache").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].session.session_id, CACHE_SESSION);
+
+        assert!(fixture_store().search("kubernetes").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unreadable_files_are_skipped() {
+        let root = std::env::temp_dir().join(format!("rusty_claw_{}", uuid::Uuid::new_v4()));
+        let project = root.join("projects").join("-home-dev-app");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(
+            project.join("good.jsonl"),
+            r#"{"type":"user","message":{"role":"user","content":"fix the login test"},"timestamp":"2025-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        // Not UTF-8: reading lines fails for the whole file
+        std::fs::write(project.join("bad.jsonl"), [0xff, 0xfe, b'\n']).unwrap();
+
+        let store = SessionStore::new(&root);
+        let sessions = store.list_all_sessions().unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, "good");
+        assert_eq!(store.list_sessions("/home/dev/app").unwrap().len(), 1);
+
+        let matches = store.search("login").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].session.session_id, "good");
+        assert_eq!(
+            matches[0].session.first_prompt.as_deref(),
+            Some("fix the login test")
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_snippet_truncates() {
+        let long = "x".repeat(SNIPPET_LEN + 10);
+        assert_eq!(snippet(&long).chars().count(), SNIPPET_LEN + 1);
+        assert_eq!(snippet("  short  "), "short");
+    }
+}
@@ -0,0 +1,766 @@
+//! Transcript export to Markdown, HTML and JSONL
+//!
+//! [`Transcript`] renders a conversation (a `Vec<Message>` or a live message stream)
+//! into shareable formats:
+//!
+//! - **Markdown** - GitHub-flavored, with tool calls, tool results and thinking blocks
+//!   in collapsible `<details>` sections
+//! - **HTML** - A standalone page with inline styles, no external assets
+//! - **JSONL** - One normalized message per line for archiving; transport-level
+//!   messages (control requests, stream deltas, MCP traffic) are dropped
+//!
+//! Subagent messages (those with a `parent_tool_use_id`) are nested under the tool
+//! call that spawned them, and the final [`ResultMessage`] becomes a footer with
+//! cost, duration and token usage.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rusty_claw::prelude::*;
+//! use rusty_claw::transcript::{Transcript, TranscriptFormat};
+//!
+//! # async fn example(client: &ClaudeClient) -> Result<(), ClawError> {
+//! let stream = client.send_message("Fix the failing test").await?;
+//! let transcript = Transcript::from_stream(stream).await?.title("Fix failing test");
+//!
+//! std::fs::write("transcript.md", transcript.to_markdown())?;
+//! std::fs::write("transcript.html", transcript.render(TranscriptFormat::Html)?)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use serde_json::Value;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::error::ClawError;
+use crate::messages::{ContentBlock, Message, ResultMessage, SystemMessage};
+
+/// Output format for [`Transcript::render`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    /// GitHub-flavored Markdown with collapsible tool sections
+    Markdown,
+    /// Standalone HTML page with inline styles
+    Html,
+    /// Normalized JSON Lines, one message per line
+    Jsonl,
+}
+
+impl TranscriptFormat {
+    /// Conventional file extension for the format (without the dot)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "md",
+            TranscriptFormat::Html => "html",
+            TranscriptFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// A recorded conversation that can be rendered to Markdown, HTML or JSONL
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    messages: Vec<Message>,
+    title: Option<String>,
+}
+
+impl Transcript {
+    /// Create a transcript from recorded messages
+    pub fn new(messages: Vec<Message>) -> Self {
+        Self {
+            messages,
+            title: None,
+        }
+    }
+
+    /// Collect a transcript from a message stream (e.g., a `ResponseStream`)
+    ///
+    /// Reads until the stream ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error yielded by the stream.
+    pub async fn from_stream<S>(stream: S) -> Result<Self, ClawError>
+    where
+        S: Stream<Item = Result<Message, ClawError>>,
+    {
+        let mut stream = std::pin::pin!(stream);
+        let mut messages = Vec::new();
+        while let Some(message) = stream.next().await {
+            messages.push(message?);
+        }
+        Ok(Self::new(messages))
+    }
+
+    /// Set the heading used in Markdown and HTML output (default: "Transcript")
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Append a message
+    pub fn push(&mut self, message: Message) {
+        self.messages.push(message);
+    }
+
+    /// The recorded messages
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Render in the given format
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::JsonDecode`] if a message cannot be serialized to JSONL.
+    pub fn render(&self, format: TranscriptFormat) -> Result<String, ClawError> {
+        match format {
+            TranscriptFormat::Markdown => Ok(self.to_markdown()),
+            TranscriptFormat::Html => Ok(self.to_html()),
+            TranscriptFormat::Jsonl => self.to_jsonl(),
+        }
+    }
+
+    /// Render as GitHub-flavored Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.heading());
+        let depths = nesting_depths(&self.messages);
+
+        for (message, depth) in self.messages.iter().zip(depths) {
+            let mut section = String::new();
+            match message {
+                Message::System(SystemMessage::Init {
+                    session_id,
+                    model,
+                    cwd,
+                    ..
+                }) => {
+                    let mut parts = vec![format!("session `{}`", session_id)];
+                    if let Some(model) = model {
+                        parts.push(format!("model `{}`", model));
+                    }
+                    if let Some(cwd) = cwd {
+                        parts.push(format!("cwd `{}`", cwd.display()));
+                    }
+                    let _ = writeln!(section, "_Started {}_", parts.join(" · "));
+                }
+                Message::System(SystemMessage::CompactBoundary { .. }) => {
+                    section.push_str("_Conversation compacted_\n");
+                }
+                Message::System(SystemMessage::Reconnected { attempt, .. }) => {
+                    let _ = writeln!(
+                        section,
+                        "_Reconnected after CLI restart (attempt {})_",
+                        attempt
+                    );
+                }
+                Message::Assistant(msg) => {
+                    section.push_str(&md_role_heading("Assistant", depth));
+                    for block in &msg.message.content {
+                        section.push_str(&md_block(block));
+                    }
+                }
+                Message::User(msg) => {
+                    section.push_str(&md_role_heading("User", depth));
+                    for block in &msg.message.content {
+                        section.push_str(&md_block(block));
+                    }
+                }
+                Message::Result(result) => section.push_str(&md_result(result)),
+                _ => continue,
+            }
+            out.push('\n');
+            out.push_str(&quote(&section, depth));
+        }
+        out
+    }
+
+    /// Render as a standalone HTML page with inline styles
+    pub fn to_html(&self) -> String {
+        let title = escape_html(self.heading());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n\
+             <body style=\"{BODY_STYLE}\">\n<h1 style=\"font-size:1.6em\">{title}</h1>\n"
+        );
+        let depths = nesting_depths(&self.messages);
+
+        for (message, depth) in self.messages.iter().zip(depths) {
+            let inner = match message {
+                Message::System(SystemMessage::Init {
+                    session_id, model, ..
+                }) => {
+                    let mut note =
+                        format!("Started session <code>{}</code>", escape_html(session_id));
+                    if let Some(model) = model {
+                        let _ = write!(note, " · model <code>{}</code>", escape_html(model));
+                    }
+                    html_note(&note)
+                }
+                Message::System(SystemMessage::CompactBoundary { .. }) => {
+                    html_note("Conversation compacted")
+                }
+                Message::System(SystemMessage::Reconnected { attempt, .. }) => html_note(&format!(
+                    "Reconnected after CLI restart (attempt {})",
+                    attempt
+                )),
+                Message::Assistant(msg) => html_message("Assistant", depth, &msg.message.content),
+                Message::User(msg) => html_message("User", depth, &msg.message.content),
+                Message::Result(result) => html_result(result),
+                _ => continue,
+            };
+            if depth > 0 {
+                let _ = writeln!(
+                    out,
+                    "<div style=\"margin-left:{}px;border-left:3px solid #d0d7de;padding-left:12px\">\n{}</div>",
+                    depth * 20,
+                    inner
+                );
+            } else {
+                out.push_str(&inner);
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    /// Render as normalized JSON Lines
+    ///
+    /// Keeps conversation messages (system, assistant, user, result, rate limit and
+    /// unrecognized messages) and drops transport-level traffic.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClawError::JsonDecode`] if a message cannot be serialized.
+    pub fn to_jsonl(&self) -> Result<String, ClawError> {
+        let mut out = String::new();
+        for message in &self.messages {
+            if matches!(
+                message,
+                Message::ControlRequest { .. }
+                    | Message::ControlResponse { .. }
+                    | Message::StreamEvent(_)
+                    | Message::McpMessage(_)
+            ) {
+                continue;
+            }
+            out.push_str(&serde_json::to_string(message)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    fn heading(&self) -> &str {
+        self.title.as_deref().unwrap_or("Transcript")
+    }
+}
+
+impl From<Vec<Message>> for Transcript {
+    fn from(messages: Vec<Message>) -> Self {
+        Self::new(messages)
+    }
+}
+
+const BODY_STYLE: &str = "font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Helvetica,Arial,sans-serif;\
+                          max-width:880px;margin:2em auto;padding:0 1em;color:#1f2328;line-height:1.5";
+const PRE_STYLE: &str = "background:#f6f8fa;border-radius:6px;padding:8px 12px;overflow-x:auto;\
+                         white-space:pre-wrap;font-size:0.85em";
+const DETAILS_STYLE: &str =
+    "margin:6px 0;border:1px solid #d0d7de;border-radius:6px;padding:4px 8px";
+
+/// Nesting depth of each message: 0 for the main conversation, +1 per subagent level
+///
+/// A message with `parent_tool_use_id` sits one level below the message that made
+/// the tool call.
+fn nesting_depths(messages: &[Message]) -> Vec<usize> {
+    let mut tool_depths: HashMap<&str, usize> = HashMap::new();
+    messages
+        .iter()
+        .map(|message| {
+            let (parent, content) = match message {
+                Message::Assistant(msg) => {
+                    (msg.parent_tool_use_id.as_deref(), &msg.message.content)
+                }
+                Message::User(msg) => (msg.parent_tool_use_id.as_deref(), &msg.message.content),
+                _ => return 0,
+            };
+            let depth = parent.map_or(0, |id| tool_depths.get(id).map_or(1, |d| d + 1));
+            for block in content {
+                if let ContentBlock::ToolUse { id, .. } = block {
+                    tool_depths.insert(id, depth);
+                }
+            }
+            depth
+        })
+        .collect()
+}
+
+/// Text of a tool result: strings verbatim, text blocks joined, anything else as JSON
+fn tool_result_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item.get("text").and_then(Value::as_str) {
+                Some(text) => text.to_string(),
+                None => pretty_json(item),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Null => String::new(),
+        other => pretty_json(other),
+    }
+}
+
+fn pretty_json(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+fn format_duration(ms: u64) -> String {
+    format!("{:.1}s", ms as f64 / 1000.0)
+}
+
+/// Summary line and usage rows shared by the Markdown and HTML footers
+struct ResultSummary {
+    headline: Vec<String>,
+    usage: Vec<(String, String)>,
+    errors: Vec<String>,
+}
+
+impl ResultSummary {
+    fn new(result: &ResultMessage) -> Self {
+        let mut headline = vec![result.subtype().to_string()];
+        let (duration_ms, num_turns) = match result {
+            ResultMessage::Success {
+                duration_ms,
+                num_turns,
+                ..
+            } => (*duration_ms, *num_turns),
+            other => other
+                .error_details()
+                .map_or((None, None), |d| (d.duration_ms, d.num_turns)),
+        };
+        if let Some(turns) = num_turns {
+            headline.push(format!("{} turns", turns));
+        }
+        if let Some(ms) = duration_ms {
+            headline.push(format_duration(ms));
+        }
+        if let Some(cost) = result.total_cost_usd() {
+            headline.push(format!("${:.4}", cost));
+        }
+
+        let mut usage = Vec::new();
+        if let Some(u) = result.usage() {
+            usage.push(("Input tokens".to_string(), u.input_tokens.to_string()));
+            usage.push(("Output tokens".to_string(), u.output_tokens.to_string()));
+            usage.push((
+                "Cache read tokens".to_string(),
+                u.cache_read_input_tokens.unwrap_or(0).to_string(),
+            ));
+            usage.push((
+                "Cache write tokens".to_string(),
+                u.cache_creation_input_tokens.unwrap_or(0).to_string(),
+            ));
+        }
+        let model_usage = match result {
+            ResultMessage::Success { model_usage, .. } => Some(model_usage),
+            other => other.error_details().map(|d| &d.model_usage),
+        };
+        if let Some(model_usage) = model_usage {
+            let mut models: Vec<_> = model_usage.iter().collect();
+            models.sort_by(|a, b| a.0.cmp(b.0));
+            for (model, u) in models {
+                usage.push((
+                    model.clone(),
+                    format!(
+                        "{} in / {} out · ${:.4}",
+                        u.input_tokens, u.output_tokens, u.cost_usd
+                    ),
+                ));
+            }
+        }
+
+        let errors = match result {
+            ResultMessage::Error { error, .. } => vec![error.clone()],
+            other => other
+                .error_details()
+                .map(|d| d.errors.clone())
+                .unwrap_or_default(),
+        };
+
+        Self {
+            headline,
+            usage,
+            errors,
+        }
+    }
+}
+
+// --- Markdown ---
+
+fn md_role_heading(role: &str, depth: usize) -> String {
+    if depth > 0 {
+        format!("### {} (subagent)\n\n", role)
+    } else {
+        format!("### {}\n\n", role)
+    }
+}
+
+/// Fenced code block whose fence is longer than any backtick run in `content`
+fn md_fence(content: &str, lang: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in content.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat((longest + 1).max(3));
+    format!(
+        "{fence}{lang}\n{}\n{fence}\n",
+        content.trim_end_matches('\n')
+    )
+}
+
+fn md_details(summary: &str, body: &str) -> String {
+    format!(
+        "<details>\n<summary>{}</summary>\n\n{}\n</details>\n\n",
+        summary, body
+    )
+}
+
+fn md_block(block: &ContentBlock) -> String {
+    match block {
+        ContentBlock::Text { text, .. } => format!("{}\n\n", text.trim_end()),
+        ContentBlock::Thinking { thinking, .. } => {
+            md_details("Thinking", &format!("{}\n", thinking.trim_end()))
+        }
+        ContentBlock::RedactedThinking { .. } => "_Thinking redacted_\n\n".to_string(),
+        ContentBlock::ToolUse { name, input, .. }
+        | ContentBlock::ServerToolUse { name, input, .. } => md_details(
+            &format!("Tool call: <code>{}</code>", escape_html(name)),
+            &md_fence(&pretty_json(input), "json"),
+        ),
+        ContentBlock::ToolResult {
+            content, is_error, ..
+        } => {
+            let summary = if *is_error {
+                "Tool result (error)"
+            } else {
+                "Tool result"
+            };
+            md_details(summary, &md_fence(&tool_result_text(content), ""))
+        }
+        ContentBlock::WebSearchToolResult { content, .. } => md_details(
+            "Web search results",
+            &md_fence(&pretty_json(content), "json"),
+        ),
+        ContentBlock::Image { .. } => "_[image]_\n\n".to_string(),
+        ContentBlock::Document { title, .. } => match title {
+            Some(title) => format!("_[document: {}]_\n\n", title),
+            None => "_[document]_\n\n".to_string(),
+        },
+        other => format!("_[{} block]_\n\n", other.block_type().unwrap_or("unknown")),
+    }
+}
+
+fn md_result(result: &ResultMessage) -> String {
+    let summary = ResultSummary::new(result);
+    let mut out = format!("---\n\n**Result:** {}\n\n", summary.headline.join(" · "));
+    for error in &summary.errors {
+        let _ = writeln!(out, "> **Error:** {}", error);
+    }
+    if !summary.errors.is_empty() {
+        out.push('\n');
+    }
+    if !summary.usage.is_empty() {
+        out.push_str("| Usage | |\n|---|---|\n");
+        for (label, value) in &summary.usage {
+            let _ = writeln!(out, "| {} | {} |", label, value);
+        }
+    }
+    out
+}
+
+/// Prefix every line with one `>` per nesting level
+fn quote(section: &str, depth: usize) -> String {
+    if depth == 0 {
+        return section.to_string();
+    }
+    let prefix = ">".repeat(depth);
+    section
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                format!("{}\n", prefix)
+            } else {
+                format!("{} {}\n", prefix, line)
+            }
+        })
+        .collect()
+}
+
+// --- HTML ---
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn html_note(html: &str) -> String {
+    format!(
+        "<p style=\"color:#59636e;font-style:italic\">{}</p>\n",
+        html
+    )
+}
+
+fn html_details(summary: &str, body: &str) -> String {
+    format!(
+        "<details style=\"{DETAILS_STYLE}\"><summary>{summary}</summary><pre style=\"{PRE_STYLE}\">{}</pre></details>\n",
+        escape_html(body)
+    )
+}
+
+fn html_message(role: &str, depth: usize, content: &[ContentBlock]) -> String {
+    let color = if role == "User" { "#0969da" } else { "#8250df" };
+    let label = if depth > 0 {
+        format!("{} (subagent)", role)
+    } else {
+        role.to_string()
+    };
+    let mut out = format!(
+        "<section style=\"margin:1em 0\">\n<h3 style=\"font-size:1em;color:{}\">{}</h3>\n",
+        color, label
+    );
+    for block in content {
+        out.push_str(&html_block(block));
+    }
+    out.push_str("</section>\n");
+    out
+}
+
+fn html_block(block: &ContentBlock) -> String {
+    match block {
+        ContentBlock::Text { text, .. } => format!(
+            "<div style=\"white-space:pre-wrap\">{}</div>\n",
+            escape_html(text.trim_end())
+        ),
+        ContentBlock::Thinking { thinking, .. } => html_details("Thinking", thinking),
+        ContentBlock::RedactedThinking { .. } => html_note("Thinking redacted"),
+        ContentBlock::ToolUse { name, input, .. }
+        | ContentBlock::ServerToolUse { name, input, .. } => html_details(
+            &format!("Tool call: <code>{}</code>", escape_html(name)),
+            &pretty_json(input),
+        ),
+        ContentBlock::ToolResult {
+            content, is_error, ..
+        } => {
+            let summary = if *is_error {
+                "<span style=\"color:#cf222e\">Tool result (error)</span>"
+            } else {
+                "Tool result"
+            };
+            html_details(summary, &tool_result_text(content))
+        }
+        ContentBlock::WebSearchToolResult { content, .. } => {
+            html_details("Web search results", &pretty_json(content))
+        }
+        ContentBlock::Image { .. } => html_note("[image]"),
+        ContentBlock::Document { title, .. } => match title {
+            Some(title) => html_note(&format!("[document: {}]", escape_html(title))),
+            None => html_note("[document]"),
+        },
+        other => html_note(&format!(
+            "[{} block]",
+            escape_html(other.block_type().unwrap_or("unknown"))
+        )),
+    }
+}
+
+fn html_result(result: &ResultMessage) -> String {
+    let summary = ResultSummary::new(result);
+    let mut out = format!(
+        "<footer style=\"margin-top:2em;border-top:1px solid #d0d7de;padding-top:1em\">\n\
+         <p><strong>Result:</strong> {}</p>\n",
+        escape_html(&summary.headline.join(" · "))
+    );
+    for error in &summary.errors {
+        let _ = writeln!(
+            out,
+            "<p style=\"color:#cf222e\"><strong>Error:</strong> {}</p>",
+            escape_html(error)
+        );
+    }
+    if !summary.usage.is_empty() {
+        out.push_str("<table style=\"border-collapse:collapse;font-size:0.9em\">\n");
+        for (label, value) in &summary.usage {
+            let _ = writeln!(
+                out,
+                "<tr><td style=\"padding:2px 12px 2px 0;color:#59636e\">{}</td><td>{}</td></tr>",
+                escape_html(label),
+                escape_html(value)
+            );
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</footer>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn conversation() -> Vec<Message> {
+        [
+            json!({
+                "type": "system",
+                "subtype": "init",
+                "session_id": "sess_1",
+                "tools": [],
+                "mcp_servers": [],
+                "model": "claude-sonnet-4-5"
+            }),
+            json!({
+                "type": "assistant",
+                "message": {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "Delegate the search"},
+                    {"type": "text", "text": "Let me look."},
+                    {"type": "tool_use", "id": "toolu_task", "name": "Task", "input": {"description": "search", "prompt": "find <main>"}}
+                ]}
+            }),
+            json!({
+                "type": "assistant",
+                "parent_tool_use_id": "toolu_task",
+                "message": {"role": "assistant", "content": [
+                    {"type": "tool_use", "id": "toolu_grep", "name": "Grep", "input": {"pattern": "fn main"}}
+                ]}
+            }),
+            json!({
+                "type": "user",
+                "parent_tool_use_id": "toolu_task",
+                "message": {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_grep", "content": "src/main.rs"}
+                ]}
+            }),
+            json!({
+                "type": "stream_event",
+                "event": {"type": "ping"}
+            }),
+            json!({
+                "type": "result",
+                "subtype": "success",
+                "result": "Found it",
+                "duration_ms": 2500,
+                "num_turns": 2,
+                "total_cost_usd": 0.0123,
+                "usage": {"input_tokens": 100, "output_tokens": 50, "cache_read_input_tokens": 10}
+            }),
+        ]
+        .into_iter()
+        .map(|value| serde_json::from_value(value).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_nesting_depths() {
+        assert_eq!(nesting_depths(&conversation()), vec![0, 0, 1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_markdown() {
+        let md = Transcript::new(conversation())
+            .title("Search")
+            .to_markdown();
+        assert!(md.starts_with("# Search\n"));
+        assert!(md.contains("_Started session `sess_1` · model `claude-sonnet-4-5`_"));
+        assert!(md.contains("<details>\n<summary>Thinking</summary>\n\nDelegate the search"));
+        assert!(md.contains("<summary>Tool call: <code>Task</code></summary>"));
+        assert!(md.contains("> ### Assistant (subagent)"));
+        assert!(md.contains("> <summary>Tool result</summary>"));
+        assert!(md.contains("> src/main.rs"));
+        assert!(md.contains("**Result:** success · 2 turns · 2.5s · $0.0123"));
+        assert!(md.contains("| Input tokens | 100 |"));
+        assert!(md.contains("| Cache read tokens | 10 |"));
+    }
+
+    #[test]
+    fn test_markdown_fence_longer_than_content_backticks() {
+        assert_eq!(md_fence("a ```b``` c", ""), "````\na ```b``` c\n````\n");
+        assert_eq!(md_fence("plain", "json"), "```json\nplain\n```\n");
+    }
+
+    #[test]
+    fn test_html_escapes_and_nests() {
+        let html = Transcript::new(conversation()).to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Transcript</title>"));
+        assert!(html.contains("find &lt;main&gt;"));
+        assert!(!html.contains("find <main>"));
+        assert!(html.contains("margin-left:20px"));
+        assert!(html.contains("<strong>Result:</strong> success · 2 turns · 2.5s · $0.0123"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+    }
+
+    #[test]
+    fn test_jsonl_drops_transport_messages() {
+        let jsonl = Transcript::new(conversation()).to_jsonl().unwrap();
+        let lines: Vec<Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines.iter().all(|line| line["type"] != "stream_event"));
+        assert_eq!(lines[2]["parent_tool_use_id"], "toolu_task");
+    }
+
+    #[test]
+    fn test_result_error_footer() {
+        let result: Message = serde_json::from_value(json!({
+            "type": "result",
+            "subtype": "error_max_turns",
+            "num_turns": 10,
+            "errors": ["Reached maximum number of turns"]
+        }))
+        .unwrap();
+        let md = Transcript::new(vec![result]).to_markdown();
+        assert!(md.contains("**Result:** error_max_turns · 10 turns"));
+        assert!(md.contains("> **Error:** Reached maximum number of turns"));
+    }
+
+    #[tokio::test]
+    async fn test_from_stream() {
+        let messages: Vec<Result<Message, ClawError>> =
+            conversation().into_iter().map(Ok).collect();
+        let transcript = Transcript::from_stream(tokio_stream::iter(messages))
+            .await
+            .unwrap();
+        assert_eq!(transcript.messages().len(), 6);
+
+        let failing = tokio_stream::iter(vec![Err(ClawError::Connection("closed".to_string()))]);
+        assert!(Transcript::from_stream(failing).await.is_err());
+    }
+
+    #[test]
+    fn test_render_dispatch() {
+        let transcript = Transcript::from(conversation());
+        assert_eq!(
+            transcript.render(TranscriptFormat::Markdown).unwrap(),
+            transcript.to_markdown()
+        );
+        assert_eq!(TranscriptFormat::Jsonl.extension(), "jsonl");
+    }
+}
@@ -10,6 +10,7 @@
 //! - Process lifecycle management (spawning, monitoring, shutdown)
 //! - Bidirectional communication (stdin writes, stdout reads)
 //! - NDJSON message framing and parsing
+//! - Bounded message delivery with backpressure (see [`ChannelConfig`])
 //! - Error handling and recovery
 //!
 //! # Default Implementation
@@ -57,8 +58,10 @@
 use async_trait::async_trait;
 use serde_json::Value;
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
 
 use crate::error::ClawError;
+use crate::options::{ChannelConfig, OverflowPolicy};
 
 mod discovery;
 mod subprocess;
@@ -66,6 +69,63 @@ mod subprocess;
 pub use discovery::CliDiscovery;
 pub use subprocess::SubprocessCLITransport;
 
+/// Bounded receiver for raw messages read by a transport
+pub type MessageReceiver = mpsc::Receiver<Result<Value, ClawError>>;
+
+/// Sending half of a [`MessageReceiver`]
+pub type MessageSender = mpsc::Sender<Result<Value, ClawError>>;
+
+/// Create a bounded message channel sized by `config` (capacity at least 1)
+pub fn message_channel(config: &ChannelConfig) -> (MessageSender, MessageReceiver) {
+    mpsc::channel(config.capacity.max(1))
+}
+
+/// Outcome of [`deliver`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Delivery {
+    /// The item was queued
+    Sent,
+    /// The channel was full and the item was a partial message, so it was dropped
+    Dropped,
+    /// The channel was full under [`OverflowPolicy::Error`]; the item was not queued
+    Overflow,
+    /// The receiver is gone
+    Closed,
+}
+
+/// Queue `item` on a bounded channel according to `policy`
+///
+/// Blocks (asynchronously) while the channel is full unless the policy allows
+/// the item to be dropped or reported as an overflow.
+pub(crate) async fn deliver(
+    tx: &MessageSender,
+    item: Result<Value, ClawError>,
+    policy: OverflowPolicy,
+) -> Delivery {
+    let may_skip = match policy {
+        OverflowPolicy::Block => false,
+        OverflowPolicy::DropPartial => matches!(&item, Ok(value) if is_partial_message(value)),
+        OverflowPolicy::Error => true,
+    };
+    if !may_skip {
+        return match tx.send(item).await {
+            Ok(()) => Delivery::Sent,
+            Err(_) => Delivery::Closed,
+        };
+    }
+    match tx.try_send(item) {
+        Ok(()) => Delivery::Sent,
+        Err(TrySendError::Full(_)) if policy == OverflowPolicy::DropPartial => Delivery::Dropped,
+        Err(TrySendError::Full(_)) => Delivery::Overflow,
+        Err(TrySendError::Closed(_)) => Delivery::Closed,
+    }
+}
+
+/// Whether a raw message is a partial streaming event that may be dropped
+pub(crate) fn is_partial_message(value: &Value) -> bool {
+    value.get("type").and_then(Value::as_str) == Some("stream_event")
+}
+
 /// Abstract transport for communicating with Claude Code CLI.
 ///
 /// The default implementation ([`SubprocessCLITransport`]) spawns a subprocess,
@@ -85,9 +145,13 @@ pub use subprocess::SubprocessCLITransport;
 ///
 /// # Message Channel
 ///
-/// [`messages()`](Transport::messages) returns an [`UnboundedReceiver`](mpsc::UnboundedReceiver)
-/// for incoming NDJSON messages. This method can only be called **once** per connection -
+/// [`messages()`](Transport::messages) returns a bounded [`MessageReceiver`] for
+/// incoming NDJSON messages. This method can only be called **once** per connection -
 /// subsequent calls will panic. Store the receiver and pass it to consumers.
+///
+/// Implementations should size the channel from a [`ChannelConfig`] (see
+/// [`message_channel()`]) and honor its [`OverflowPolicy`] when the consumer
+/// falls behind, so memory use stays bounded.
 #[async_trait]
 pub trait Transport: Send + Sync {
     /// Establish the connection (spawn process, open socket, etc.)
@@ -130,15 +194,16 @@ pub trait Transport: Send + Sync {
     ///
     /// # Returns
     ///
-    /// An [`UnboundedReceiver`](mpsc::UnboundedReceiver) that yields:
+    /// A bounded [`MessageReceiver`] that yields:
     /// - `Ok(Value)` for successfully parsed NDJSON messages
     /// - `Err(ClawError::JsonDecode)` for malformed JSON
+    /// - `Err(ClawError::ChannelOverflow)` if the channel overflowed under [`OverflowPolicy::Error`]
     /// - Channel closes when the process exits or stdout is closed
     ///
     /// # Panics
     ///
     /// Panics if called more than once per connection.
-    fn messages(&self) -> mpsc::UnboundedReceiver<Result<Value, ClawError>>;
+    fn messages(&self) -> MessageReceiver;
 
     /// Signal end of input (close stdin)
     ///
@@ -173,3 +238,57 @@ pub trait Transport: Send + Sync {
     /// - Messages can be sent and received
     fn is_ready(&self) -> bool;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(capacity: usize, overflow: OverflowPolicy) -> ChannelConfig {
+        ChannelConfig { capacity, overflow }
+    }
+
+    #[tokio::test]
+    async fn test_deliver_drop_partial_only_drops_stream_events() {
+        let (tx, mut rx) = message_channel(&config(1, OverflowPolicy::DropPartial));
+        let policy = OverflowPolicy::DropPartial;
+
+        assert_eq!(
+            deliver(&tx, Ok(json!({"type": "stream_event"})), policy).await,
+            Delivery::Sent
+        );
+        assert_eq!(
+            deliver(&tx, Ok(json!({"type": "stream_event"})), policy).await,
+            Delivery::Dropped
+        );
+
+        // A full message waits for space instead of being dropped
+        let full =
+            tokio::spawn(
+                async move { deliver(&tx, Ok(json!({"type": "assistant"})), policy).await },
+            );
+        assert_eq!(rx.recv().await.unwrap().unwrap()["type"], "stream_event");
+        assert_eq!(full.await.unwrap(), Delivery::Sent);
+        assert_eq!(rx.recv().await.unwrap().unwrap()["type"], "assistant");
+    }
+
+    #[tokio::test]
+    async fn test_deliver_error_policy_reports_overflow() {
+        let (tx, rx) = message_channel(&config(1, OverflowPolicy::Error));
+        let policy = OverflowPolicy::Error;
+
+        assert_eq!(deliver(&tx, Ok(json!({})), policy).await, Delivery::Sent);
+        assert_eq!(
+            deliver(&tx, Ok(json!({})), policy).await,
+            Delivery::Overflow
+        );
+        drop(rx);
+        assert_eq!(deliver(&tx, Ok(json!({})), policy).await, Delivery::Closed);
+    }
+
+    #[test]
+    fn test_message_channel_clamps_capacity() {
+        let (tx, _rx) = message_channel(&config(0, OverflowPolicy::Block));
+        assert_eq!(tx.max_capacity(), 1);
+    }
+}
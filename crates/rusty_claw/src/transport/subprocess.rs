@@ -14,15 +14,15 @@ use async_trait::async_trait;
 use serde_json::Value;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStdin, Command};
-use tokio::sync::{Mutex, mpsc};
+use tokio::sync::Mutex;
 
 use tracing::{debug, error, trace, warn};
 
 use crate::error::ClawError;
-use crate::transport::Transport;
-
-/// Type alias for the message receiver channel
-type MessageReceiver = mpsc::UnboundedReceiver<Result<Value, ClawError>>;
+use crate::options::ChannelConfig;
+use crate::transport::{
+    Delivery, MessageReceiver, MessageSender, Transport, deliver, message_channel,
+};
 
 /// Transport implementation that spawns Claude CLI as a subprocess
 ///
@@ -92,6 +92,9 @@ pub struct SubprocessCLITransport {
 
     /// Optional buffer size limit for stdout reads (currently informational)
     max_buffer_size: Option<usize>,
+
+    /// Capacity and overflow policy of the stdout message channel
+    channel: ChannelConfig,
 }
 
 impl SubprocessCLITransport {
@@ -135,6 +138,7 @@ impl SubprocessCLITransport {
             stderr_buffer: Arc::new(Mutex::new(String::new())),
             stderr_callback: None,
             max_buffer_size: None,
+            channel: ChannelConfig::default(),
         }
     }
 
@@ -162,15 +166,26 @@ impl SubprocessCLITransport {
         self.max_buffer_size = Some(size);
     }
 
+    /// Set the capacity and overflow policy of the stdout message channel
+    ///
+    /// Takes effect on the next [`connect()`](Transport::connect). With the default
+    /// [`OverflowPolicy::Block`](crate::options::OverflowPolicy::Block), a slow
+    /// consumer pauses the stdout reader instead of growing memory.
+    pub fn set_channel_config(&mut self, config: ChannelConfig) {
+        self.channel = config;
+    }
+
     /// Spawn background task to read stdout and parse NDJSON messages
     fn spawn_reader_task(
         stdout: tokio::process::ChildStdout,
-        tx: mpsc::UnboundedSender<Result<Value, ClawError>>,
+        tx: MessageSender,
         connected: Arc<AtomicBool>,
+        channel: ChannelConfig,
     ) {
         tokio::spawn(async move {
             let reader = BufReader::new(stdout);
             let mut lines = reader.lines();
+            let mut dropped: u64 = 0;
 
             debug!("Started stdout reader task");
 
@@ -183,19 +198,36 @@ impl SubprocessCLITransport {
                 }
 
                 // Parse JSON
-                match serde_json::from_str::<Value>(&line) {
-                    Ok(value) => {
-                        if tx.send(Ok(value)).is_err() {
-                            debug!("Message receiver dropped, stopping reader task");
-                            break;
+                let item = serde_json::from_str::<Value>(&line).map_err(|e| {
+                    error!("Failed to parse JSON line '{}': {}", line, e);
+                    ClawError::JsonDecode(e)
+                });
+
+                // Bounded send: may wait for the consumer, which in turn stops
+                // reading stdout and lets the pipe apply backpressure to the CLI
+                match deliver(&tx, item, channel.overflow).await {
+                    Delivery::Sent => {}
+                    Delivery::Dropped => {
+                        dropped += 1;
+                        if dropped == 1 || dropped.is_power_of_two() {
+                            warn!(dropped, "Message channel full, dropping partial messages");
                         }
                     }
-                    Err(e) => {
-                        error!("Failed to parse JSON line '{}': {}", line, e);
-                        if tx.send(Err(ClawError::JsonDecode(e))).is_err() {
-                            debug!("Message receiver dropped, stopping reader task");
-                            break;
-                        }
+                    Delivery::Overflow => {
+                        error!(
+                            capacity = channel.capacity,
+                            "Message channel full, stopping reader task"
+                        );
+                        let _ = tx
+                            .send(Err(ClawError::ChannelOverflow {
+                                capacity: channel.capacity,
+                            }))
+                            .await;
+                        break;
+                    }
+                    Delivery::Closed => {
+                        debug!("Message receiver dropped, stopping reader task");
+                        break;
                     }
                 }
             }
@@ -407,15 +439,15 @@ impl Transport for SubprocessCLITransport {
             .take()
             .ok_or_else(|| ClawError::Connection("failed to capture stderr".to_string()))?;
 
-        // Set up message channel
-        let (tx, rx) = mpsc::unbounded_channel();
+        // Set up bounded message channel
+        let (tx, rx) = message_channel(&self.channel);
         *self.messages_rx.lock().unwrap() = Some(rx);
 
         // Store stdin
         *self.stdin.lock().await = Some(stdin);
 
         // Spawn background tasks
-        Self::spawn_reader_task(stdout, tx, self.connected.clone());
+        Self::spawn_reader_task(stdout, tx, self.connected.clone(), self.channel);
         Self::spawn_stderr_task(
             stderr,
             self.stderr_buffer.clone(),
@@ -125,7 +125,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match result {
             Ok(Message::Assistant(msg)) => {
                 for block in &msg.message.content {
-                    if let ContentBlock::Text { text } = block {
+                    if let ContentBlock::Text { text, .. } = block {
                         print!("{}", text);
                         io::stdout().flush()?;
                     }
@@ -119,7 +119,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(Message::Assistant(msg)) => {
                 for block in &msg.message.content {
                     match block {
-                        ContentBlock::Text { text } => {
+                        ContentBlock::Text { text, .. } => {
                             print!("{}", text);
                             io::stdout().flush()?;
                         }
@@ -62,7 +62,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(Message::Assistant(msg)) => {
                 message_count += 1;
                 for block in &msg.message.content {
-                    if let ContentBlock::Text { text } = block {
+                    if let ContentBlock::Text { text, .. } = block {
                         print!("{}", text);
                         io::stdout().flush()?;
                     }
@@ -97,7 +97,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match result {
             Ok(Message::Assistant(msg)) => {
                 for block in &msg.message.content {
-                    if let ContentBlock::Text { text } = block {
+                    if let ContentBlock::Text { text, .. } = block {
                         print!("{}", text);
                         io::stdout().flush()?;
                     }
@@ -82,7 +82,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(Message::Assistant(msg)) => {
                 for block in &msg.message.content {
                     match block {
-                        ContentBlock::Text { text } => {
+                        ContentBlock::Text { text, .. } => {
                             print!("{}", text);
                             io::stdout().flush()?;
                         }
@@ -99,6 +99,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         ContentBlock::Thinking { thinking, .. } => {
                             println!("[thinking: {}...]", &thinking[..thinking.len().min(50)]);
                         }
+                        other => {
+                            println!("[{} block]", other.block_type().unwrap_or("unknown"));
+                        }
                     }
                 }
             }
@@ -42,10 +42,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("--- MCP Status ---\n");
     match client.mcp_status().await {
         Ok(status) => {
-            println!(
-                "MCP status: {}",
-                serde_json::to_string_pretty(&status).unwrap()
-            );
+            if status.mcp_servers.is_empty() {
+                println!("No MCP servers configured");
+            }
+            for server in &status.mcp_servers {
+                println!(
+                    "  {} — {:?} ({} tools)",
+                    server.name,
+                    server.status,
+                    server.tools.len()
+                );
+                if let Some(error) = &server.error {
+                    println!("    error: {}", error);
+                }
+            }
         }
         Err(e) => {
             // mcp_status may not be supported in all CLI versions
@@ -73,7 +83,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match result {
             Ok(Message::Assistant(msg)) => {
                 for block in &msg.message.content {
-                    if let ContentBlock::Text { text } = block {
+                    if let ContentBlock::Text { text, .. } = block {
                         print!("{}", text);
                         io::stdout().flush()?;
                         char_count += text.len();
@@ -62,7 +62,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 // messages, each containing incremental text. Without it, you'd
                 // see one large message at the end of each turn.
                 for block in &msg.message.content {
-                    if let ContentBlock::Text { text } = block {
+                    if let ContentBlock::Text { text, .. } = block {
                         print!("{}", text);
                         io::stdout().flush()?;
                     }
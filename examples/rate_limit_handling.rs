@@ -1,7 +1,8 @@
 //! Rate limit handling — respond to rate limits and handle SDK errors.
 //!
 //! This example demonstrates:
-//! - `Message::RateLimitEvent` — detecting rate limits in the message stream
+//! - `Message::RateLimitEvent` — typed rate limit status in the message stream
+//! - `RateLimitPolicy` — waiting for a rejected limit to reset before the next turn
 //! - `ClawError` variants — pattern matching on all error types
 //! - Graceful error recovery strategies
 //!
@@ -45,6 +46,9 @@ fn handle_error(err: &ClawError) {
             eprintln!("[ERROR] Control timeout waiting for: {}", subtype);
             eprintln!("  Fix: Increase timeout or check for deadlocks.");
         }
+        ClawError::ControlCancelled { subtype } => {
+            eprintln!("[ERROR] Control request cancelled: {}", subtype);
+        }
         ClawError::ControlError(msg) => {
             eprintln!("[ERROR] Control protocol error: {}", msg);
         }
@@ -55,6 +59,28 @@ fn handle_error(err: &ClawError) {
             eprintln!("[ERROR] Tool execution failed: {}", msg);
             eprintln!("  Fix: Check your tool handler implementation.");
         }
+        ClawError::SubscriberLagged { skipped } => {
+            eprintln!("[ERROR] Session subscriber dropped {} messages.", skipped);
+            eprintln!("  Fix: Consume the session stream faster.");
+        }
+        ClawError::StructuredOutput { reason, raw } => {
+            eprintln!("[ERROR] Structured output mismatch: {}", reason);
+            eprintln!("  Raw output: {}", raw);
+        }
+        ClawError::RateLimited { limit, retry_after } => {
+            eprintln!("[ERROR] Rate limit {} reached.", limit);
+            eprintln!(
+                "  Fix: Retry in {}s or raise the RateLimitPolicy max_wait.",
+                retry_after.as_secs()
+            );
+        }
+        ClawError::ChannelOverflow { capacity } => {
+            eprintln!(
+                "[ERROR] Message channel overflowed (capacity {}).",
+                capacity
+            );
+            eprintln!("  Fix: Raise channel_capacity or use OverflowPolicy::Block.");
+        }
     }
 }
 
@@ -88,6 +114,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         },
         ClawError::ControlError("permission denied".to_string()),
         ClawError::ToolExecution("handler panicked".to_string()),
+        ClawError::RateLimited {
+            limit: "five_hour".to_string(),
+            retry_after: std::time::Duration::from_secs(1800),
+        },
     ];
 
     for err in &errors {
@@ -102,6 +132,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .max_turns(3)
         .model("claude-haiku-4-5")
         .permission_mode(PermissionMode::AcceptEdits)
+        // Wait up to 5 minutes for a rejected limit to reset before sending a turn
+        .rate_limit_policy(RateLimitPolicy::wait(std::time::Duration::from_secs(300)))
         .build();
 
     let mut client = ClaudeClient::new(options)?;
@@ -112,14 +144,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     while let Some(result) = stream.next().await {
         match result {
             Ok(Message::RateLimitEvent(event)) => {
-                // Rate limit events tell you about API throttling.
-                // You can use these to implement backoff or notify users.
-                println!("[RATE LIMIT] {:?}", event);
-                println!("  Consider implementing exponential backoff.\n");
+                // Rate limit events report how close the session is to its limits.
+                // The client remembers the latest one for its RateLimitPolicy.
+                let info = &event.rate_limit_info;
+                println!(
+                    "[RATE LIMIT] {:?} limit: {:?}, utilization: {:?}",
+                    info.rate_limit_type, info.status, info.utilization
+                );
+                if let Some(retry_after) = info.retry_after() {
+                    println!("  Blocked for another {}s.\n", retry_after.as_secs());
+                }
             }
             Ok(Message::Assistant(msg)) => {
                 for block in &msg.message.content {
-                    if let ContentBlock::Text { text } = block {
+                    if let ContentBlock::Text { text, .. } = block {
                         print!("{}", text);
                         io::stdout().flush()?;
                     }
@@ -80,7 +80,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(Message::Assistant(msg)) => {
                 for block in &msg.message.content {
                     match block {
-                        ContentBlock::Text { text } => {
+                        ContentBlock::Text { text, .. } => {
                             print!("{}", text);
                             io::stdout().flush()?;
                         }
@@ -96,6 +96,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             print!(".");
                             io::stdout().flush()?;
                         }
+                        other => {
+                            println!("\n[{} block]", other.block_type().unwrap_or("unknown"));
+                        }
                     }
                 }
             }
@@ -4,7 +4,8 @@
 //! - `.allowed_tools()` / `.disallowed_tools()` — static allow/deny lists
 //! - `CanUseToolHandler` trait — programmatic runtime permission checks
 //! - `DefaultPermissionHandler` — pre-built handler with builder pattern
-//! - `PermissionDecision` — rich result type with input mutation support
+//! - `PermissionDecision` — rich result type with input mutation and deny messages
+//! - `ToolPermissionContext` — CLI suggestions for "always allow" rule updates
 //!
 //! ## Run
 //! ```sh
@@ -27,6 +28,7 @@ impl CanUseToolHandler for ReadOnlyPermissionHandler {
         &self,
         tool_name: &str,
         tool_input: &serde_json::Value,
+        _context: &ToolPermissionContext,
     ) -> Result<PermissionDecision, ClawError> {
         // Allow read-only tools
         let read_tools = ["Read", "Glob", "Grep", "LSP"];
@@ -34,6 +36,7 @@ impl CanUseToolHandler for ReadOnlyPermissionHandler {
             println!("  [Permission] ALLOW: {} (read-only tool)", tool_name);
             return Ok(PermissionDecision::Allow {
                 updated_input: None,
+                updated_permissions: vec![],
             });
         }
 
@@ -46,15 +49,21 @@ impl CanUseToolHandler for ReadOnlyPermissionHandler {
                 println!("  [Permission] ALLOW: Bash (safe command: {})", cmd);
                 return Ok(PermissionDecision::Allow {
                     updated_input: None,
+                    updated_permissions: vec![],
                 });
             }
             println!("  [Permission] DENY:  Bash (unsafe command: {})", cmd);
-            return Ok(PermissionDecision::Deny { interrupt: false });
+            return Ok(PermissionDecision::deny(
+                "Commands that move, delete, or overwrite files are not allowed",
+            ));
         }
 
         // Deny everything else
         println!("  [Permission] DENY:  {} (not in allowlist)", tool_name);
-        Ok(PermissionDecision::Deny { interrupt: false })
+        Ok(PermissionDecision::deny(format!(
+            "{} is not available in this read-only session",
+            tool_name
+        )))
     }
 }
 
@@ -68,6 +77,7 @@ impl CanUseToolHandler for SanitizingHandler {
         &self,
         tool_name: &str,
         tool_input: &serde_json::Value,
+        _context: &ToolPermissionContext,
     ) -> Result<PermissionDecision, ClawError> {
         if tool_name == "Bash"
             && let Some(cmd) = tool_input.get("command").and_then(|v| v.as_str())
@@ -78,14 +88,40 @@ impl CanUseToolHandler for SanitizingHandler {
             println!("  [Sanitize] Replacing dangerous command with safe echo");
             return Ok(PermissionDecision::Allow {
                 updated_input: Some(sanitized),
+                updated_permissions: vec![],
             });
         }
         Ok(PermissionDecision::Allow {
             updated_input: None,
+            updated_permissions: vec![],
         })
     }
 }
 
+/// Handler that allows `npm test` and accepts the CLI's suggestions, so the
+/// CLI stops asking for the rest of the session.
+#[derive(Debug)]
+struct AlwaysAllowHandler;
+
+#[async_trait]
+impl CanUseToolHandler for AlwaysAllowHandler {
+    async fn can_use_tool(
+        &self,
+        tool_name: &str,
+        tool_input: &serde_json::Value,
+        context: &ToolPermissionContext,
+    ) -> Result<PermissionDecision, ClawError> {
+        let command = tool_input.get("command").and_then(|v| v.as_str());
+        if tool_name == "Bash" && command == Some("npm test") {
+            return Ok(PermissionDecision::Allow {
+                updated_input: None,
+                updated_permissions: context.suggestions.clone(),
+            });
+        }
+        Ok(PermissionDecision::deny("Only `npm test` may run"))
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt()
@@ -133,7 +169,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ];
 
     for (tool, input, expected_allow) in &test_cases {
-        let result = default_handler.can_use_tool(tool, input).await?;
+        let result = default_handler
+            .can_use_tool(tool, input, &ToolPermissionContext::default())
+            .await?;
         let allowed = result.is_allowed();
         println!(
             "  DefaultPermissionHandler.can_use_tool({:?}) = {} (expected: {})",
@@ -158,7 +196,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ];
 
     for (tool, input) in &test_cases {
-        let result = handler.can_use_tool(tool, input).await?;
+        let result = handler
+            .can_use_tool(tool, input, &ToolPermissionContext::default())
+            .await?;
         println!(
             "    {} {}\n",
             tool,
@@ -170,30 +210,57 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
-    // --- 4. Input mutation (PermissionDecision::Allow { updated_input }) ---
+    // --- 4. Input mutation (PermissionDecision::Allow { updated_input, .. }) ---
     println!("=== Input Mutation with PermissionDecision ===\n");
 
     let sanitizer = SanitizingHandler;
     let result = sanitizer
-        .can_use_tool("Bash", &json!({"command": "run dangerous script"}))
+        .can_use_tool(
+            "Bash",
+            &json!({"command": "run dangerous script"}),
+            &ToolPermissionContext::default(),
+        )
         .await?;
     match &result {
         PermissionDecision::Allow {
             updated_input: Some(input),
+            ..
         } => {
             println!("  Sanitized input: {}", input);
         }
         PermissionDecision::Allow {
             updated_input: None,
+            ..
         } => {
             println!("  Allowed with original input");
         }
-        PermissionDecision::Deny { interrupt } => {
-            println!("  Denied (interrupt={})", interrupt);
+        PermissionDecision::Deny {
+            message, interrupt, ..
+        } => {
+            println!("  Denied: {} (interrupt={})", message, interrupt);
         }
     }
 
-    // --- 5. Register with client via options ---
+    // --- 5. "Always allow" via permission suggestions ---
+    println!("\n=== Always Allow with ToolPermissionContext ===\n");
+
+    let context = ToolPermissionContext {
+        suggestions: vec![PermissionUpdate::allow_in_session(
+            PermissionRuleValue::new("Bash").with_content("npm test"),
+        )],
+        tool_use_id: Some("toolu_01".to_string()),
+        ..Default::default()
+    };
+    let result = AlwaysAllowHandler
+        .can_use_tool("Bash", &json!({"command": "npm test"}), &context)
+        .await?;
+    println!(
+        "  Allowed with {} rule update(s): {:?}",
+        result.updated_permissions().len(),
+        result.updated_permissions()
+    );
+
+    // --- 6. Register with client via options ---
     println!("\n=== Register Handler via ClaudeAgentOptions ===\n");
 
     let handler = ReadOnlyPermissionHandler;
@@ -206,7 +273,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Handler set in options. It will be registered before connect().");
     let _client = ClaudeClient::new(options)?;
 
-    // --- 6. Register with client directly ---
+    // --- 7. Register with client directly ---
     println!("\n=== Register Handler Directly with Client ===\n");
 
     let options2 = ClaudeAgentOptions::builder()
@@ -222,7 +289,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("the handler for every tool use request.\n");
 
     println!(
-        "Done — demonstrated static lists, DefaultPermissionHandler, custom handler, and input mutation."
+        "Done — demonstrated static lists, DefaultPermissionHandler, custom handler, input mutation, and rule updates."
     );
     Ok(())
 }
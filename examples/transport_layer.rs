@@ -90,7 +90,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("The Transport trait defines the subprocess lifecycle:");
     println!("  1. transport.connect()    — spawn CLI, establish pipes");
     println!("  2. transport.write(bytes) — send JSON messages to stdin");
-    println!("  3. transport.messages()   — get mpsc::UnboundedReceiver for stdout");
+    println!("  3. transport.messages()   — get bounded MessageReceiver for stdout");
     println!("  4. transport.end_input()  — close stdin (signal no more input)");
     println!("  5. transport.close()      — graceful shutdown (SIGTERM → SIGKILL)");
     println!("  6. transport.is_ready()   — check if connected and alive");